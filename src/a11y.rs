@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use log::warn;
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+
+use crate::output::OutputBackend;
+
+/// Wraps another backend and audits every HTML page written through it
+/// against a subset of the WCAG 2.1 AA rules that can be checked on the
+/// markup alone, remembering the issues found per page.
+pub struct A11yCheckOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    issues: Mutex<Vec<(PathBuf, Vec<String>)>>,
+}
+
+impl<'a> A11yCheckOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        A11yCheckOutput {
+            inner,
+            issues: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Prints the issues found so far and returns how many there were.
+    pub fn report(&self) -> usize {
+        let issues = self.issues.lock().unwrap();
+
+        for (path, page_issues) in issues.iter() {
+            warn!("Accessibility issues in {:?}:", path);
+            for issue in page_issues {
+                warn!("  {}", issue);
+            }
+        }
+
+        issues
+            .iter()
+            .map(|(_, page_issues)| page_issues.len())
+            .sum()
+    }
+}
+
+impl OutputBackend for A11yCheckOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if relative_path.extension().and_then(|s| s.to_str()) == Some("html") {
+            let issues = audit(&String::from_utf8_lossy(contents));
+            if !issues.is_empty() {
+                self.issues
+                    .lock()
+                    .unwrap()
+                    .push((relative_path.to_path_buf(), issues));
+            }
+        }
+
+        self.inner.write(relative_path, contents)
+    }
+}
+
+#[derive(Default)]
+struct Audit {
+    issues: Vec<String>,
+    ids: HashSet<String>,
+    has_lang: bool,
+    title: Option<String>,
+    main_ids: Vec<Option<String>>,
+    fragment_links: Vec<String>,
+    last_heading: u8,
+}
+
+fn audit(html: &str) -> Vec<String> {
+    let dom = parse_document(RcDom::default(), Default::default()).one(html);
+    let mut audit = Audit::default();
+    visit(&dom.document, &mut audit);
+
+    if !audit.has_lang {
+        audit
+            .issues
+            .push("html element has no lang attribute".into());
+    }
+    if audit.title.as_deref().is_none_or(|t| t.trim().is_empty()) {
+        audit.issues.push("page has no title".into());
+    }
+    match audit.main_ids.as_slice() {
+        [] => audit.issues.push("page has no main landmark".into()),
+        [main_id] => {
+            let skippable = main_id.as_ref().is_some_and(|id| {
+                audit
+                    .fragment_links
+                    .iter()
+                    .any(|href| href.strip_prefix('#') == Some(id))
+            });
+            if !skippable {
+                audit
+                    .issues
+                    .push("page has no link to skip to the main content".into());
+            }
+        }
+        _ => audit
+            .issues
+            .push("page has more than one main landmark".into()),
+    }
+
+    audit.issues
+}
+
+fn visit(node: &Handle, audit: &mut Audit) {
+    if let NodeData::Element { name, attrs, .. } = &node.data {
+        let attrs = attrs.borrow();
+        let attr = |attr_name: &str| {
+            attrs
+                .iter()
+                .find(|a| &*a.name.local == attr_name)
+                .map(|a| a.value.to_string())
+        };
+
+        if let Some(id) = attr("id") {
+            if !audit.ids.insert(id.clone()) {
+                audit.issues.push(format!("duplicate id \"{}\"", id));
+            }
+        }
+
+        match &*name.local {
+            "html" => audit.has_lang = attr("lang").is_some_and(|l| !l.trim().is_empty()),
+            "title" => audit.title = Some(text_content(node)),
+            "main" => audit.main_ids.push(attr("id")),
+            "th" if attr("scope").is_none() => {
+                audit.issues.push(format!(
+                    "table header \"{}\" has no scope",
+                    text_content(node).trim()
+                ));
+            }
+            "a" => {
+                if let Some(href) = attr("href") {
+                    let named = attr("aria-label").is_some_and(|l| !l.trim().is_empty())
+                        || !text_content(node).trim().is_empty();
+                    if !named {
+                        audit
+                            .issues
+                            .push(format!("link to \"{}\" has no accessible name", href));
+                    }
+                    if href.starts_with('#') {
+                        audit.fragment_links.push(href);
+                    }
+                }
+            }
+            "img" if attr("alt").is_none() => {
+                audit.issues.push("image has no alt text".into());
+            }
+            heading @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                let level = heading.as_bytes()[1] - b'0';
+                if level > audit.last_heading + 1 {
+                    audit.issues.push(format!(
+                        "heading level skipped: h{} follows h{}",
+                        level, audit.last_heading
+                    ));
+                }
+                audit.last_heading = level;
+            }
+            _ => {}
+        }
+    }
+
+    for child in node.children.borrow().iter() {
+        visit(child, audit);
+    }
+}
+
+fn text_content(node: &Handle) -> String {
+    let mut text = String::new();
+    if let NodeData::Text { contents } = &node.data {
+        text.push_str(&contents.borrow());
+    }
+    for child in node.children.borrow().iter() {
+        text.push_str(&text_content(child));
+    }
+    text
+}
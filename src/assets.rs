@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha384};
+use walkdir::WalkDir;
+
+use crate::output::OutputBackend;
+
+/// Reference to an asset written into the generated site. `href` is relative
+/// to the site root; templates prefix it with `root` to reach it from nested
+/// pages.
+#[derive(Serialize)]
+pub struct AssetLink {
+    href: String,
+    integrity: String,
+}
+
+/// Length of the content hash in hashed asset filenames.
+const HASH_LENGTH: usize = 16;
+
+/// Writes an asset under `href`, or with `hashed` under `href` with a hash of
+/// its contents before the extension, so that a changed asset gets a new URL
+/// and can be cached indefinitely.
+pub fn write_asset(
+    output: &dyn OutputBackend,
+    href: &str,
+    contents: &[u8],
+    hashed: bool,
+) -> Result<AssetLink, Box<dyn std::error::Error>> {
+    let href = if hashed {
+        hashed_name(href, contents)
+    } else {
+        href.to_string()
+    };
+    output.write(Path::new(&href), contents)?;
+
+    Ok(AssetLink {
+        href,
+        integrity: integrity(contents),
+    })
+}
+
+fn hashed_name(href: &str, contents: &[u8]) -> String {
+    let digest = Sha256::digest(contents);
+    let hash: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let hash = &hash[..HASH_LENGTH];
+    match href.rsplit_once('.') {
+        Some((stem, extension)) => format!("{}.{}.{}", stem, hash, extension),
+        None => format!("{}.{}", href, hash),
+    }
+}
+
+/// Copies every file under `dir` to the same path relative to the site
+/// root, for templates to reference. Returns the number of files copied.
+pub fn copy_assets(
+    output: &dyn OutputBackend,
+    dir: &Path,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut copied = 0;
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            output.write(entry.path().strip_prefix(dir)?, &fs::read(entry.path())?)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Whether `path` names an asset written with a content hash.
+pub fn is_hashed(path: &Path) -> bool {
+    let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    stem.rsplit_once('.').is_some_and(|(_, hash)| {
+        hash.len() == HASH_LENGTH && hash.bytes().all(|b| b.is_ascii_hexdigit())
+    })
+}
+
+/// Subresource integrity value for the `integrity` attribute.
+pub fn integrity(contents: &[u8]) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(contents)))
+}
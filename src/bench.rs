@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use log::{info, LevelFilter};
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+
+use crate::output::OutputBackend;
+use crate::parser::{generate_index, ConvertOptions, IndexEntry};
+use crate::pipeline::{convert_files, Jobs};
+
+/// Discards everything written to it, so that benchmarks measure the
+/// converter rather than the disk.
+struct NullOutput;
+
+impl OutputBackend for NullOutput {
+    fn write(&self, _: &Path, _: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+}
+
+/// Throughput of a build, as saved to and compared with a baseline file.
+#[derive(Serialize, Deserialize)]
+pub struct Measurement {
+    pub files: usize,
+    pub triples: usize,
+    pub triples_per_sec: f64,
+    pub files_per_sec: f64,
+}
+
+impl Measurement {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Change in triple throughput relative to `baseline`, in percent.
+    pub fn change_from(&self, baseline: &Measurement) -> f64 {
+        (self.triples_per_sec / baseline.triples_per_sec - 1.0) * 100.0
+    }
+}
+
+/// Builds the site for `inputs` `iterations` times and returns the
+/// throughput of the median build.
+pub fn bench(
+    inputs: &[PathBuf],
+    input_dir: &str,
+    tera: &Tera,
+    base_context: &Context,
+    iterations: usize,
+) -> Result<Measurement, Box<dyn std::error::Error>> {
+    let options = ConvertOptions::default();
+    let jobs = Jobs::default();
+    let mut durations = Vec::new();
+    let mut files = 0;
+    let mut triples = 0;
+
+    let build = || -> Result<(usize, usize), Box<dyn std::error::Error>> {
+        let mut entries = Vec::new();
+        let mut triples = 0;
+        for (path, result) in convert_files(
+            inputs,
+            input_dir,
+            &NullOutput,
+            tera,
+            base_context,
+            &options,
+            &jobs,
+        ) {
+            let converted = result.map_err(|e| format!("Error converting {:?}: {}", path, e))?;
+            triples += converted.triples;
+            entries.push(IndexEntry::new(
+                converted.path.to_string_lossy().to_string(),
+                path.to_string_lossy().to_string(),
+            ));
+        }
+        generate_index(&NullOutput, &entries, tera, base_context, &options)?;
+        Ok((entries.len(), triples))
+    };
+
+    for iteration in 1..=iterations {
+        // logging every file would be measured along with converting it
+        let level = log::max_level();
+        log::set_max_level(level.min(LevelFilter::Warn));
+        let start = Instant::now();
+        let built = build();
+        let duration = start.elapsed();
+        log::set_max_level(level);
+        (files, triples) = built?;
+
+        info!(
+            "Build {}/{}: {:.3}s",
+            iteration,
+            iterations,
+            duration.as_secs_f64()
+        );
+        durations.push(duration);
+    }
+
+    durations.sort();
+    let median = durations[durations.len() / 2].max(Duration::from_nanos(1));
+
+    Ok(Measurement {
+        files,
+        triples,
+        triples_per_sec: triples as f64 / median.as_secs_f64(),
+        files_per_sec: files as f64 / median.as_secs_f64(),
+    })
+}
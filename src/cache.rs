@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::corpus::Outline;
+use crate::parser::Converted;
+
+/// Name of the build cache, kept at the root of the output directory.
+pub const CACHE_FILE: &str = ".ttl2html-cache.json";
+
+/// What an earlier run produced for each input file, so that files whose
+/// contents did not change need not be converted again.
+pub struct BuildCache {
+    output_dir: PathBuf,
+    settings: String,
+    /// Fingerprint of what the pages know about the other files.
+    corpus: String,
+    previous_corpus: String,
+    previous: BTreeMap<String, CachedFile>,
+    current: BTreeMap<String, CachedFile>,
+    /// Hashes of the inputs as they were when first looked up, by source
+    /// path.
+    hashes: BTreeMap<String, String>,
+    /// Outlines of the inputs looked up or recorded in this run, by source
+    /// path.
+    outlines: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CacheContents {
+    settings: String,
+    corpus: String,
+    files: BTreeMap<String, CachedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+    /// SHA-256 of the input file.
+    hash: String,
+    converted: serde_json::Value,
+    outline: Option<serde_json::Value>,
+}
+
+impl BuildCache {
+    /// Reads the cache of `output_dir`. The cache is ignored when it is
+    /// missing, unreadable or was written with other `settings`, so that
+    /// every file is converted again.
+    pub fn load(output_dir: &Path, settings: String) -> Self {
+        let previous = fs::read(output_dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_slice::<CacheContents>(&contents).ok())
+            .filter(|contents| contents.settings == settings)
+            .unwrap_or_default();
+
+        BuildCache {
+            output_dir: output_dir.to_path_buf(),
+            settings,
+            corpus: String::new(),
+            previous_corpus: previous.corpus,
+            previous: previous.files,
+            current: BTreeMap::new(),
+            hashes: BTreeMap::new(),
+            outlines: BTreeMap::new(),
+        }
+    }
+
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    /// Sets the fingerprint of what the pages know about the other files,
+    /// such as where their subjects are described. Results of an earlier run
+    /// with another fingerprint are not reused.
+    pub fn set_corpus(&mut self, fingerprint: String) {
+        self.corpus = fingerprint;
+    }
+
+    /// The earlier outline of the input at `input_path`, read from `source`
+    /// relative to the input directory, if its contents did not change.
+    pub fn outline(&mut self, input_path: &Path, source: &Path) -> Option<Outline> {
+        let hash = self.hash(input_path, source)?;
+        let cached = self.previous.get(&key(source))?;
+        if cached.hash != hash {
+            return None;
+        }
+        let outline = cached.outline.clone()?;
+        self.outlines.insert(key(source), outline.clone());
+        serde_json::from_value(outline).ok()
+    }
+
+    /// Records the outline of the input read from `source` for the next run.
+    pub fn record_outline(&mut self, source: &Path, outline: &Outline) {
+        if let Ok(outline) = serde_json::to_value(outline) {
+            self.outlines.insert(key(source), outline);
+        }
+    }
+
+    /// The earlier result for the input at `input_path`, read from `source`
+    /// relative to the input directory, if its contents and the corpus did
+    /// not change and every file it wrote is still in the output directory.
+    pub fn reuse(&mut self, input_path: &Path, source: &Path) -> Option<Converted> {
+        let hash = self.hash(input_path, source)?;
+        let cached = self.previous.get(&key(source))?;
+        if cached.hash != hash || self.corpus != self.previous_corpus {
+            return None;
+        }
+        let converted: Converted = serde_json::from_value(cached.converted.clone()).ok()?;
+        converted
+            .outputs
+            .iter()
+            .all(|path| self.output_dir.join(path).is_file())
+            .then_some(converted)
+    }
+
+    /// Records the result for the input read from `source` for the next
+    /// run, with its outline, under the hash it had when it was first looked
+    /// up.
+    pub fn record(
+        &mut self,
+        source: &Path,
+        converted: &Converted,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(hash) = self.hashes.remove(&key(source)) {
+            let converted = serde_json::to_value(converted)?;
+            let outline = self.outlines.remove(&key(source));
+            self.current.insert(
+                key(source),
+                CachedFile {
+                    hash,
+                    converted,
+                    outline,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Hash of the input at `input_path` as it was when first looked up.
+    fn hash(&mut self, input_path: &Path, source: &Path) -> Option<String> {
+        if let Some(hash) = self.hashes.get(&key(source)) {
+            return Some(hash.clone());
+        }
+        let hash = content_hash(input_path).ok()?;
+        self.hashes.insert(key(source), hash.clone());
+        Some(hash)
+    }
+
+    /// Writes the files recorded in this run, dropping inputs that are gone
+    /// or failed to convert.
+    pub fn save(self) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = CacheContents {
+            settings: self.settings,
+            corpus: self.corpus,
+            files: self.current,
+        };
+        fs::create_dir_all(&self.output_dir)?;
+        fs::write(
+            self.output_dir.join(CACHE_FILE),
+            serde_json::to_vec(&contents)?,
+        )?;
+        Ok(())
+    }
+}
+
+fn key(source: &Path) -> String {
+    source.to_string_lossy().replace('\\', "/")
+}
+
+/// Hex SHA-256 of the file at `path`.
+pub fn content_hash(path: &Path) -> std::io::Result<String> {
+    Ok(format!("{:x}", Sha256::digest(fs::read(path)?)))
+}
+
+/// Hex SHA-256 over `parts`, each length-prefixed so that they cannot run
+/// into one another.
+pub fn fingerprint<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Serializes a map as a list of pairs, for maps whose keys are not strings.
+pub mod pairs {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<K, V, S>(map: &BTreeMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
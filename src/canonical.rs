@@ -0,0 +1,75 @@
+//! Canonical URLs of the generated pages, and the `robots.txt` pointing
+//! crawlers at the site.
+
+use std::path::Path;
+
+use url::Url;
+
+use crate::output::OutputBackend;
+use crate::sitemap::page_url;
+
+/// Wraps another backend and adds a `<link rel="canonical">` with the
+/// public URL of every HTML page written through it, unless the page has
+/// one already. Print views point at the page they were made from.
+pub struct CanonicalOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    base_url: Url,
+}
+
+impl<'a> CanonicalOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend, base_url: Url) -> Self {
+        CanonicalOutput { inner, base_url }
+    }
+}
+
+impl OutputBackend for CanonicalOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let html = std::str::from_utf8(contents).ok();
+        // the link goes on a line of its own ahead of the one closing the head
+        let head_end = html.and_then(|html| {
+            let head_end = html.find("</head>")?;
+            Some(html[..head_end].rfind('\n').map_or(0, |i| i + 1))
+        });
+        let (Some(html), Some(head_end), Some("html")) = (
+            html,
+            head_end,
+            relative_path.extension().and_then(|s| s.to_str()),
+        ) else {
+            return self.inner.write(relative_path, contents);
+        };
+        // pages written through here before, as incremental builds pass
+        // them on again, keep the link they have
+        if html[..head_end].contains("<link rel=\"canonical\"") {
+            return self.inner.write(relative_path, contents);
+        }
+
+        let page = match relative_path
+            .to_str()
+            .and_then(|p| p.strip_suffix(".print.html"))
+        {
+            Some(page) => Path::new(page).with_extension("html"),
+            None => relative_path.to_path_buf(),
+        };
+        let url = page_url(&self.base_url, &page)?;
+        let link = format!(
+            "    <link rel=\"canonical\" href=\"{}\" />\n",
+            url.as_str().replace('&', "&amp;").replace('"', "&quot;")
+        );
+        let html = format!("{}{}{}", &html[..head_end], link, &html[head_end..]);
+        self.inner.write(relative_path, html.as_bytes())
+    }
+}
+
+/// `robots.txt` allowing crawlers everywhere, with the sitemap at `sitemap`
+/// when there is one.
+pub fn robots_txt(sitemap: Option<&Url>) -> String {
+    let mut robots = String::from("User-agent: *\nAllow: /\n");
+    if let Some(sitemap) = sitemap {
+        robots.push_str(&format!("\nSitemap: {}\n", sitemap));
+    }
+    robots
+}
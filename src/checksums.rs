@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+
+use crate::output::{url_path, OutputBackend};
+use crate::stdio::read_input;
+
+/// Manifest of the generated files, written with `--checksums`.
+pub const SUMS_FILE: &str = "SHA256SUMS";
+
+/// Wraps another backend and remembers the SHA-256 of every file written
+/// through it, so a `SHA256SUMS` file can be produced at the end of the run.
+pub struct ChecksumOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    sums: Mutex<Vec<(PathBuf, String)>>,
+}
+
+impl<'a> ChecksumOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        ChecksumOutput {
+            inner,
+            sums: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Writes `SHA256SUMS` (in the format understood by `sha256sum -c`)
+    /// listing every file written so far.
+    pub fn write_sums(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut sums = self.sums.lock().unwrap().clone();
+        sums.sort();
+        sums.dedup_by(|a, b| a.0 == b.0);
+
+        self.inner
+            .write(Path::new(SUMS_FILE), format_sums(&sums).as_bytes())
+    }
+}
+
+impl OutputBackend for ChecksumOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.write(relative_path, contents)?;
+
+        let mut sums = self.sums.lock().unwrap();
+        // a rewritten file replaces its earlier checksum
+        sums.retain(|(path, _)| path != relative_path);
+        sums.push((relative_path.to_path_buf(), sha256_hex(contents)));
+
+        Ok(())
+    }
+}
+
+/// Writes `SHA256SUMS.inputs` with the checksums of the source files, relative
+/// to the input directory.
+pub fn write_input_sums(
+    output: &dyn OutputBackend,
+    input_dir: &str,
+    inputs: &[PathBuf],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut sums = Vec::new();
+    for input in inputs {
+        let relative_path = input.strip_prefix(input_dir)?;
+        sums.push((relative_path.to_path_buf(), sha256_hex(&read_input(input)?)));
+    }
+    sums.sort();
+
+    output.write(
+        Path::new("SHA256SUMS.inputs"),
+        format_sums(&sums).as_bytes(),
+    )
+}
+
+fn format_sums(sums: &[(PathBuf, String)]) -> String {
+    sums.iter()
+        .map(|(path, sum)| format!("{}  {}\n", sum, url_path(path)))
+        .collect()
+}
+
+/// The checksums of a `SHA256SUMS` file by path.
+pub fn parse_sums(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(sum, path)| (path.to_string(), sum.to_string()))
+        .collect()
+}
+
+pub fn sha256_hex(contents: &[u8]) -> String {
+    hex(&Sha256::digest(contents))
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,1649 @@
+//! The `turtle2rdf` command line tool, built on the rest of the crate.
+
+use crate::a11y::A11yCheckOutput;
+use crate::assets::{copy_assets, write_asset};
+use crate::bench::{bench, Measurement};
+use crate::cache::{fingerprint, BuildCache};
+use crate::canonical::{robots_txt, CanonicalOutput};
+use crate::checksums::{write_input_sums, ChecksumOutput};
+use crate::collation::LabelOrder;
+use crate::concordance::write_concordance;
+use crate::config::with_config;
+use crate::conneg::{ContentNegotiation, SERVERS};
+use crate::corpus::{site_links, Corpus};
+use crate::csp::StrictCspOutput;
+use crate::datasets::DatasetStats;
+use crate::deploy::target_from_url;
+use crate::diagnostic::Diagnostic;
+use crate::dry_run::DryRunOutput;
+use crate::error_report::ErrorReport;
+use crate::escape;
+use crate::golden::{self, MemoryOutput};
+use crate::html_check::HtmlCheckOutput;
+use crate::http::RetryPolicy;
+use crate::labels::collisions;
+use crate::link_check::LinkCheckOutput;
+use crate::logging;
+use crate::minify::MinifyOutput;
+use crate::output::{backend_from_arg, url_path, OutputBackend, ThrottledOutput};
+use crate::output_paths::{resolve_collisions, CollisionPolicy};
+use crate::parser::{generate_index, ConvertOptions, ErrorPolicy, IndexEntry};
+use crate::pipeline::{convert_files, outline_files, FileResult, Jobs};
+use crate::prefixes;
+use crate::progress;
+use crate::search::{write_search, write_search_index};
+use crate::serve::{LiveReload, Server};
+use crate::sitemap::{parse_base_url, SitemapOutput};
+use crate::sites::TermsManifest;
+use crate::stats::SiteStats;
+use crate::stdio::{stdin_path, STDIO};
+use crate::summary::{Resource, SiteSummary, SummaryPage};
+use crate::usage::UsageStats;
+use crate::vocab::DISPLAY_LABEL_PREDICATES;
+use crate::walk::InputWalk;
+use crate::watch::watch;
+use crate::{
+    base_context, input_files, templates, templates_from_dir, themed_templates, InputFormat, THEMES,
+};
+
+use chrono::{DateTime, Utc};
+use clap::{Arg, ArgAction, ArgMatches, Command, ValueHint};
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use tera::{Context, Tera};
+use walkdir::WalkDir;
+
+/// Runs the `turtle2rdf` command line tool.
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let command = Command::new("RDF to HTML Converter")
+        .version("0.1.0")
+        .author("Radu Dita <radu@kyrt.tech>")
+        .about("Converts RDF Turtle files to HTML")
+        .args(convert_args())
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .short('v')
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help("Also prints the number of triples and the time taken for every file"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .short('q')
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .conflicts_with("verbose")
+                .help("Prints only warnings and errors"),
+        )
+        // options on the command line replace those of the configuration file
+        .args_override_self(true)
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("deploy")
+                .about("Uploads a generated site to a remote target")
+                .args_override_self(true)
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("OUTPUT_DIR")
+                        .value_hint(ValueHint::DirPath)
+                        .help("Sets the directory of the generated site")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("target")
+                        .short('t')
+                        .long("target")
+                        .value_name("URL")
+                        .help("Sets the deploy target, e.g. ssh://user@host/srv/www")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("identity")
+                        .long("identity")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Sets the SSH private key used to authenticate"),
+                )
+                .arg(
+                    Arg::new("delete")
+                        .long("delete")
+                        .action(ArgAction::SetTrue)
+                        .help("Removes remote files that are no longer generated"),
+                )
+                .arg(
+                    Arg::new("cid-file")
+                        .long("cid-file")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Writes the CID of the site to FILE when deploying to IPFS"),
+                )
+                .arg(config_arg())
+                .args(retry_args()),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Serves a generated site locally for previewing")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("OUTPUT_DIR")
+                        .value_hint(ValueHint::DirPath)
+                        .help("Sets the directory of the generated site")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("address")
+                        .long("address")
+                        .value_name("HOST:PORT")
+                        .default_value("127.0.0.1:8000")
+                        .help("Sets the address to listen on"),
+                )
+                .arg(
+                    Arg::new("cache-control")
+                        .long("cache-control")
+                        .value_name("EXT=VALUE")
+                        .action(ArgAction::Append)
+                        .help("Sends VALUE as the Cache-Control header for files with extension EXT"),
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Measures conversion throughput over a corpus")
+                .arg(
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .value_name("INPUT_DIR")
+                        .value_hint(ValueHint::AnyPath)
+                        .help("Sets the directory of the corpus")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("iterations")
+                        .long("iterations")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(u64).range(1..))
+                        .default_value("5")
+                        .help("Builds the corpus N times and reports the median build"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Compares the results with a baseline saved with --save-baseline"),
+                )
+                .arg(
+                    Arg::new("save-baseline")
+                        .long("save-baseline")
+                        .value_name("FILE")
+                        .value_hint(ValueHint::FilePath)
+                        .help("Saves the results to FILE for later comparisons"),
+                )
+                .arg(
+                    Arg::new("max-regression")
+                        .long("max-regression")
+                        .value_name("PERCENT")
+                        .value_parser(clap::value_parser!(f64))
+                        .requires("baseline")
+                        .help("Fails if triple throughput dropped by more than PERCENT from the baseline"),
+                ),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("Converts the inputs and compares the result with a stored golden output")
+                .args_override_self(true)
+                .args(
+                    convert_args()
+                        .into_iter()
+                        .filter(|arg| {
+                            !["output", "incremental", "watch", "serve"].contains(&arg.get_id().as_str())
+                        }),
+                )
+                .arg(
+                    Arg::new("golden")
+                        .long("golden")
+                        .value_name("DIR")
+                        .value_hint(ValueHint::DirPath)
+                        .help("Sets the directory holding the expected output")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("update")
+                        .long("update")
+                        .action(ArgAction::SetTrue)
+                        .help("Replaces the golden output with the current output instead of comparing"),
+                ),
+        );
+    let args = with_config(std::env::args_os().collect(), &command)?;
+    let matches = command.get_matches_from(args);
+    logging::init(logging::level(
+        matches.get_flag("verbose"),
+        matches.get_flag("quiet"),
+    ));
+
+    match matches.subcommand() {
+        Some(("deploy", deploy_matches)) => deploy(deploy_matches),
+        Some(("serve", serve_matches)) => serve(serve_matches),
+        Some(("bench", bench_matches)) => run_bench(bench_matches),
+        Some(("check", check_matches)) => check(check_matches),
+        _ => convert(&matches),
+    }
+}
+
+fn deploy(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = matches.get_one::<String>("output").unwrap();
+    let target = matches.get_one::<String>("target").unwrap();
+    let identity = matches.get_one::<String>("identity").map(Path::new);
+    let cid_file = matches.get_one::<String>("cid-file").map(Path::new);
+
+    let target = target_from_url(
+        target,
+        identity,
+        matches.get_flag("delete"),
+        cid_file,
+        retry_policy(matches),
+    )?;
+    target.deploy(Path::new(output_dir))
+}
+
+fn serve(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = matches.get_one::<String>("output").unwrap();
+    let address = matches.get_one::<String>("address").unwrap();
+
+    let mut cache_control = HashMap::new();
+    for value in matches
+        .get_many::<String>("cache-control")
+        .unwrap_or_default()
+    {
+        let (extension, header) = value
+            .split_once('=')
+            .ok_or_else(|| format!("Expected EXT=VALUE for --cache-control, got {:?}", value))?;
+        cache_control.insert(
+            extension.trim_start_matches('.').to_string(),
+            header.to_string(),
+        );
+    }
+
+    Server::new(Path::new(output_dir), cache_control).run(address)
+}
+
+fn run_bench(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let input_dir = matches.get_one::<String>("input").unwrap();
+    let iterations = *matches.get_one::<u64>("iterations").unwrap() as usize;
+
+    let measurement = bench(
+        &input_files(input_dir),
+        input_dir,
+        &templates(),
+        &base_context(),
+        iterations,
+    )?;
+    info!(
+        "{} file(s), {} triple(s): {:.0} triples/sec, {:.1} files/sec",
+        measurement.files,
+        measurement.triples,
+        measurement.triples_per_sec,
+        measurement.files_per_sec
+    );
+
+    if let Some(path) = matches.get_one::<String>("save-baseline") {
+        measurement.save(Path::new(path))?;
+    }
+
+    if let Some(path) = matches.get_one::<String>("baseline") {
+        let baseline = Measurement::load(Path::new(path))?;
+        let change = measurement.change_from(&baseline);
+        info!(
+            "Baseline: {:.0} triples/sec, {:.1} files/sec ({:+.1}% triples/sec)",
+            baseline.triples_per_sec, baseline.files_per_sec, change
+        );
+
+        if let Some(max) = matches.get_one::<f64>("max-regression") {
+            if -change > *max {
+                return Err(format!(
+                    "Throughput dropped by {:.1}%, more than the allowed {}%",
+                    -change, max
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn convert(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = matches.get_one::<String>("output").unwrap();
+    let from_stdin = matches.get_one::<String>("input").unwrap() == STDIO;
+    if (from_stdin || output_dir == STDIO)
+        && (matches.get_flag("incremental")
+            || matches.get_flag("watch")
+            || matches.contains_id("serve"))
+    {
+        return Err("--incremental, --watch and --serve cannot read standard input or write to standard output".into());
+    }
+    if output_dir == STDIO {
+        return convert_to_stdout(matches);
+    }
+    if matches.get_flag("dry-run") {
+        let dry_run = DryRunOutput::default();
+        build(matches, &dry_run, None, false, Some(&dry_run))?;
+        return dry_run.report(output_dir);
+    }
+    let backend = backend_from_arg(output_dir, retry_policy(matches))?;
+
+    // watching rebuilds incrementally, so that only changed files are converted
+    let watching = matches.get_flag("watch");
+    let incremental = matches.get_flag("incremental") || watching;
+    let serve_port = matches.get_one::<u16>("serve").copied();
+    if (incremental || serve_port.is_some()) && output_dir.starts_with("s3://") {
+        return Err("--incremental, --watch and --serve need a local output directory".into());
+    }
+    let cache_dir = incremental.then_some(Path::new(output_dir));
+    // pages served while watching reload themselves after each rebuild
+    let live_reload = (watching && serve_port.is_some()).then(LiveReload::default);
+    let result = build(
+        matches,
+        backend.as_ref(),
+        cache_dir,
+        live_reload.is_some(),
+        None,
+    );
+    if !watching {
+        result?;
+        return match serve_port {
+            Some(port) => preview_server(output_dir).run(&format!("127.0.0.1:{}", port)),
+            None => Ok(()),
+        };
+    }
+
+    // a failed build is reported and retried on the next change
+    if let Err(e) = result {
+        error!("Error: {}", e);
+    }
+    if let Some(port) = serve_port {
+        let mut server = preview_server(output_dir);
+        if let Some(live_reload) = &live_reload {
+            server = server.with_live_reload(live_reload.clone());
+        }
+        thread::spawn(move || {
+            if let Err(e) = server.run(&format!("127.0.0.1:{}", port)) {
+                error!("Error serving the site: {}", e);
+            }
+        });
+    }
+    let mut dirs = vec![PathBuf::from(matches.get_one::<String>("input").unwrap())];
+    dirs.extend(matches.get_one::<String>("templates").map(PathBuf::from));
+    dirs.extend(matches.get_one::<String>("assets").map(PathBuf::from));
+    watch(&dirs, Path::new(output_dir), || {
+        match build(
+            matches,
+            backend.as_ref(),
+            cache_dir,
+            live_reload.is_some(),
+            None,
+        ) {
+            Ok(()) => {
+                if let Some(live_reload) = &live_reload {
+                    live_reload.notify();
+                }
+            }
+            Err(e) => error!("Error: {}", e),
+        }
+    })
+}
+
+/// Converts a single input file, or standard input, in memory and writes
+/// its page to standard output. Messages go to standard error instead.
+fn convert_to_stdout(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    logging::to_stderr();
+    let (_, inputs) = select_inputs(matches)?;
+    let [input] = inputs.as_slice() else {
+        return Err("-o - needs a single input file or -i -".into());
+    };
+    let options = ConvertOptions {
+        slugify: matches.get_flag("slugify"),
+        ..Default::default()
+    };
+    let page = options
+        .output_source_path(Path::new(input.file_name().unwrap()))
+        .with_extension("html");
+
+    let rendered = MemoryOutput::default();
+    build(matches, &rendered, None, false, None)?;
+    let files = rendered.into_files();
+    let html = files
+        .get(&page)
+        .ok_or_else(|| format!("No page was written for {:?}", input))?;
+    std::io::stdout().write_all(html)?;
+    Ok(())
+}
+
+/// Server for previewing the site after conversion, with the default cache
+/// headers.
+fn preview_server(output_dir: &str) -> Server {
+    Server::new(Path::new(output_dir), HashMap::new())
+}
+
+/// Renders the golden output check: converts the inputs in memory and
+/// compares the result with the tree in `--golden`, or replaces that tree
+/// with `--update`.
+fn check(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let golden_dir = Path::new(matches.get_one::<String>("golden").unwrap());
+
+    let rendered = MemoryOutput::default();
+    build(matches, &rendered, None, false, None)?;
+    let files = rendered.into_files();
+
+    if matches.get_flag("update") {
+        golden::update(&files, golden_dir)?;
+        info!("Updated {:?} with {} file(s)", golden_dir, files.len());
+        return Ok(());
+    }
+
+    let differences = golden::compare(&files, golden_dir)?;
+    for difference in &differences {
+        warn!("{}", difference);
+    }
+    if !differences.is_empty() {
+        return Err(format!("{} file(s) differ from {:?}", differences.len(), golden_dir).into());
+    }
+    info!("All {} file(s) match {:?}", files.len(), golden_dir);
+
+    Ok(())
+}
+
+/// Converts the inputs that changed since the run recorded in `cache` and
+/// reuses the earlier results of the others. The files of reused inputs are
+/// passed through `output` again so that checks, checksums and the sitemap
+/// still cover the whole site.
+#[allow(clippy::too_many_arguments)]
+fn convert_changed(
+    inputs: &[PathBuf],
+    input_dir: &str,
+    output: &dyn OutputBackend,
+    cache: &mut BuildCache,
+    tera: &Tera,
+    base_context: &Context,
+    options: &ConvertOptions,
+    jobs: &Jobs,
+) -> Result<Vec<FileResult>, Box<dyn std::error::Error>> {
+    let mut reused = Vec::new();
+    for path in inputs {
+        reused.push(cache.reuse(path, path.strip_prefix(input_dir)?));
+    }
+    let changed: Vec<PathBuf> = inputs
+        .iter()
+        .zip(&reused)
+        .filter(|(_, reused)| reused.is_none())
+        .map(|(path, _)| path.clone())
+        .collect();
+    info!(
+        "Reusing {} unchanged file(s), converting {}",
+        inputs.len() - changed.len(),
+        changed.len()
+    );
+    progress::start(changed.len());
+
+    let mut converted = convert_files(
+        &changed,
+        input_dir,
+        output,
+        tera,
+        base_context,
+        options,
+        jobs,
+    )
+    .into_iter()
+    .peekable();
+    let mut results = Vec::new();
+    for (path, reused) in inputs.iter().zip(reused) {
+        match reused {
+            Some(reused) => {
+                for file in &reused.outputs {
+                    let contents = fs::read(cache.output_dir().join(file))?;
+                    output.write(file, &contents)?;
+                }
+                results.push((path.clone(), Ok(reused)));
+            }
+            // a failing file stops the conversion early under fail-fast
+            None => {
+                if let Some(result) = converted.next_if(|(converted, _)| converted == path) {
+                    results.push(result);
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Everything besides the input files that the pages depend on, hashed, so
+/// that changing any of it converts every file again. What the pages know
+/// about each other is left to the corpus fingerprint of the cache.
+fn build_settings(
+    matches: &ArgMatches,
+    base_context: &Context,
+    options: &ConvertOptions,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // options that do not change the pages; assets are copied on every run
+    let ignored = [
+        "assets",
+        "dry-run",
+        "error-report",
+        "input",
+        "verbose",
+        "quiet",
+        "output",
+        "config",
+        "incremental",
+        "watch",
+        "serve",
+        "jobs",
+        "parse-jobs",
+        "render-jobs",
+        "write-jobs",
+        "group-memory",
+    ];
+    let mut ids: Vec<&str> = matches
+        .ids()
+        .map(|id| id.as_str())
+        .filter(|id| !ignored.contains(id))
+        .collect();
+    ids.sort();
+
+    let mut parts = vec![env!("CARGO_PKG_VERSION").as_bytes().to_vec()];
+    for id in ids {
+        let values: Vec<_> = matches.get_raw(id).into_iter().flatten().collect();
+        parts.push(format!("{}={:?}", id, values).into_bytes());
+    }
+    parts.push(base_context.clone().into_json().to_string().into_bytes());
+    parts.push(format!("{:?}", options.prefixes).into_bytes());
+    // pages of other files may be renamed as files are added
+    let mut renamed: Vec<_> = options.renamed_outputs.iter().collect();
+    renamed.sort();
+    parts.push(format!("{:?}", renamed).into_bytes());
+    let mut links: Vec<_> = options.documentation_links.iter().collect();
+    links.sort();
+    parts.push(format!("{:?}", links).into_bytes());
+    if let Some(dir) = matches.get_one::<String>("templates") {
+        for entry in WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry?;
+            if entry.file_type().is_file() {
+                parts.push(entry.path().to_string_lossy().as_bytes().to_vec());
+                parts.push(fs::read(entry.path())?);
+            }
+        }
+    }
+
+    Ok(fingerprint(parts.iter().map(Vec::as_slice)))
+}
+
+/// The directory the pages are placed relative to, and the files to convert:
+/// the selected files below `--input` when it is a directory, or the file
+/// itself, in its parent directory, or standard input for `-`.
+fn select_inputs(
+    matches: &ArgMatches,
+) -> Result<(String, Vec<PathBuf>), Box<dyn std::error::Error>> {
+    let input = matches.get_one::<String>("input").unwrap();
+    if input == STDIO {
+        return Ok((STDIO.to_string(), vec![stdin_path()]));
+    }
+    let path = Path::new(input);
+    if path.is_file() {
+        if InputFormat::from_path(path).is_none() {
+            return Err(format!(
+                "{} is not a Turtle, TriG, N-Triples, N-Quads or RDF/XML file",
+                input
+            )
+            .into());
+        }
+        let parent = path.parent().unwrap_or(Path::new(""));
+        return Ok((
+            parent.to_string_lossy().to_string(),
+            vec![path.to_path_buf()],
+        ));
+    }
+
+    let include: Vec<String> = matches
+        .get_many::<String>("include")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let exclude: Vec<String> = matches
+        .get_many::<String>("exclude")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let mut walk = InputWalk::new(&include, &exclude)?;
+    walk.follow_symlinks = matches.get_flag("follow-symlinks");
+    walk.max_depth = matches
+        .get_one::<u64>("max-depth")
+        .map(|depth| *depth as usize);
+    walk.same_file_system = matches.get_flag("same-file-system");
+    Ok((input.clone(), walk.files(input)))
+}
+
+/// `path` relative to `input_dir`, with `/` separators.
+fn relative_input(path: &Path, input_dir: &str) -> String {
+    url_path(path.strip_prefix(input_dir).unwrap_or(path))
+}
+
+/// `path:line:column: message` followed by the source snippet.
+fn located(path: &Path, diagnostic: &Diagnostic) -> String {
+    format!(
+        "{}:{}:{}: {}\n{}",
+        path.display(),
+        diagnostic.line,
+        diagnostic.column,
+        diagnostic.message,
+        diagnostic.snippet
+    )
+}
+
+/// Converts the input directory, writing the site to `backend`. With a
+/// `cache_dir`, input files that did not change since the run recorded
+/// there are not converted again. With `live_reload`, pages reload
+/// themselves when the preview server reports a rebuild. With `dry_run`,
+/// which `backend` is then too, the files of every input are listed
+/// instead of written.
+fn build(
+    matches: &ArgMatches,
+    backend: &dyn OutputBackend,
+    cache_dir: Option<&Path>,
+    live_reload: bool,
+    dry_run: Option<&DryRunOutput>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    let (input_dir, inputs) = select_inputs(matches)?;
+    let input_dir = input_dir.as_str();
+    let mut jobs = match matches.get_one::<u64>("jobs") {
+        Some(n) => Jobs::uniform(*n as usize),
+        None => Jobs::default(),
+    };
+    for (arg, stage) in [
+        ("parse-jobs", &mut jobs.parse),
+        ("render-jobs", &mut jobs.render),
+        ("write-jobs", &mut jobs.write),
+    ] {
+        if let Some(n) = matches.get_one::<u64>(arg) {
+            *stage = *n as usize;
+        }
+    }
+    let throttled = ThrottledOutput::new(backend, jobs.write);
+
+    let strict_csp = matches.get_flag("strict-csp");
+    let csp = StrictCspOutput::new(&throttled);
+    let checked: &dyn OutputBackend = if strict_csp { &csp } else { &throttled };
+    let html_check = HtmlCheckOutput::new(checked);
+    let checked: &dyn OutputBackend = if matches.get_flag("check-html") {
+        &html_check
+    } else {
+        checked
+    };
+    let accessible = matches.get_flag("accessible");
+    let a11y_check = A11yCheckOutput::new(checked);
+    let checked: &dyn OutputBackend = if accessible { &a11y_check } else { checked };
+    let check_links = matches.get_one::<String>("check-links");
+    let link_check = LinkCheckOutput::new(checked);
+    let checked: &dyn OutputBackend = if check_links.is_some() {
+        &link_check
+    } else {
+        checked
+    };
+    let checksums = ChecksumOutput::new(checked);
+    let checked: &dyn OutputBackend = if matches.get_flag("checksums") {
+        &checksums
+    } else {
+        checked
+    };
+    let sitemap = SitemapOutput::new(checked);
+    let checked: &dyn OutputBackend = if matches.get_flag("sitemap") {
+        &sitemap
+    } else {
+        checked
+    };
+    let minify = MinifyOutput::new(checked);
+    let checked: &dyn OutputBackend = if matches.get_flag("minify") {
+        &minify
+    } else {
+        checked
+    };
+    let base_url = matches
+        .get_one::<String>("base-url")
+        .map(|url| parse_base_url(url))
+        .transpose()?;
+    let canonical = base_url
+        .clone()
+        .map(|base_url| CanonicalOutput::new(checked, base_url));
+    let output: &dyn OutputBackend = match &canonical {
+        Some(canonical) => canonical,
+        None => checked,
+    };
+
+    let theme = matches.get_one::<String>("theme").unwrap();
+    let tera = match matches.get_one::<String>("templates") {
+        Some(dir) => templates_from_dir(Path::new(dir), theme)?,
+        None => themed_templates(theme)?,
+    };
+
+    if let Some(dir) = matches.get_one::<String>("assets") {
+        let copied = copy_assets(output, Path::new(dir))?;
+        info!("Copied {} asset(s) from {}", copied, dir);
+    }
+
+    let hash_assets = matches.get_flag("hash-assets");
+    let switcher = matches.get_flag("quick-switcher");
+    let mut css_context = Context::new();
+    css_context.insert("accessible", &accessible);
+    css_context.insert("switcher", &switcher);
+    let dark_mode = matches.get_flag("dark-mode");
+    css_context.insert("color_scheme", &dark_mode);
+    let mut stylesheets = HashMap::new();
+    if matches.get_flag("external-styles") || strict_csp {
+        let print = matches.get_flag("print-view").then_some("print");
+        for name in ["page", "index"].into_iter().chain(print) {
+            let css = tera.render(&format!("{}.css", name), &css_context)?;
+            let link = write_asset(
+                output,
+                &format!("{}.css", name),
+                css.as_bytes(),
+                hash_assets,
+            )?;
+            stylesheets.insert(name, link);
+        }
+    }
+
+    let mut base_context = base_context();
+    base_context.insert("stylesheets", &stylesheets);
+    base_context.insert("accessible", &accessible);
+    if switcher {
+        let script = write_asset(
+            output,
+            "switcher.js",
+            include_bytes!("../templates/switcher.js"),
+            hash_assets,
+        )?;
+        base_context.insert("switcher", &script);
+    }
+    if dark_mode {
+        let script = write_asset(
+            output,
+            "color-scheme.js",
+            include_bytes!("../templates/color-scheme.js"),
+            hash_assets,
+        )?;
+        base_context.insert("color_scheme", &script);
+    }
+    if live_reload {
+        let script = write_asset(
+            output,
+            "live-reload.js",
+            include_bytes!("../templates/live-reload.js"),
+            hash_assets,
+        )?;
+        base_context.insert("live_reload", &script);
+    }
+    base_context.insert("analytics", &analytics_snippet(matches)?);
+
+    let mut convert_options = ConvertOptions {
+        lenient: matches.get_flag("lenient"),
+        readable_iris: matches.get_flag("readable-iris"),
+        max_literal_length: matches
+            .get_one::<u64>("max-literal-length")
+            .map(|max| *max as usize),
+        label_properties: matches
+            .get_many::<String>("label-property")
+            .unwrap()
+            .cloned()
+            .collect(),
+        languages: matches
+            .get_many::<String>("lang")
+            .map(|languages| languages.cloned().collect())
+            .unwrap_or_default(),
+        prefixes: global_prefixes(matches)?,
+        index_page_size: Some(*matches.get_one::<u64>("index-page-size").unwrap() as usize),
+        label_order: if matches.contains_id("locale") || matches.get_flag("natural-sort") {
+            Some(LabelOrder::new(
+                matches.get_one::<String>("locale").map(String::as_str),
+                matches.get_flag("natural-sort"),
+            )?)
+        } else {
+            None
+        },
+        on_error: match matches.get_one::<String>("on-error").map(String::as_str) {
+            _ if matches.get_flag("strict") => ErrorPolicy::FailFast,
+            Some("fail-fast") => ErrorPolicy::FailFast,
+            Some("skip-file") => ErrorPolicy::SkipFile,
+            _ => ErrorPolicy::Continue,
+        },
+        graphs: matches
+            .get_many::<String>("graph")
+            .map(|graphs| graphs.cloned().collect()),
+        documentation_links: documentation_links(matches)?,
+        site_links: HashMap::new(),
+        corpus: Corpus::default(),
+        specification_links: !matches.get_flag("no-spec-links"),
+        print_view: matches.get_flag("print-view"),
+        source_view: matches.get_flag("source-view"),
+        copy_sources: matches.get_flag("copy-sources"),
+        slugify: matches.get_flag("slugify"),
+        renamed_outputs: HashMap::new(),
+        base_iri: base_iri(matches)?,
+        merge_same_as: matches.get_flag("merge-same-as"),
+        group_memory: matches
+            .get_one::<u64>("group-memory")
+            .map(|megabytes| *megabytes as usize * 1024 * 1024),
+        split_by_subject: matches.get_flag("split-by-subject"),
+        group_by_type: matches.get_flag("group-by-type"),
+        ontology: matches.get_flag("ontology"),
+        json_ld: matches.get_flag("json-ld"),
+        rdfa: matches.get_flag("rdfa"),
+        obfuscate_emails: !matches.get_flag("show-emails"),
+        recover: matches.get_flag("recover"),
+        index_base_url: base_url
+            .clone()
+            .filter(|_| matches.get_flag("absolute-links")),
+    };
+
+    let mut index_entries = Vec::new();
+    let mut converted_inputs = Vec::new();
+    let mut dataset_stats = DatasetStats::default();
+    let mut search_entries = Vec::new();
+    let mut subjects = Vec::new();
+    let mut usage_stats = UsageStats::default();
+    let mut site_stats = SiteStats::default();
+    let servers: Vec<&String> = matches
+        .get_many::<String>("conneg")
+        .map(|servers| servers.collect())
+        .unwrap_or_default();
+    let mut conneg = (!servers.is_empty())
+        .then(|| base_url.clone().map(ContentNegotiation::new))
+        .flatten();
+    let mut label_uses = Vec::new();
+    let mut summary = SiteSummary {
+        title: "Index of RDF Files".to_string(),
+        description: matches.get_one::<String>("site-description").cloned(),
+        base_url: base_url.clone().map(String::from),
+        ..Default::default()
+    };
+    let mut recovered_errors = 0;
+    let mut failed_files = 0;
+    let error_report_path = matches.get_one::<String>("error-report").map(Path::new);
+    let mut error_report = ErrorReport::default();
+
+    let collision_policy = match matches
+        .get_one::<String>("on-collision")
+        .map(String::as_str)
+    {
+        Some("rename") => CollisionPolicy::Rename,
+        _ => CollisionPolicy::Fail,
+    };
+    convert_options.renamed_outputs =
+        resolve_collisions(&inputs, input_dir, &convert_options, collision_policy)?;
+
+    let mut cache = cache_dir
+        .map(|dir| -> Result<_, Box<dyn std::error::Error>> {
+            let settings = build_settings(matches, &base_context, &convert_options)?;
+            Ok(BuildCache::load(dir, settings))
+        })
+        .transpose()?;
+    // a single file has no other pages to link to
+    if inputs.len() > 1 {
+        let outlines = outline_files(
+            &inputs,
+            input_dir,
+            &convert_options,
+            jobs.parse,
+            cache.as_mut(),
+        );
+        if !matches.get_flag("no-site-links") {
+            convert_options.site_links = site_links(&outlines);
+        }
+        convert_options.corpus = Corpus::new(&outlines);
+    }
+    if let Some(cache) = &mut cache {
+        cache.set_corpus(convert_options.corpus.fingerprint().to_string());
+    }
+    let results = match &mut cache {
+        Some(cache) => convert_changed(
+            &inputs,
+            input_dir,
+            output,
+            cache,
+            &tera,
+            &base_context,
+            &convert_options,
+            &jobs,
+        )?,
+        None => {
+            progress::start(inputs.len());
+            convert_files(
+                &inputs,
+                input_dir,
+                output,
+                &tera,
+                &base_context,
+                &convert_options,
+                &jobs,
+            )
+        }
+    };
+    // with the bar, the files need not be listed again
+    let progress_shown = progress::finish();
+    for (path, result) in results {
+        match result {
+            Ok(converted) => {
+                if let Some(cache) = &mut cache {
+                    cache.record(path.strip_prefix(input_dir)?, &converted)?;
+                }
+                for error in &converted.errors {
+                    warn!("Recovered from parse error at {}", located(&path, error));
+                }
+                error_report.add_recovered(relative_input(&path, input_dir), &converted.errors);
+                recovered_errors += converted.errors.len();
+                if let Some(dry_run) = dry_run {
+                    dry_run.plan(&path, &converted.outputs);
+                } else if !progress_shown {
+                    info!("Successfully converted {:?}", path);
+                }
+                let entry = IndexEntry::new(
+                    url_path(&converted.path),
+                    path.file_name().unwrap().to_string_lossy().to_string(),
+                );
+                let modified = fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .map(DateTime::<Utc>::from);
+                dataset_stats.add(&converted.graphs, &entry, modified);
+                site_stats.add(&converted, &entry);
+                if let Some(conneg) = &mut conneg {
+                    conneg.add(entry.path(), &converted.subjects);
+                }
+                search_entries.extend(converted.search_entries);
+                subjects.extend(converted.subjects);
+                usage_stats.add(converted.usage);
+                label_uses.extend(converted.labels);
+                for (prefix, iri) in converted.namespaces {
+                    summary.namespaces.entry(prefix).or_insert(iri);
+                }
+                summary.pages.push(SummaryPage {
+                    name: entry.name().to_string(),
+                    path: entry.path().to_string(),
+                    triples: converted.triples,
+                });
+                index_entries.push(entry);
+                converted_inputs.push(path);
+            }
+            Err(e) => {
+                error!("Error converting file {:?}: {}", path, e);
+                error_report.add_failed(relative_input(&path, input_dir), &e);
+                if convert_options.on_error == ErrorPolicy::FailFast {
+                    if let Some(report_path) = error_report_path {
+                        error_report.write(report_path)?;
+                    }
+                    return Err(format!("Stopped at the first error, in {:?}", path).into());
+                }
+                failed_files += 1;
+            }
+        }
+    }
+
+    for collision in collisions(&label_uses) {
+        warn!("Warning: {}", collision);
+    }
+
+    if matches.get_flag("terms-manifest") {
+        let base_url = base_url.as_ref().unwrap();
+        TermsManifest::from_entries(&search_entries, base_url)?.write(output)?;
+        summary.resources.push(Resource {
+            path: "terms.json".to_string(),
+            description: "Documentation URL of every term, by term IRI".to_string(),
+        });
+    }
+    summary.terms = search_entries.len();
+
+    let search = matches.get_flag("search");
+    if search || switcher {
+        write_search_index(output, &search_entries)?;
+    }
+    if search {
+        write_search(output, &tera, &base_context, hash_assets)?;
+        base_context.insert("search", &true);
+        summary.resources.push(Resource {
+            path: "search-index.json".to_string(),
+            description: "Label, IRI and page of every term".to_string(),
+        });
+        summary.resources.push(Resource {
+            path: "search.html".to_string(),
+            description: "Search page for terms and their descriptions".to_string(),
+        });
+    }
+
+    if matches.get_flag("concordance") {
+        write_concordance(output, subjects, &tera, &base_context, &convert_options)?;
+        base_context.insert("concordance", &true);
+        summary.resources.push(Resource {
+            path: "all.html".to_string(),
+            description: "Every subject with its types and source file".to_string(),
+        });
+    }
+
+    if matches.get_flag("usage-report") {
+        usage_stats.write_report(output, &tera, &base_context)?;
+        base_context.insert("usage", &true);
+        summary.resources.push(Resource {
+            path: "usage.html".to_string(),
+            description: "How often each class and property is used".to_string(),
+        });
+    }
+
+    if matches.get_flag("stats") {
+        site_stats.write_page(output, &tera, &base_context)?;
+        base_context.insert("stats", &true);
+        summary.resources.push(Resource {
+            path: "stats.html".to_string(),
+            description:
+                "Statement, subject, predicate and class counts of the site and of every file"
+                    .to_string(),
+        });
+    }
+
+    if dataset_stats.has_named_graphs() {
+        dataset_stats.write_overview(output, &tera, &base_context)?;
+        base_context.insert("datasets", &true);
+        summary.resources.push(Resource {
+            path: "datasets.html".to_string(),
+            description: "Named graphs with their statement counts and sources".to_string(),
+        });
+    }
+
+    if !matches.get_flag("no-index") {
+        generate_index(
+            output,
+            &index_entries,
+            &tera,
+            &base_context,
+            &convert_options,
+        )?;
+        summary.resources.insert(
+            0,
+            Resource {
+                path: "index.html".to_string(),
+                description: "Index of the pages generated from each input file".to_string(),
+            },
+        );
+    }
+
+    if matches.get_flag("site-summary") {
+        summary.write(output)?;
+    }
+
+    if strict_csp {
+        let analytics = matches.contains_id("plausible").then_some(PLAUSIBLE_ORIGIN);
+        csp.write_policy(search || switcher || dark_mode, search, analytics)?;
+    }
+
+    if let Some(base_url) = &base_url {
+        let sitemap_url = if matches.get_flag("sitemap") {
+            let sitemap_path = sitemap.write_sitemaps(base_url)?;
+            info!("Wrote {}", sitemap_path);
+            Some(base_url.join(&sitemap_path)?)
+        } else {
+            None
+        };
+        output.write(
+            Path::new("robots.txt"),
+            robots_txt(sitemap_url.as_ref()).as_bytes(),
+        )?;
+    }
+
+    if let Some(conneg) = &conneg {
+        for server in &servers {
+            let path = conneg.write(output, server)?;
+            info!("Wrote {}", path);
+        }
+    }
+
+    if accessible {
+        let issues = a11y_check.report();
+        info!("Accessibility check: {} issue(s)", issues);
+    }
+
+    if matches.get_flag("check-html") {
+        let malformed = html_check.report();
+        info!("HTML check: {} malformed page(s)", malformed);
+    }
+
+    if let Some(mode) = check_links {
+        let broken = link_check.broken_links();
+        for link in &broken {
+            warn!("Broken link: {}", link);
+        }
+        info!("Link check: {} broken link(s)", broken.len());
+
+        if mode == "fail" && !broken.is_empty() {
+            return Err(format!("{} broken intra-site link(s)", broken.len()).into());
+        }
+    }
+
+    if matches.get_flag("checksums") {
+        if matches.get_flag("checksum-inputs") {
+            write_input_sums(backend, input_dir, &converted_inputs)?;
+        }
+        checksums.write_sums()?;
+    }
+
+    if let Some(cache) = cache {
+        cache.save()?;
+    }
+
+    if let Some(report_path) = error_report_path {
+        error_report.write(report_path)?;
+    }
+
+    info!(
+        "Converted {} file(s), {} failed, {} parse error(s) recovered",
+        converted_inputs.len(),
+        failed_files,
+        recovered_errors
+    );
+    debug!("Finished in {:.1?}", start.elapsed());
+    if failed_files > 0 {
+        return Err(format!("{} file(s) could not be converted", failed_files).into());
+    }
+
+    Ok(())
+}
+
+/// Arguments of the conversion, shared by the `check` subcommand.
+fn convert_args() -> Vec<Arg> {
+    let mut args = vec![
+        Arg::new("input")
+            .short('i')
+            .long("input")
+            .value_name("INPUT")
+            .value_hint(ValueHint::AnyPath)
+            .help("Sets the input directory, or a single file to convert, or - for standard input")
+            .required(true),
+        Arg::new("output")
+            .short('o')
+            .long("output")
+            .value_name("OUTPUT_DIR")
+            .value_hint(ValueHint::DirPath)
+            .help("Sets the output directory, or an s3://bucket/prefix URL, or - to write the page of a single input to standard output")
+            .required(true),
+        Arg::new("include")
+            .long("include")
+            .value_name("GLOB")
+            .action(ArgAction::Append)
+            .help("Converts only the input files matching GLOB, relative to the input directory; may be given more than once"),
+        Arg::new("exclude")
+            .long("exclude")
+            .value_name("GLOB")
+            .action(ArgAction::Append)
+            .help("Skips the input files and directories matching GLOB, relative to the input directory; may be given more than once"),
+        Arg::new("follow-symlinks")
+            .long("follow-symlinks")
+            .action(ArgAction::SetTrue)
+            .help("Enters symbolically linked directories below the input directory"),
+        Arg::new("max-depth")
+            .long("max-depth")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Converts only the input files at most N levels below the input directory; 1 converts only the files directly in it"),
+        Arg::new("same-file-system")
+            .long("same-file-system")
+            .action(ArgAction::SetTrue)
+            .help("Does not enter directories on another file system than the input directory"),
+        config_arg(),
+        Arg::new("checksums")
+            .long("checksums")
+            .action(ArgAction::SetTrue)
+            .help("Writes a SHA256SUMS file covering every generated file"),
+        Arg::new("checksum-inputs")
+            .long("checksum-inputs")
+            .action(ArgAction::SetTrue)
+            .requires("checksums")
+            .help("Also writes SHA256SUMS.inputs covering the converted input files"),
+        Arg::new("external-styles")
+            .long("external-styles")
+            .action(ArgAction::SetTrue)
+            .help("Writes the stylesheets to separate files linked with subresource integrity"),
+        Arg::new("show-emails")
+            .long("show-emails")
+            .action(ArgAction::SetTrue)
+            .help("Shows email addresses as plain text instead of entity-encoding them"),
+        Arg::new("print-view")
+            .long("print-view")
+            .action(ArgAction::SetTrue)
+            .help("Also writes a print-ready NAME.print.html variant of every page, linked from the page"),
+        Arg::new("source-view")
+            .long("source-view")
+            .action(ArgAction::SetTrue)
+            .help("Also writes a NAME.source.html view of the input of every page, with syntax highlighting and line anchors, linked from the page"),
+        Arg::new("slugify")
+            .long("slugify")
+            .action(ArgAction::SetTrue)
+            .help("Names pages in lowercase ASCII, with dashes for spaces and reserved characters; the index keeps the input file names"),
+        Arg::new("on-collision")
+            .long("on-collision")
+            .value_name("POLICY")
+            .value_parser(["fail", "rename"])
+            .default_value("fail")
+            .help("Fails the run (fail) or numbers the pages (rename) when the pages of several input files would have the same path"),
+        Arg::new("copy-sources")
+            .long("copy-sources")
+            .action(ArgAction::SetTrue)
+            .help("Copies every input file next to its page, under the same relative path, and links it from the page"),
+        Arg::new("conneg")
+            .long("conneg")
+            .value_name("SERVER")
+            .value_parser(SERVERS.to_vec())
+            .action(ArgAction::Append)
+            .requires("base-url")
+            .requires("copy-sources")
+            .help("Writes web server rules redirecting the IRIs of terms below the base URL to their page, or to the copied input file for clients asking for RDF; can be repeated"),
+        Arg::new("split-by-subject")
+            .long("split-by-subject")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("print-view")
+            .help("Writes a page per subject, and lists the subjects of each file on the file's page"),
+        Arg::new("group-by-type")
+            .long("group-by-type")
+            .action(ArgAction::SetTrue)
+            .help("Shows the subjects of each page in a section per rdf:type, with a bar linking to the sections"),
+        Arg::new("ontology")
+            .long("ontology")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("group-by-type")
+            .help("Documents ontologies: sections for classes, properties and individuals, with definitions, superclasses, domains and ranges"),
+        Arg::new("json-ld")
+            .long("json-ld")
+            .action(ArgAction::SetTrue)
+            .help("Embeds the statements of every page in it as JSON-LD, for search engines and other clients"),
+        Arg::new("rdfa")
+            .long("rdfa")
+            .action(ArgAction::SetTrue)
+            .help("Annotates the statement tables with RDFa, so that the statements can be read back from the pages"),
+        Arg::new("hash-assets")
+            .long("hash-assets")
+            .action(ArgAction::SetTrue)
+            .help("Adds a hash of their contents to the names of stylesheets and scripts so they can be cached indefinitely"),
+        Arg::new("strict-csp")
+            .long("strict-csp")
+            .action(ArgAction::SetTrue)
+            .help("Guarantees pages work under a strict Content-Security-Policy and writes the recommended header"),
+        Arg::new("accessible")
+            .long("accessible")
+            .action(ArgAction::SetTrue)
+            .help("Adds skip links and higher-contrast styles, and checks every page for accessibility issues"),
+        Arg::new("check-html")
+            .long("check-html")
+            .action(ArgAction::SetTrue)
+            .help("Parses every generated page and reports malformed markup"),
+        Arg::new("check-links")
+            .long("check-links")
+            .value_name("MODE")
+            .value_parser(["warn", "fail"])
+            .num_args(0..=1)
+            .default_missing_value("warn")
+            .help("Verifies that every intra-site link resolves; fails the run in 'fail' mode"),
+        Arg::new("templates")
+            .long("templates")
+            .value_name("DIR")
+            .value_hint(ValueHint::DirPath)
+            .help("Loads templates from DIR, replacing the built-in templates of the same name"),
+        Arg::new("minify")
+            .long("minify")
+            .action(ArgAction::SetTrue)
+            .help("Collapses the whitespace of the generated HTML"),
+        Arg::new("theme")
+            .long("theme")
+            .value_name("THEME")
+            .value_parser(THEMES.to_vec())
+            .default_value("default")
+            .help("Styles the site with one of the built-in themes"),
+        Arg::new("dark-mode")
+            .long("dark-mode")
+            .action(ArgAction::SetTrue)
+            .help("Follows the reader's dark mode preference and adds a light/dark toggle to every page"),
+        Arg::new("assets")
+            .long("assets")
+            .value_name("DIR")
+            .value_hint(ValueHint::DirPath)
+            .help("Copies the files in DIR into the output, keeping their paths"),
+        Arg::new("lenient")
+            .long("lenient")
+            .action(ArgAction::SetTrue)
+            .help("Accepts sloppy input and renders statements that would otherwise be dropped"),
+        Arg::new("on-error")
+            .long("on-error")
+            .value_name("POLICY")
+            .value_parser(["continue", "fail-fast", "skip-file"])
+            .default_value("continue")
+            .help("Skips invalid statements (continue), stops the run (fail-fast) or leaves out the whole file (skip-file) on errors"),
+        Arg::new("strict")
+            .long("strict")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["on-error", "recover"])
+            .help("Stops the run with a non-zero exit status at the first syntax error, like --on-error fail-fast"),
+        Arg::new("dry-run")
+            .long("dry-run")
+            .action(ArgAction::SetTrue)
+            .conflicts_with_all(["incremental", "watch", "serve"])
+            .help("Lists the input files that would be converted and the files that would be written, including collisions, without writing anything"),
+        Arg::new("error-report")
+            .long("error-report")
+            .value_name("FILE")
+            .value_hint(ValueHint::FilePath)
+            .help("Writes a JSON report of the files that failed to convert and the errors recovered from, with their kind, message and location"),
+        Arg::new("recover")
+            .long("recover")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("on-error")
+            .help("Parses one statement at a time so that a syntax error only drops the statement it is in"),
+        Arg::new("graph")
+            .long("graph")
+            .value_name("IRI")
+            .action(ArgAction::Append)
+            .help("Converts only statements in the named graph IRI, or in the default graph for 'default'; can be repeated"),
+        Arg::new("search")
+            .long("search")
+            .action(ArgAction::SetTrue)
+            .help("Writes a search page and search-index.json covering every term with an anchor"),
+        Arg::new("quick-switcher")
+            .long("quick-switcher")
+            .action(ArgAction::SetTrue)
+            .help("Adds a palette, opened with the / key, to jump to any term of the site"),
+        Arg::new("concordance")
+            .long("concordance")
+            .action(ArgAction::SetTrue)
+            .help("Writes an all.html page listing every subject with its types and source file"),
+        Arg::new("usage-report")
+            .long("usage-report")
+            .action(ArgAction::SetTrue)
+            .help("Writes a usage.html report of how often each class and property is used"),
+        Arg::new("stats")
+            .long("stats")
+            .action(ArgAction::SetTrue)
+            .help("Writes a stats.html page counting the statements, subjects, predicates and classes"),
+        Arg::new("site-summary")
+            .long("site-summary")
+            .action(ArgAction::SetTrue)
+            .help("Writes llms.txt and index.json describing the site for crawlers and AI assistants"),
+        Arg::new("site-description")
+            .long("site-description")
+            .value_name("TEXT")
+            .help("Describes the purpose of the site in llms.txt and index.json"),
+        Arg::new("terms-manifest")
+            .long("terms-manifest")
+            .action(ArgAction::SetTrue)
+            .requires("base-url")
+            .help("Writes terms.json listing the documentation URL of every term, for other sites to link to"),
+        Arg::new("link-site")
+            .long("link-site")
+            .value_name("URL")
+            .value_hint(ValueHint::AnyPath)
+            .action(ArgAction::Append)
+            .help("Links terms documented by another generated site, given its URL or a path to its terms.json; can be repeated"),
+        Arg::new("term-links")
+            .long("term-links")
+            .value_name("FILE")
+            .value_hint(ValueHint::FilePath)
+            .help("Links the terms in FILE, a JSON object of IRIs and URLs, to those URLs, overriding all other links"),
+        Arg::new("merge-same-as")
+            .long("merge-same-as")
+            .action(ArgAction::SetTrue)
+            .help("Shows the subjects connected by owl:sameAs as one entry, listing their other IRIs"),
+        Arg::new("no-spec-links")
+            .long("no-spec-links")
+            .action(ArgAction::SetTrue)
+            .help("Links rdf, rdfs, owl, xsd and skos terms to their IRIs instead of the W3C specifications"),
+        Arg::new("no-site-links")
+            .long("no-site-links")
+            .action(ArgAction::SetTrue)
+            .help("Links terms described in other input files to their IRIs instead of their pages"),
+        Arg::new("no-index")
+            .long("no-index")
+            .action(ArgAction::SetTrue)
+            .help("Does not write the index of the converted files"),
+        Arg::new("index-page-size")
+            .long("index-page-size")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .default_value("1000")
+            .help("Splits the index into pages once it has more than N entries"),
+        Arg::new("base")
+            .long("base")
+            .value_name("IRI")
+            .help("Resolves relative IRIs in files without a base of their own against IRI, where {path} stands for the path of the file in the input directory and {name} for its name without extension"),
+        Arg::new("base-url")
+            .long("base-url")
+            .value_name("URL")
+            .help("Sets the public URL the generated site is served from"),
+        Arg::new("sitemap")
+            .long("sitemap")
+            .action(ArgAction::SetTrue)
+            .requires("base-url")
+            .help("Writes sitemap.xml, split with a sitemap_index.xml for very large sites"),
+        Arg::new("absolute-links")
+            .long("absolute-links")
+            .action(ArgAction::SetTrue)
+            .requires("base-url")
+            .help("Links the pages from the index by their absolute URL under --base-url"),
+        Arg::new("analytics-snippet")
+            .long("analytics-snippet")
+            .value_name("FILE")
+            .value_hint(ValueHint::FilePath)
+            .help("Injects the HTML in FILE into the head of every page"),
+        Arg::new("plausible")
+            .long("plausible")
+            .value_name("DOMAIN")
+            .conflicts_with("analytics-snippet")
+            .help("Injects the Plausible analytics script for DOMAIN into every page"),
+        Arg::new("prefix-file")
+            .long("prefix-file")
+            .value_name("FILE")
+            .value_hint(ValueHint::FilePath)
+            .help("Shortens IRIs with the prefixes in FILE where their file declares none"),
+        Arg::new("no-builtin-prefixes")
+            .long("no-builtin-prefixes")
+            .action(ArgAction::SetTrue)
+            .help("Leaves IRIs of common vocabularies in full where their file declares no prefix"),
+        Arg::new("readable-iris")
+            .long("readable-iris")
+            .action(ArgAction::SetTrue)
+            .help("Shows IRIs with decoded punycode hosts and percent-escapes"),
+        Arg::new("max-literal-length")
+            .long("max-literal-length")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64))
+            .help("Truncates literals longer than N characters and writes them in full to an attachment"),
+        Arg::new("label-property")
+            .long("label-property")
+            .value_name("IRI")
+            .action(ArgAction::Append)
+            .default_values(DISPLAY_LABEL_PREDICATES)
+            .help("Shows the values of the predicate IRI in place of the names of terms; can be repeated, in order of priority"),
+        Arg::new("lang")
+            .long("lang")
+            .value_name("LANG")
+            .action(ArgAction::Append)
+            .help("Shows only text in language LANG where a term has it, falling back to untagged text; can be repeated, in order of preference"),
+        Arg::new("locale")
+            .long("locale")
+            .value_name("LOCALE")
+            .help("Sorts subjects and index entries by label using the collation rules of LOCALE, e.g. de or sv"),
+        Arg::new("natural-sort")
+            .long("natural-sort")
+            .action(ArgAction::SetTrue)
+            .help("Sorts subjects and index entries by label, comparing embedded numbers by value"),
+        Arg::new("jobs")
+            .short('j')
+            .long("jobs")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Converts up to N files at a time, from parsing to writing, with up to N threads per stage unless set below (default: number of cores)"),
+        Arg::new("parse-jobs")
+            .long("parse-jobs")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Parses up to N files at a time (default: number of cores)"),
+        Arg::new("render-jobs")
+            .long("render-jobs")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Renders up to N pages at a time (default: number of cores)"),
+        Arg::new("write-jobs")
+            .long("write-jobs")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Writes up to N output files at a time (default: number of cores)"),
+        Arg::new("group-memory")
+            .long("group-memory")
+            .value_name("MB")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .help("Groups the statements of files taking more than MB megabytes in memory by sorting them on disk"),
+        Arg::new("incremental")
+            .long("incremental")
+            .action(ArgAction::SetTrue)
+            .help("Only converts input files that changed since the last run into the same output directory"),
+        Arg::new("watch")
+            .long("watch")
+            .action(ArgAction::SetTrue)
+            .help("Keeps running and converts changed input files again whenever the input directory or templates change"),
+        Arg::new("serve")
+            .long("serve")
+            .value_name("PORT")
+            .value_parser(clap::value_parser!(u16))
+            .num_args(0..=1)
+            .default_missing_value("8000")
+            .help("Serves the output directory on http://127.0.0.1:PORT/ after converting (default port: 8000)"),
+    ];
+    args.extend(retry_args());
+    args
+}
+
+fn config_arg() -> Arg {
+    Arg::new("config")
+        .long("config")
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .help("Reads options from the TOML file FILE (default: ttl2html.toml, if present); options on the command line take precedence")
+}
+
+/// Options controlling how requests to remote services are retried, shared
+/// by the conversion and the deploy subcommand.
+fn retry_args() -> [Arg; 2] {
+    [
+        Arg::new("retries")
+            .long("retries")
+            .value_name("N")
+            .value_parser(clap::value_parser!(u32))
+            .default_value("3")
+            .help(
+                "Retries failed requests to remote services up to N times with exponential backoff",
+            ),
+        Arg::new("http-timeout")
+            .long("http-timeout")
+            .value_name("SECONDS")
+            .value_parser(clap::value_parser!(u64).range(1..))
+            .default_value("300")
+            .help("Gives up on a remote operation, including its retries, after SECONDS"),
+    ]
+}
+
+fn retry_policy(matches: &ArgMatches) -> RetryPolicy {
+    RetryPolicy {
+        retries: *matches.get_one::<u32>("retries").unwrap(),
+        timeout: Duration::from_secs(*matches.get_one::<u64>("http-timeout").unwrap()),
+    }
+}
+
+/// Documentation URLs from `--term-links` and of the terms of the sites
+/// given with `--link-site`. When several sites document a term, the first
+/// one wins.
+fn documentation_links(
+    matches: &ArgMatches,
+) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let mut links: HashMap<String, String> = match matches.get_one::<String>("term-links") {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| format!("Invalid term links {}: {}", path, e))?,
+        None => HashMap::new(),
+    };
+    for site in matches.get_many::<String>("link-site").unwrap_or_default() {
+        let manifest = TermsManifest::load(site, retry_policy(matches))?;
+        info!(
+            "Linking {} term(s) documented by {}",
+            manifest.terms.len(),
+            site
+        );
+        for (iri, url) in manifest.terms {
+            links.entry(iri).or_insert(url);
+        }
+    }
+
+    Ok(links)
+}
+
+/// Prefixes from `--prefix-file`, followed by the built-in ones unless
+/// `--no-builtin-prefixes` is given.
+fn global_prefixes(
+    matches: &ArgMatches,
+) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let mut global = match matches.get_one::<String>("prefix-file") {
+        Some(path) => prefixes::load(Path::new(path))?,
+        None => Vec::new(),
+    };
+    if !matches.get_flag("no-builtin-prefixes") {
+        global.extend(prefixes::built_in());
+    }
+    Ok(global)
+}
+
+/// The template of `--base`, checked to make an absolute IRI.
+fn base_iri(matches: &ArgMatches) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(template) = matches.get_one::<String>("base") else {
+        return Ok(None);
+    };
+    let options = ConvertOptions {
+        base_iri: Some(template.clone()),
+        ..Default::default()
+    };
+    let example = options
+        .base_iri_for(Path::new("onto.ttl"))
+        .unwrap_or_default();
+    url::Url::parse(&example).map_err(|e| format!("Invalid --base {:?}: {}", template, e))?;
+    Ok(Some(template.clone()))
+}
+
+/// Origin the Plausible script is loaded from and reports to.
+const PLAUSIBLE_ORIGIN: &str = "https://plausible.io";
+
+/// HTML injected into the head of every page for analytics, if requested.
+fn analytics_snippet(matches: &ArgMatches) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if let Some(path) = matches.get_one::<String>("analytics-snippet") {
+        return Ok(Some(fs::read_to_string(path)?));
+    }
+
+    Ok(matches.get_one::<String>("plausible").map(|domain| {
+        format!(
+            "<script defer data-domain=\"{}\" src=\"{}/js/script.js\"></script>",
+            escape::escape_html(domain),
+            PLAUSIBLE_ORIGIN
+        )
+    }))
+}
@@ -0,0 +1,89 @@
+use std::cmp::Ordering;
+
+use icu_collator::preferences::CollationNumericOrdering;
+use icu_collator::{Collator, CollatorBorrowed, CollatorPreferences};
+use icu_locale_core::Locale;
+
+/// Order used for subject labels and index entry names instead of the default
+/// code point order: the Unicode Collation Algorithm tailored to a locale, so
+/// accented and non-Latin labels sort the way readers expect, and/or natural
+/// order, where embedded numbers compare by value (`term2` before `term10`).
+pub struct LabelOrder {
+    collator: Option<CollatorBorrowed<'static>>,
+    natural: bool,
+}
+
+impl LabelOrder {
+    pub fn new(locale: Option<&str>, natural: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        let collator = match locale {
+            Some(locale) => {
+                let locale: Locale = locale
+                    .parse()
+                    .map_err(|e| format!("Invalid locale {:?}: {}", locale, e))?;
+                let mut preferences = CollatorPreferences::from(&locale);
+                if natural {
+                    preferences.numeric_ordering = Some(CollationNumericOrdering::True);
+                }
+                Some(Collator::try_new(preferences, Default::default())?)
+            }
+            None => None,
+        };
+
+        Ok(LabelOrder { collator, natural })
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match &self.collator {
+            Some(collator) => collator.compare(a, b),
+            None if self.natural => natural_compare(a, b),
+            None => a.cmp(b),
+        }
+    }
+}
+
+/// Compares strings chunk by chunk, runs of ASCII digits by numeric value and
+/// everything else by code point.
+fn natural_compare(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+
+    loop {
+        match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match (is_number(x), is_number(y)) {
+                    (true, true) => {
+                        let x = x.trim_start_matches('0');
+                        let y = y.trim_start_matches('0');
+                        x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+                    }
+                    _ => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+fn is_number(chunk: &str) -> bool {
+    chunk.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Splits a string into alternating runs of digits and non-digits.
+fn chunks(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let digits = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
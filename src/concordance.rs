@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+
+use crate::output::OutputBackend;
+use crate::parser::{ConvertOptions, Pagination};
+
+/// Row of the concordance page: one subject of the corpus.
+#[derive(Serialize, Deserialize)]
+pub struct Subject {
+    pub label: String,
+    pub iri: String,
+    /// Displayed names and IRIs of the subject's `rdf:type`s.
+    pub types: Vec<(String, String)>,
+    /// Page describing the subject, relative to the site root.
+    pub page: String,
+    /// Entry on the page, for subjects that have one.
+    pub anchor: Option<String>,
+    /// Input file the subject was read from, relative to the input directory.
+    pub source: String,
+}
+
+/// Writes `all.html`, listing every subject of the corpus sorted by label.
+/// Past `index_page_size` subjects, the list continues on `all-2.html`,
+/// `all-3.html` and so on.
+pub fn write_concordance(
+    output: &dyn OutputBackend,
+    mut subjects: Vec<Subject>,
+    tera: &Tera,
+    base_context: &Context,
+    options: &ConvertOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    subjects.sort_by(|a, b| {
+        match &options.label_order {
+            Some(label_order) => label_order.compare(&a.label, &b.label),
+            None => a.label.cmp(&b.label),
+        }
+        .then_with(|| a.iri.cmp(&b.iri))
+    });
+
+    let page_size = options.index_page_size.unwrap_or(usize::MAX).max(1);
+    let pages: Vec<&[Subject]> = if subjects.is_empty() {
+        vec![&[]]
+    } else {
+        subjects.chunks(page_size).collect()
+    };
+    let page_path = |i: usize| match i {
+        0 => "all.html".to_string(),
+        _ => format!("all-{}.html", i + 1),
+    };
+
+    for (i, page) in pages.iter().enumerate() {
+        let pagination = (pages.len() > 1).then(|| Pagination {
+            current: i + 1,
+            total: pages.len(),
+            previous: (i > 0).then(|| page_path(i - 1)),
+            next: (i + 1 < pages.len()).then(|| page_path(i + 1)),
+        });
+
+        let mut context = base_context.clone();
+        context.insert("title", "All Subjects");
+        context.insert("root", "");
+        context.insert("subjects", page);
+        context.insert("total", &subjects.len());
+        context.insert("pagination", &pagination);
+
+        let html = tera.render("all.html", &context)?;
+        output.write(Path::new(&page_path(i)), html.as_bytes())?;
+    }
+
+    Ok(())
+}
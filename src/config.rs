@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, ArgAction, Command, ValueHint};
+use toml::{Table, Value};
+
+/// Configuration file read from the current directory when no `--config`
+/// is given.
+pub const CONFIG_FILE: &str = "ttl2html.toml";
+
+/// Inserts the options set in the configuration file before the options of
+/// the command line `args`, so that those given on the command line take
+/// precedence. Keys of the file are the long names of options, such as
+/// `output = "site"`, `print-view = true` or `graph = ["a", "b"]`. A table
+/// named after a subcommand, such as `[deploy]`, holds options of that
+/// subcommand only. Subcommands that take no `--config` leave the file
+/// unread.
+///
+/// Values of repeatable options given on the command line replace those of
+/// the file, `--no-FLAG` on the command line turns off a flag the file sets,
+/// and relative paths in the file are resolved against its directory.
+pub fn with_config(
+    args: Vec<OsString>,
+    command: &Command,
+) -> Result<Vec<OsString>, Box<dyn std::error::Error>> {
+    // the options of a subcommand follow its name
+    let (target, insert_at) = match args
+        .get(1)
+        .and_then(|arg| arg.to_str())
+        .and_then(|name| command.find_subcommand(name))
+    {
+        Some(subcommand) => (subcommand, 2),
+        None => (command, 1),
+    };
+    if !has_arg(target, "config") {
+        return Ok(args);
+    }
+    let (mut args, negated) = negated_flags(args, target, insert_at);
+    let given = given_options(&args[insert_at..], target);
+
+    let path = match config_path(&args) {
+        Some(path) => path,
+        None if Path::new(CONFIG_FILE).is_file() => PathBuf::from(CONFIG_FILE),
+        None => return Ok(args),
+    };
+    let base = path.parent().unwrap_or(Path::new(""));
+    let table: Table = fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+        .parse()
+        .map_err(|e| format!("Invalid configuration {}: {}", path.display(), e))?;
+
+    let section = match (insert_at, table.get(target.get_name())) {
+        (2, Some(Value::Table(section))) => Some(section),
+        _ => None,
+    };
+    let mut options = Vec::new();
+    let entries = table.iter().map(|entry| (entry, false));
+    for ((key, value), in_section) in
+        entries.chain(section.into_iter().flatten().map(|entry| (entry, true)))
+    {
+        // sections of subcommands
+        if value.is_table() && command.find_subcommand(key).is_some() {
+            continue;
+        }
+        let Some(arg) = find_arg(target, key) else {
+            // options of the conversion that this subcommand does not take
+            if !in_section && find_arg(command, key).is_some() {
+                continue;
+            }
+            return Err(format!("Unknown option {:?} in {}", key, path.display()).into());
+        };
+        let replaced =
+            matches!(arg.get_action(), ArgAction::Append) && given.contains(arg.get_id().as_str());
+        if negated.contains(key) || replaced {
+            continue;
+        }
+        options.extend(
+            option(arg, key, value, base).map_err(|e| format!("{} in {}", e, path.display()))?,
+        );
+    }
+
+    args.splice(insert_at..insert_at, options);
+    Ok(args)
+}
+
+/// The value of `--config` on the command line.
+fn config_path(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// `args` without the `--no-FLAG` options that turn off a flag of `target`
+/// set in the configuration file, and the names of those flags. Flags that
+/// have a `--no-` option of their own keep it.
+fn negated_flags(
+    args: Vec<OsString>,
+    target: &Command,
+    options_at: usize,
+) -> (Vec<OsString>, HashSet<String>) {
+    let mut negated = HashSet::new();
+    let mut kept = Vec::with_capacity(args.len());
+    let mut options = true;
+    for (i, arg) in args.into_iter().enumerate() {
+        let flag = arg
+            .to_str()
+            .and_then(|arg| arg.strip_prefix("--no-"))
+            .filter(|_| options && i >= options_at)
+            .filter(|flag| find_arg(target, &format!("no-{}", flag)).is_none())
+            .filter(|flag| {
+                find_arg(target, flag)
+                    .is_some_and(|arg| matches!(arg.get_action(), ArgAction::SetTrue))
+            });
+        match flag {
+            Some(flag) => {
+                negated.insert(flag.to_string());
+            }
+            None => {
+                options &= arg != "--";
+                kept.push(arg);
+            }
+        }
+    }
+    (kept, negated)
+}
+
+/// Ids of the options of `target` given in `args`, in their long or short
+/// form.
+fn given_options(args: &[OsString], target: &Command) -> HashSet<String> {
+    let mut given = HashSet::new();
+    for arg in args.iter().map(|arg| arg.to_string_lossy()) {
+        if arg == "--" {
+            break;
+        }
+        let found = if let Some(long) = arg.strip_prefix("--") {
+            let long = long.split('=').next().unwrap_or_default();
+            find_arg(target, long)
+        } else if let Some(short) = arg.strip_prefix('-').and_then(|s| s.chars().next()) {
+            target
+                .get_arguments()
+                .find(|arg| arg.get_short() == Some(short))
+        } else {
+            None
+        };
+        given.extend(found.map(|arg| arg.get_id().to_string()));
+    }
+    given
+}
+
+fn has_arg(command: &Command, id: &str) -> bool {
+    command.get_arguments().any(|arg| arg.get_id() == id)
+}
+
+fn find_arg<'a>(command: &'a Command, key: &str) -> Option<&'a Arg> {
+    command
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(key))
+}
+
+/// The command-line form of `key = value`, with relative paths resolved
+/// against `base`.
+fn option(arg: &Arg, key: &str, value: &Value, base: &Path) -> Result<Vec<OsString>, String> {
+    let takes_values = arg.get_action().takes_values();
+    let values = match value {
+        // flags, and options whose value is optional
+        Value::Boolean(set) => {
+            return Ok(set
+                .then(|| format!("--{}", key).into())
+                .into_iter()
+                .collect())
+        }
+        Value::Array(values) if takes_values => values.iter().collect(),
+        _ if takes_values => vec![value],
+        _ => return Err(format!("Expected true or false for {:?}", key)),
+    };
+
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::String(s) if is_relative_path(arg, s) => {
+                let mut option = OsString::from(format!("--{}=", key));
+                option.push(base.join(s));
+                Ok(option)
+            }
+            Value::String(s) => Ok(format!("--{}={}", key, s).into()),
+            Value::Integer(n) => Ok(format!("--{}={}", key, n).into()),
+            Value::Float(n) => Ok(format!("--{}={}", key, n).into()),
+            _ => Err(format!("Expected a string or number for {:?}", key)),
+        })
+        .collect()
+}
+
+/// Whether `value` of `arg` is a relative path, rather than an absolute one,
+/// a URL or `-` for standard input or output.
+fn is_relative_path(arg: &Arg, value: &str) -> bool {
+    matches!(
+        arg.get_value_hint(),
+        ValueHint::AnyPath | ValueHint::FilePath | ValueHint::DirPath
+    ) && value != "-"
+        && !value.contains("://")
+        && Path::new(value).is_relative()
+}
@@ -0,0 +1,198 @@
+//! Web server configuration for content negotiation: requests for the IRI
+//! of a term are redirected to its page, or to the input file it was read
+//! from when the client asks for RDF.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use percent_encoding::percent_decode_str;
+use url::{Position, Url};
+
+use crate::concordance::Subject;
+use crate::output::{media_type, OutputBackend};
+
+/// Web servers a configuration can be written for.
+pub const SERVERS: &[&str] = &["apache", "nginx"];
+
+/// Where the requests for one resource path are redirected to.
+struct Negotiated {
+    /// Page, relative to the site root, with the anchor of the entry for
+    /// terms that are not described on a page of their own.
+    page: String,
+    /// Copy of the input file, relative to the site root.
+    source: String,
+    media_type: &'static str,
+}
+
+/// Resource paths below the base URL of the site, taken from the IRIs of
+/// the subjects documented on it.
+pub struct ContentNegotiation {
+    base_url: Url,
+    resources: BTreeMap<String, Negotiated>,
+}
+
+impl ContentNegotiation {
+    pub fn new(base_url: Url) -> Self {
+        ContentNegotiation {
+            base_url,
+            resources: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the subjects of the page `page` whose IRIs are below the base
+    /// URL. Hash IRIs lead to `page` itself, other IRIs to the entry of the
+    /// subject; the first subject to claim a path keeps it.
+    pub fn add(&mut self, page: &str, subjects: &[Subject]) {
+        for subject in subjects {
+            let Some(relative) = subject.iri.strip_prefix(self.base_url.as_str()) else {
+                continue;
+            };
+            let (path, page) = match relative.split_once('#') {
+                Some((path, _)) => (path, page.to_string()),
+                None => match &subject.anchor {
+                    Some(anchor) => (relative, format!("{}#{}", subject.page, anchor)),
+                    None => (relative, subject.page.clone()),
+                },
+            };
+            // the site root serves the index, and queries are not matched
+            if path.is_empty() || path.contains('?') {
+                continue;
+            }
+            let path = percent_decode_str(path).decode_utf8_lossy().to_string();
+            if page.split('#').next() == Some(path.as_str()) || subject.source == path {
+                continue;
+            }
+            self.resources.entry(path).or_insert_with(|| Negotiated {
+                page,
+                media_type: media_type(Path::new(&subject.source)),
+                source: subject.source.clone(),
+            });
+        }
+    }
+
+    /// Writes the configuration for `server`, one of [`SERVERS`], and
+    /// returns the path it was written to.
+    pub fn write(
+        &self,
+        output: &dyn OutputBackend,
+        server: &str,
+    ) -> Result<&'static str, Box<dyn std::error::Error>> {
+        let (path, config) = match server {
+            "apache" => (".htaccess", self.apache()),
+            "nginx" => ("conneg.nginx.conf", self.nginx()),
+            _ => return Err(format!("Unknown web server {}", server).into()),
+        };
+        output.write(Path::new(path), config.as_bytes())?;
+        Ok(path)
+    }
+
+    /// An `.htaccess` file for the site root, using `mod_rewrite`.
+    fn apache(&self) -> String {
+        let mut config = format!(
+            "# Content negotiation for the terms of {}\n\
+             Options -MultiViews\n\
+             RewriteEngine On\n\
+             RewriteBase {}\n",
+            self.base_url,
+            self.base_url.path()
+        );
+        for (media_type, extensions) in self.media_types() {
+            config.push_str(&format!(
+                "AddType {} {}\n",
+                media_type,
+                extensions.join(" ")
+            ));
+        }
+        config.push_str(
+            "<IfModule mod_headers.c>\n\
+             \x20 Header append Vary Accept\n\
+             </IfModule>\n",
+        );
+        for (path, negotiated) in &self.resources {
+            let pattern = format!("^{}$", regex_escape(path));
+            config.push_str(&format!(
+                "\nRewriteCond %{{HTTP_ACCEPT}} {}\n\
+                 RewriteRule {} {} [R=303,L]\n\
+                 RewriteRule {} {} [R=303,NE,L]\n",
+                regex_escape(negotiated.media_type),
+                pattern,
+                negotiated.source,
+                pattern,
+                negotiated.page,
+            ));
+        }
+        config
+    }
+
+    /// Locations to include in the `server` block of the site.
+    fn nginx(&self) -> String {
+        let mut config = format!(
+            "# Content negotiation for the terms of {}\n\
+             # Include this file in the server block of the site.\n",
+            self.base_url
+        );
+        for (media_type, extensions) in self.media_types() {
+            config.push_str(&format!(
+                "\nlocation ~* \\.({})$ {{\n    types {{ {} {}; }}\n}}\n",
+                extensions.join("|"),
+                media_type,
+                extensions.join(" "),
+            ));
+        }
+        for (path, negotiated) in &self.resources {
+            let location = format!("{}{}", self.base_url.path(), path);
+            config.push_str(&format!(
+                "\nlocation = \"{}\" {{\n    \
+                 add_header Vary Accept;\n    \
+                 if ($http_accept ~* \"{}\") {{\n        \
+                 return 303 \"{}\";\n    \
+                 }}\n    \
+                 return 303 \"{}\";\n\
+                 }}\n",
+                location.replace('"', "\\\""),
+                regex_escape(negotiated.media_type),
+                self.absolute_path(&negotiated.source),
+                self.absolute_path(&negotiated.page),
+            ));
+        }
+        config
+    }
+
+    /// `target`, relative to the site root, as an absolute path on the
+    /// server.
+    fn absolute_path(&self, target: &str) -> String {
+        match self.base_url.join(target) {
+            Ok(url) => url[Position::BeforePath..].to_string(),
+            Err(_) => format!("{}{}", self.base_url.path(), target),
+        }
+    }
+
+    /// File extensions of the copied input files, by media type.
+    fn media_types(&self) -> BTreeMap<&'static str, Vec<String>> {
+        let mut media_types: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+        for negotiated in self.resources.values() {
+            let Some(extension) = Path::new(&negotiated.source).extension() else {
+                continue;
+            };
+            let extension = extension.to_string_lossy().to_string();
+            let extensions = media_types.entry(negotiated.media_type).or_default();
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+            }
+        }
+        media_types
+    }
+}
+
+/// Escapes the characters of `text` that have a meaning in the regular
+/// expressions of Apache and nginx, and spaces, which end them.
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$ \"".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
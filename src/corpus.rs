@@ -0,0 +1,121 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::fingerprint;
+use crate::concordance;
+use crate::labels::{collisions, LabelUse};
+
+/// What the pages of other files need to know about an input file, kept in
+/// the build cache so that unchanged files need not be parsed ahead of the
+/// conversion again.
+#[derive(Serialize, Deserialize)]
+pub struct Outline {
+    /// Named subjects of the file, with their page and anchor.
+    pub subjects: Vec<concordance::Subject>,
+    /// Labels of the named subjects.
+    pub labels: Vec<LabelUse>,
+    /// Pairs of a named concept and a broader one, from `skos:broader` and
+    /// `skos:narrower`.
+    pub broader: Vec<(String, String)>,
+    /// Pairs of named concepts related by `skos:related`, in both orders.
+    pub related: Vec<(String, String)>,
+}
+
+/// What the pages know about the files of the whole site, gathered from
+/// their outlines ahead of the conversion. Empty when files are converted
+/// on their own.
+#[derive(Default)]
+pub struct Corpus {
+    files: usize,
+    /// Labels of the named subjects, by IRI; the first file wins.
+    labels: HashMap<String, String>,
+    /// The labels given to more than one term, across all files.
+    shared_labels: Vec<LabelUse>,
+    broader: BTreeSet<(String, String)>,
+    related: BTreeSet<(String, String)>,
+    fingerprint: String,
+}
+
+impl Corpus {
+    pub fn new(outlines: &[Outline]) -> Self {
+        let mut corpus = Corpus {
+            files: outlines.len(),
+            ..Default::default()
+        };
+        for outline in outlines {
+            for subject in &outline.subjects {
+                corpus
+                    .labels
+                    .entry(subject.iri.clone())
+                    .or_insert_with(|| subject.label.clone());
+            }
+            corpus.broader.extend(outline.broader.iter().cloned());
+            corpus.related.extend(outline.related.iter().cloned());
+        }
+        let labels: Vec<LabelUse> = outlines
+            .iter()
+            .flat_map(|outline| outline.labels.iter().cloned())
+            .collect();
+        corpus.shared_labels = collisions(&labels)
+            .into_iter()
+            .flat_map(|collision| collision.uses)
+            .cloned()
+            .collect();
+        let parts: Vec<Vec<u8>> = outlines
+            .iter()
+            .filter_map(|outline| serde_json::to_vec(outline).ok())
+            .collect();
+        corpus.fingerprint = fingerprint(parts.iter().map(Vec::as_slice));
+        corpus
+    }
+
+    /// Whether the corpus was gathered from no files at all.
+    pub fn is_empty(&self) -> bool {
+        self.files == 0
+    }
+
+    /// Label of the subject `iri` is described as in the corpus.
+    pub fn label(&self, iri: &str) -> Option<&str> {
+        self.labels.get(iri).map(String::as_str)
+    }
+
+    /// Uses of the labels given to more than one term across all files.
+    pub fn shared_labels(&self) -> &[LabelUse] {
+        &self.shared_labels
+    }
+
+    /// Pairs of a concept and a broader one across all files.
+    pub fn broader(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.broader.iter().map(|(a, b)| (a.as_str(), b.as_str()))
+    }
+
+    /// Pairs of related concepts across all files, in both orders.
+    pub fn related(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.related.iter().map(|(a, b)| (a.as_str(), b.as_str()))
+    }
+
+    /// Hash of the outlines the corpus was gathered from, which changes
+    /// whenever what the pages know about other files does.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+}
+
+/// Page and anchor of the named subjects of every outline by IRI, relative
+/// to the site root, for [`ConvertOptions::site_links`]. When several files
+/// describe a subject, the first one wins.
+///
+/// [`ConvertOptions::site_links`]: crate::parser::ConvertOptions::site_links
+pub fn site_links(outlines: &[Outline]) -> HashMap<String, String> {
+    let mut links = HashMap::new();
+    for subject in outlines.iter().flat_map(|outline| &outline.subjects) {
+        links
+            .entry(subject.iri.clone())
+            .or_insert_with(|| match &subject.anchor {
+                Some(anchor) => format!("{}#{}", subject.page, anchor),
+                None => subject.page.clone(),
+            });
+    }
+    links
+}
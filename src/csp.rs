@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+
+use crate::output::OutputBackend;
+
+/// Policy recommended for sites generated in strict CSP mode: pages only load
+/// stylesheets from the site itself and nothing else.
+pub const RECOMMENDED_POLICY: &str = "default-src 'none'; style-src 'self'; img-src 'self' data:; \
+     base-uri 'none'; form-action 'none'; frame-ancestors 'none'";
+
+/// Wraps another backend and rejects HTML pages containing inline styles or
+/// scripts, which a strict Content-Security-Policy would block.
+pub struct StrictCspOutput<'a> {
+    inner: &'a dyn OutputBackend,
+}
+
+impl<'a> StrictCspOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        StrictCspOutput { inner }
+    }
+
+    /// Writes `content-security-policy.txt` with the header to configure on
+    /// the web server. `scripts` allows the search page and the quick switcher
+    /// to run scripts from the site that fetch the search index, `forms`
+    /// allows the search form of the index to submit to the site, and
+    /// `analytics` allows the script of an analytics origin and its requests.
+    pub fn write_policy(
+        &self,
+        scripts: bool,
+        forms: bool,
+        analytics: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut policy = if forms {
+            RECOMMENDED_POLICY.replace("form-action 'none'", "form-action 'self'")
+        } else {
+            RECOMMENDED_POLICY.to_string()
+        };
+        let sources: Vec<&str> = scripts
+            .then_some("'self'")
+            .into_iter()
+            .chain(analytics)
+            .collect();
+        if !sources.is_empty() {
+            let sources = sources.join(" ");
+            policy.push_str(&format!("; script-src {0}; connect-src {0}", sources));
+        }
+        let snippet = format!("Content-Security-Policy: {}\n", policy);
+        self.inner
+            .write(Path::new("content-security-policy.txt"), snippet.as_bytes())
+    }
+}
+
+impl OutputBackend for StrictCspOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if relative_path.extension().and_then(|s| s.to_str()) == Some("html") {
+            if let Some(violation) = find_inline_content(&String::from_utf8_lossy(contents)) {
+                return Err(format!(
+                    "{:?} contains {} which is not allowed in strict CSP mode",
+                    relative_path, violation
+                )
+                .into());
+            }
+        }
+
+        self.inner.write(relative_path, contents)
+    }
+}
+
+/// The first element or attribute of `html` that a strict policy would block.
+fn find_inline_content(html: &str) -> Option<&'static str> {
+    let dom = parse_document(RcDom::default(), Default::default()).one(html);
+    find_in(&dom.document)
+}
+
+fn find_in(node: &Handle) -> Option<&'static str> {
+    if let NodeData::Element { name, attrs, .. } = &node.data {
+        let attrs = attrs.borrow();
+        let attr = |attr_name: &str| {
+            attrs
+                .iter()
+                .find(|a| &*a.name.local == attr_name)
+                .map(|a| a.value.to_string())
+        };
+
+        match &*name.local {
+            "style" => return Some("an inline <style> element"),
+            // JSON-LD is data that the browser does not run
+            "script"
+                if attr("src").is_none()
+                    && attr("type")
+                        .is_none_or(|t| !t.eq_ignore_ascii_case("application/ld+json")) =>
+            {
+                return Some("an inline <script> element")
+            }
+            _ => {}
+        }
+        for a in attrs.iter() {
+            let attr_name = &*a.name.local;
+            if attr_name == "style" {
+                return Some("an inline style attribute");
+            }
+            if attr_name.starts_with("on") {
+                return Some("an inline event handler");
+            }
+            if matches!(attr_name, "href" | "src" | "action" | "formaction")
+                && a.value
+                    .trim_start()
+                    .to_lowercase()
+                    .starts_with("javascript:")
+            {
+                return Some("a javascript: URL");
+            }
+        }
+    }
+
+    node.children.borrow().iter().find_map(find_in)
+}
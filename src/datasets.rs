@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use crate::output::OutputBackend;
+use crate::parser::IndexEntry;
+
+/// Statements of one graph across all input files.
+#[derive(Serialize)]
+struct GraphStats {
+    /// Graph IRI, or `None` for the default graph.
+    iri: Option<String>,
+    triples: usize,
+    sources: Vec<IndexEntry>,
+    last_modified: Option<String>,
+}
+
+#[derive(Default)]
+struct GraphSources {
+    triples: usize,
+    sources: Vec<IndexEntry>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+/// Collects per-graph statement counts and the files each graph was read
+/// from, for the datasets overview page.
+#[derive(Default)]
+pub struct DatasetStats {
+    graphs: BTreeMap<Option<String>, GraphSources>,
+}
+
+impl DatasetStats {
+    /// Records the graphs of one converted file. `source` links to the page
+    /// generated from it and `modified` is the time the file was last changed.
+    pub fn add(
+        &mut self,
+        graphs: &BTreeMap<Option<String>, usize>,
+        source: &IndexEntry,
+        modified: Option<DateTime<Utc>>,
+    ) {
+        for (graph, count) in graphs {
+            let stats = self.graphs.entry(graph.clone()).or_default();
+            stats.triples += count;
+            stats.sources.push(source.clone());
+            stats.last_modified = stats.last_modified.max(modified);
+        }
+    }
+
+    /// Whether any input used named graphs, which is when an overview is
+    /// worth writing.
+    pub fn has_named_graphs(&self) -> bool {
+        self.graphs.keys().any(Option::is_some)
+    }
+
+    /// Writes `datasets.html` with a row per graph.
+    pub fn write_overview(
+        &self,
+        output: &dyn OutputBackend,
+        tera: &Tera,
+        base_context: &Context,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let graphs: Vec<GraphStats> = self
+            .graphs
+            .iter()
+            .map(|(iri, stats)| GraphStats {
+                iri: iri.clone(),
+                triples: stats.triples,
+                sources: stats.sources.clone(),
+                last_modified: stats
+                    .last_modified
+                    .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()),
+            })
+            .collect();
+
+        let mut context = base_context.clone();
+        context.insert("title", "Datasets");
+        context.insert("root", "");
+        context.insert("graphs", &graphs);
+
+        let html = tera.render("datasets.html", &context)?;
+        output.write(Path::new("datasets.html"), html.as_bytes())
+    }
+}
@@ -0,0 +1,73 @@
+//! Display of literals according to their datatype.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// XML Schema datatypes whose values are numbers.
+const NUMERIC_TYPES: &[&str] = &[
+    "decimal",
+    "integer",
+    "double",
+    "float",
+    "long",
+    "int",
+    "short",
+    "byte",
+    "nonNegativeInteger",
+    "nonPositiveInteger",
+    "positiveInteger",
+    "negativeInteger",
+    "unsignedLong",
+    "unsignedInt",
+    "unsignedShort",
+    "unsignedByte",
+];
+
+/// How a literal of some datatype is shown, for templates to style it:
+/// `number`, `date` or `boolean`.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    Number,
+    Date,
+    Boolean,
+}
+
+/// The text shown for the literal `value` of `datatype`, and the kind of
+/// value it is, for the datatypes shown differently from their lexical
+/// form. Values that are not valid for their datatype are shown as written.
+pub fn display_value(value: &str, datatype: &str) -> Option<(String, ValueKind)> {
+    let local = datatype.strip_prefix(XSD)?;
+    match local {
+        "boolean" => match value.trim() {
+            "true" | "1" => Some(("✓ true".to_string(), ValueKind::Boolean)),
+            "false" | "0" => Some(("✗ false".to_string(), ValueKind::Boolean)),
+            _ => None,
+        },
+        "date" => NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .ok()
+            .map(|date| (date.format("%-d %B %Y").to_string(), ValueKind::Date)),
+        "dateTime" => {
+            let value = value.trim();
+            if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+                Some((
+                    time.format("%-d %B %Y, %H:%M:%S %:z").to_string(),
+                    ValueKind::Date,
+                ))
+            } else {
+                NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                    .ok()
+                    .map(|time| {
+                        (
+                            time.format("%-d %B %Y, %H:%M:%S").to_string(),
+                            ValueKind::Date,
+                        )
+                    })
+            }
+        }
+        _ if NUMERIC_TYPES.contains(&local) => Some((value.to_string(), ValueKind::Number)),
+        _ => None,
+    }
+}
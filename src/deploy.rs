@@ -0,0 +1,351 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use log::info;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use url::Url;
+use walkdir::WalkDir;
+
+use crate::cache::CACHE_FILE;
+use crate::checksums::{parse_sums, SUMS_FILE};
+use crate::http::RetryPolicy;
+
+pub trait DeployTarget {
+    fn deploy(&self, site_dir: &Path) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// Uploads the site with `rsync` over `ssh`. When both the site and the
+/// remote copy have a `SHA256SUMS` manifest, only the files whose checksums
+/// differ are transferred; otherwise rsync compares checksums on the remote
+/// side.
+pub struct RsyncTarget {
+    host: String,
+    user: Option<String>,
+    port: Option<u16>,
+    path: String,
+    identity: Option<PathBuf>,
+    delete: bool,
+}
+
+impl RsyncTarget {
+    fn from_url(url: &Url) -> Result<Self, Box<dyn std::error::Error>> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("Missing host in deploy target {}", url))?
+            .to_string();
+        let user = match url.username() {
+            "" => None,
+            user => Some(percent_decode_str(user).decode_utf8()?.into_owned()),
+        };
+
+        Ok(RsyncTarget {
+            host,
+            user,
+            port: url.port(),
+            path: percent_decode_str(url.path()).decode_utf8()?.into_owned(),
+            identity: None,
+            delete: false,
+        })
+    }
+
+    fn login(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    fn destination(&self) -> String {
+        format!("{}:{}", self.login(), self.path)
+    }
+
+    fn ssh_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(port) = self.port {
+            args.extend(["-p".to_string(), port.to_string()]);
+        }
+        if let Some(identity) = &self.identity {
+            args.extend(["-i".to_string(), identity.to_string_lossy().to_string()]);
+        }
+        args
+    }
+
+    /// The `ssh` command for rsync's `-e`, which rsync splits at spaces
+    /// outside of quotes.
+    fn ssh_command(&self) -> String {
+        std::iter::once("ssh".to_string())
+            .chain(self.ssh_args().iter().map(|arg| shell_quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The `SHA256SUMS` deployed last time, if there is one.
+    fn remote_sums(&self) -> Option<String> {
+        let path = format!("{}/{}", self.path.trim_end_matches('/'), SUMS_FILE);
+        let output = Command::new("ssh")
+            .args(self.ssh_args())
+            .arg(self.login())
+            .arg(format!("cat {}", shell_quote(&path)))
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// The files of the site whose checksums differ from those of the
+    /// remote copy, followed by the manifest itself, or `None` when either
+    /// manifest is missing.
+    fn changed_files(&self, site_dir: &Path) -> Option<Vec<String>> {
+        let local = parse_sums(&fs::read_to_string(site_dir.join(SUMS_FILE)).ok()?);
+        let remote = parse_sums(&self.remote_sums()?);
+        if local == remote {
+            return Some(Vec::new());
+        }
+        let mut changed: Vec<String> = local
+            .into_iter()
+            .filter(|(path, sum)| remote.get(path) != Some(sum))
+            .map(|(path, _)| path)
+            .collect();
+        changed.push(SUMS_FILE.to_string());
+        Some(changed)
+    }
+}
+
+/// `arg` in single quotes, for a POSIX shell and for rsync's `-e`.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r#"'"'"'"#))
+}
+
+impl DeployTarget for RsyncTarget {
+    fn deploy(&self, site_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        // the trailing slash makes rsync copy the directory contents rather than
+        // the directory itself
+        let source = format!("{}/", site_dir.display());
+
+        let mut command = Command::new("rsync");
+        command
+            .arg("--recursive")
+            .arg("--compress")
+            .arg("--checksum")
+            .arg("--itemize-changes")
+            // keeps the remote shell from splitting the destination path at spaces
+            .arg("--protect-args")
+            .arg(format!("--exclude=/{}", CACHE_FILE))
+            .arg("-e")
+            .arg(self.ssh_command());
+        // removing remote files takes a comparison of the whole tree
+        let changed = if self.delete {
+            command.arg("--delete");
+            None
+        } else {
+            self.changed_files(site_dir)
+        };
+        if changed.as_ref().is_some_and(Vec::is_empty) {
+            info!("{} is up to date", self.destination());
+            return Ok(());
+        }
+        if changed.is_some() {
+            command.arg("--files-from=-").stdin(Stdio::piped());
+        }
+        command.arg(source).arg(self.destination());
+
+        info!("Deploying {:?} to {}", site_dir, self.destination());
+        let mut child = command.spawn()?;
+        if let Some(changed) = changed {
+            info!("Uploading {} changed file(s)", changed.len());
+            let mut stdin = child.stdin.take().unwrap();
+            for path in changed {
+                writeln!(stdin, "{}", path)?;
+            }
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(format!("rsync exited with {}", status).into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds the site to IPFS through the HTTP API of a local or remote node
+/// (`/api/v0/add`) and prints the CID of the resulting root directory, also
+/// writing it to `cid_file` when set.
+pub struct IpfsTarget {
+    api: Url,
+    retry: RetryPolicy,
+    cid_file: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct IpfsAddEntry {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+impl IpfsTarget {
+    fn from_url(url: &Url) -> Result<Self, Box<dyn std::error::Error>> {
+        let scheme = if url.scheme() == "ipfs+https" {
+            "https"
+        } else {
+            "http"
+        };
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("Missing host in deploy target {}", url))?;
+        let port = url.port().unwrap_or(5001);
+
+        let api = Url::parse(&format!(
+            "{}://{}:{}/api/v0/add?pin=true&cid-version=1",
+            scheme, host, port
+        ))?;
+
+        Ok(IpfsTarget {
+            api,
+            retry: RetryPolicy::default(),
+            cid_file: None,
+        })
+    }
+}
+
+impl DeployTarget for IpfsTarget {
+    fn deploy(&self, site_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let root = site_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "site".to_string());
+        let boundary = "ttl2html-ipfs-boundary";
+        let mut body = Vec::new();
+
+        // every file is added under a common root directory, so the CID of that
+        // directory addresses the whole site
+        for entry in WalkDir::new(site_dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name() != CACHE_FILE)
+        {
+            let relative = entry.path().strip_prefix(site_dir)?;
+            let name = if relative.as_os_str().is_empty() {
+                root.clone()
+            } else {
+                format!("{}/{}", root, relative.to_string_lossy())
+            };
+            let name = utf8_percent_encode(&name, NON_ALPHANUMERIC).to_string();
+
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            if entry.file_type().is_dir() {
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+                         Content-Type: application/x-directory\r\n\r\n",
+                        name
+                    )
+                    .as_bytes(),
+                );
+            } else {
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+                         Content-Type: application/octet-stream\r\n\r\n",
+                        name
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(&fs::read(entry.path())?);
+            }
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+        info!("Adding {:?} to IPFS via {}", site_dir, self.api);
+        let response = self
+            .retry
+            .send(&format!("POST {}", self.api), Some(&body), || {
+                ureq::post(self.api.as_str()).set(
+                    "Content-Type",
+                    &format!("multipart/form-data; boundary={}", boundary),
+                )
+            })?
+            .into_string()?;
+
+        // the API answers with one JSON object per added entry
+        for line in response.lines().filter(|line| !line.trim().is_empty()) {
+            let entry: IpfsAddEntry = serde_json::from_str(line)?;
+            if entry.name == root {
+                // printed even with --quiet, so that scripts can capture it
+                println!("{}", entry.hash);
+                if let Some(cid_file) = &self.cid_file {
+                    fs::write(cid_file, format!("{}\n", entry.hash))?;
+                }
+                return Ok(());
+            }
+        }
+
+        Err("IPFS node did not report a CID for the site root".into())
+    }
+}
+
+/// Builds a deploy target from a URL such as `ssh://user@host:22/srv/www` or
+/// `ipfs://127.0.0.1:5001`. The URL scheme selects the target implementation.
+pub fn target_from_url(
+    target: &str,
+    identity: Option<&Path>,
+    delete: bool,
+    cid_file: Option<&Path>,
+    retry: RetryPolicy,
+) -> Result<Box<dyn DeployTarget>, Box<dyn std::error::Error>> {
+    let url = Url::parse(target)?;
+
+    match url.scheme() {
+        "ssh" | "rsync+ssh" => {
+            let mut rsync = RsyncTarget::from_url(&url)?;
+            rsync.identity = identity.map(Path::to_path_buf);
+            rsync.delete = delete;
+            Ok(Box::new(rsync))
+        }
+        "ipfs" | "ipfs+https" => {
+            let mut ipfs = IpfsTarget::from_url(&url)?;
+            ipfs.retry = retry;
+            ipfs.cid_file = cid_file.map(Path::to_path_buf);
+            Ok(Box::new(ipfs))
+        }
+        scheme => Err(format!("Unsupported deploy target scheme: {}", scheme).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rsync_target(url: &str) -> RsyncTarget {
+        RsyncTarget::from_url(&Url::parse(url).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn rsync_paths_are_decoded() {
+        let target = rsync_target("ssh://deploy@example.org/srv/my%20site/caf%C3%A9");
+
+        assert_eq!(target.path, "/srv/my site/café");
+        assert_eq!(target.destination(), "deploy@example.org:/srv/my site/café");
+    }
+
+    #[test]
+    fn rsync_users_are_decoded() {
+        let target = rsync_target("ssh://ci%40build@example.org/srv/www");
+
+        assert_eq!(target.login(), "ci@build@example.org");
+    }
+
+    #[test]
+    fn invalid_utf8_paths_are_rejected() {
+        assert!(RsyncTarget::from_url(&Url::parse("ssh://example.org/srv/%FF").unwrap()).is_err());
+    }
+}
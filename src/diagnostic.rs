@@ -0,0 +1,94 @@
+use std::fmt;
+
+use oxttl::{TextPosition, TurtleSyntaxError};
+use serde::{Deserialize, Serialize};
+
+/// An error in an input file, located by line and column (both counted from
+/// 1) with the offending source line underlined.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Diagnostic {
+    pub line: u64,
+    pub column: u64,
+    pub message: String,
+    /// The source line and a caret line marking the error, ready to print.
+    pub snippet: String,
+}
+
+impl Diagnostic {
+    /// Locates a parser error in `input`, the text that was parsed.
+    pub fn from_syntax_error(error: &TurtleSyntaxError, input: &str) -> Self {
+        let location = error.location();
+        Diagnostic::new(input, location.start, location.end, error.message())
+    }
+
+    /// An error spanning `start` to `end`, positions counted from 0, in
+    /// `input`.
+    pub fn new(input: &str, start: TextPosition, end: TextPosition, message: &str) -> Self {
+        Diagnostic {
+            line: start.line + 1,
+            column: start.column + 1,
+            message: message.to_string(),
+            snippet: snippet(input, start, end),
+        }
+    }
+
+    /// An error at byte `offset` of `input`, for parsers that report no
+    /// line and column.
+    pub fn at_offset(input: &str, offset: usize, message: &str) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let position = TextPosition {
+            line: before.matches('\n').count() as u64,
+            column: before[line_start..].chars().count() as u64,
+            offset: offset as u64,
+        };
+        Diagnostic::new(input, position, position, message)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}\n{}",
+            self.line, self.column, self.message, self.snippet
+        )
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// The line holding `start`, numbered, with carets under `start..end`, or
+/// up to the end of the line when the error spans several lines.
+fn snippet(input: &str, start: TextPosition, end: TextPosition) -> String {
+    let source = input.lines().nth(start.line as usize).unwrap_or_default();
+    let number = (start.line + 1).to_string();
+    let line_length = source.chars().count() as u64;
+
+    let end_column = if end.line == start.line {
+        end.column
+    } else {
+        line_length
+    };
+    let width = end_column.saturating_sub(start.column).max(1);
+    // tabs are kept so that the carets line up with the source
+    let indent: String = source
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(start.column as usize)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!(
+        "{} | {}\n{} | {}{}",
+        number,
+        source,
+        " ".repeat(number.len()),
+        indent,
+        "^".repeat(width as usize)
+    )
+}
@@ -0,0 +1,87 @@
+//! `--dry-run`: the site is converted as usual, but its files are only
+//! listed instead of written.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{info, warn};
+
+use crate::output::{url_path, OutputBackend};
+
+/// Records the paths written to it, and the input file each page is made
+/// from, and discards their contents.
+#[derive(Default)]
+pub struct DryRunOutput {
+    files: Mutex<BTreeSet<PathBuf>>,
+    /// Input file of every file written for one, by output path.
+    inputs: Mutex<BTreeMap<PathBuf, PathBuf>>,
+    collisions: Mutex<usize>,
+}
+
+impl DryRunOutput {
+    /// Logs that `input` would be converted to `outputs`, and warns about
+    /// the outputs another input was converted to already.
+    pub fn plan(&self, input: &Path, outputs: &[PathBuf]) {
+        let names: Vec<String> = outputs.iter().map(|output| url_path(output)).collect();
+        info!("Would convert {:?} to {}", input, names.join(", "));
+
+        let mut inputs = self.inputs.lock().unwrap();
+        let mut collisions = self.collisions.lock().unwrap();
+        for output in outputs {
+            if let Some(other) = inputs.insert(output.clone(), input.to_path_buf()) {
+                warn!(
+                    "Collision: {} would be written for both {:?} and {:?}",
+                    url_path(output),
+                    other,
+                    input
+                );
+                *collisions += 1;
+            }
+        }
+    }
+
+    /// Logs the files that would be written besides those of the inputs,
+    /// and how many files in all, and of those how many exist in
+    /// `output_dir` already. Existing files can only be told apart in a
+    /// local directory. Fails if two inputs would write the same file.
+    pub fn report(&self, output_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let files = self.files.lock().unwrap();
+        let inputs = self.inputs.lock().unwrap();
+        for file in files.iter().filter(|file| !inputs.contains_key(*file)) {
+            info!("Would write {}", url_path(file));
+        }
+        if output_dir.starts_with("s3://") {
+            info!(
+                "Dry run: would write {} file(s) to {}",
+                files.len(),
+                output_dir
+            );
+        } else {
+            let existing = files
+                .iter()
+                .filter(|file| Path::new(output_dir).join(file).exists())
+                .count();
+            info!(
+                "Dry run: would write {} file(s) to {}, {} of them replacing existing files",
+                files.len(),
+                output_dir,
+                existing
+            );
+        }
+        match *self.collisions.lock().unwrap() {
+            0 => Ok(()),
+            collisions => Err(format!("{} output path collision(s)", collisions).into()),
+        }
+    }
+}
+
+impl OutputBackend for DryRunOutput {
+    fn write(&self, relative_path: &Path, _: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(relative_path.to_path_buf());
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Errors of a run as JSON, for pipelines that surface failures without
+//! scraping the output of the converter.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::diagnostic::Diagnostic;
+
+/// What kind of error stopped the conversion of a file.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The input is not valid Turtle, TriG, N-Triples, N-Quads or RDF/XML.
+    Syntax,
+    /// The input could not be read or the output not written.
+    Io,
+    /// A template failed to render.
+    Template,
+    Other,
+}
+
+/// Why converting a file failed. Unlike the error it was made from, it can
+/// be sent between threads.
+#[derive(Debug, Clone)]
+pub struct FileError {
+    pub kind: ErrorKind,
+    /// The error as it is printed.
+    pub message: String,
+    /// Location of a syntax error in the input.
+    pub diagnostic: Option<Diagnostic>,
+}
+
+impl FileError {
+    pub fn new(error: &(dyn std::error::Error + 'static)) -> Self {
+        let diagnostic = error.downcast_ref::<Diagnostic>().cloned();
+        let kind = if diagnostic.is_some() {
+            ErrorKind::Syntax
+        } else if error.is::<std::io::Error>() {
+            ErrorKind::Io
+        } else if error.is::<tera::Error>() {
+            ErrorKind::Template
+        } else {
+            ErrorKind::Other
+        };
+        FileError {
+            kind,
+            message: error.to_string(),
+            diagnostic,
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// One error of the report.
+#[derive(Serialize)]
+struct ReportedError {
+    /// Input file, relative to the input directory.
+    file: String,
+    kind: ErrorKind,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<u64>,
+}
+
+impl ReportedError {
+    fn new(file: String, kind: ErrorKind, message: &str, diagnostic: Option<&Diagnostic>) -> Self {
+        ReportedError {
+            file,
+            kind,
+            // the location has fields of its own
+            message: diagnostic
+                .map_or(message, |d| d.message.as_str())
+                .to_string(),
+            line: diagnostic.map(|d| d.line),
+            column: diagnostic.map(|d| d.column),
+        }
+    }
+}
+
+/// The files that failed to convert, and the errors recovered from in the
+/// others, written with `--error-report`.
+#[derive(Serialize, Default)]
+pub struct ErrorReport {
+    failed: Vec<ReportedError>,
+    recovered: Vec<ReportedError>,
+}
+
+impl ErrorReport {
+    /// Records that `file` could not be converted.
+    pub fn add_failed(&mut self, file: String, error: &FileError) {
+        self.failed.push(ReportedError::new(
+            file,
+            error.kind,
+            &error.message,
+            error.diagnostic.as_ref(),
+        ));
+    }
+
+    /// Records the syntax errors `file` was converted despite.
+    pub fn add_recovered(&mut self, file: String, errors: &[Diagnostic]) {
+        for error in errors {
+            self.recovered.push(ReportedError::new(
+                file.clone(),
+                ErrorKind::Syntax,
+                &error.message,
+                Some(error),
+            ));
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| format!("Cannot write error report {}: {}", path.display(), e).into())
+    }
+}
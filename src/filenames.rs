@@ -0,0 +1,43 @@
+//! Output file names made safe for URLs and every file system, for
+//! `--slugify`.
+
+use std::path::{Component, Path, PathBuf};
+
+use slug::slugify;
+
+/// `name` transliterated to ASCII, lowercased, and with every run of
+/// spaces and reserved characters replaced by a dash. Names with nothing
+/// left become `file`.
+pub fn slugify_name(name: &str) -> String {
+    match slugify(name) {
+        slug if slug.is_empty() => "file".to_string(),
+        slug => slug,
+    }
+}
+
+/// `path` with every directory name and file stem transliterated to ASCII,
+/// lowercased, and with spaces and reserved characters replaced by dashes,
+/// as in `Café Menu/Plats du jour.ttl` to `cafe-menu/plats-du-jour.ttl`.
+pub fn slugify_path(path: &Path) -> PathBuf {
+    let mut slugged = PathBuf::new();
+    let mut components = path.components().peekable();
+    while let Some(component) = components.next() {
+        let Component::Normal(name) = component else {
+            slugged.push(component);
+            continue;
+        };
+        let name = Path::new(name);
+        let is_file = components.peek().is_none();
+        let stem = match name.file_stem() {
+            Some(stem) if is_file => stem,
+            _ => name.as_os_str(),
+        };
+        let mut slug = slugify_name(&stem.to_string_lossy());
+        if let Some(extension) = name.extension().filter(|_| is_file) {
+            slug.push('.');
+            slug.push_str(&extension.to_string_lossy().to_lowercase());
+        }
+        slugged.push(slug);
+    }
+    slugged
+}
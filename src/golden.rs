@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use walkdir::WalkDir;
+
+use crate::output::{url_path, OutputBackend};
+use crate::parser::{generate_index, ConvertOptions, IndexEntry};
+use crate::{base_context, convert_file, input_files, templates};
+
+/// Lines of unchanged context around each change in a diff.
+const CONTEXT: usize = 3;
+
+/// Beyond this many changed lines a diff is not computed, to keep memory
+/// bounded on completely rewritten files.
+const MAX_EDITS: usize = 2000;
+
+/// Backend keeping the generated files in memory, to compare them with a
+/// golden output tree.
+#[derive(Default)]
+pub struct MemoryOutput {
+    files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryOutput {
+    pub fn into_files(self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.files.into_inner().unwrap()
+    }
+}
+
+impl OutputBackend for MemoryOutput {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(relative_path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+}
+
+/// How a generated file differs from the golden tree.
+pub enum Difference {
+    /// The golden tree has a file that was not generated.
+    Missing(PathBuf),
+    /// A file was generated that the golden tree does not have.
+    Unexpected(PathBuf),
+    /// The file differs; `diff` is a unified diff, or a note when the file
+    /// is not text or changed too much to diff.
+    Changed { path: PathBuf, diff: String },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difference::Missing(path) => write!(f, "Missing from output: {}", path.display()),
+            Difference::Unexpected(path) => {
+                write!(f, "Not in golden output: {}", path.display())
+            }
+            Difference::Changed { path, diff } => {
+                write!(f, "Changed: {}\n{}", path.display(), diff)
+            }
+        }
+    }
+}
+
+/// Compares generated files, by path relative to the site root, with the
+/// files in `golden_dir`.
+pub fn compare(
+    files: &BTreeMap<PathBuf, Vec<u8>>,
+    golden_dir: &Path,
+) -> Result<Vec<Difference>, Box<dyn std::error::Error>> {
+    let golden = golden_files(golden_dir)?;
+    let mut differences = Vec::new();
+
+    for path in golden.iter().filter(|path| !files.contains_key(*path)) {
+        differences.push(Difference::Missing(path.clone()));
+    }
+    for (path, contents) in files {
+        if !golden.contains(path) {
+            differences.push(Difference::Unexpected(path.clone()));
+            continue;
+        }
+
+        let expected = fs::read(golden_dir.join(path))?;
+        if expected != *contents {
+            let name = path.display().to_string();
+            let diff = match (
+                std::str::from_utf8(&expected),
+                std::str::from_utf8(contents),
+            ) {
+                (Ok(old), Ok(new)) => unified_diff(old, new, &name),
+                _ => "Binary files differ\n".to_string(),
+            };
+            differences.push(Difference::Changed {
+                path: path.clone(),
+                diff,
+            });
+        }
+    }
+
+    Ok(differences)
+}
+
+/// Converts the files below `input_dir` with the built-in templates, as
+/// [`convert_file`] and [`generate_index`] do, and returns the files written
+/// by path relative to the site root.
+pub fn render(
+    input_dir: &str,
+    options: &ConvertOptions,
+) -> Result<BTreeMap<PathBuf, Vec<u8>>, Box<dyn std::error::Error>> {
+    let output = MemoryOutput::default();
+    let tera = templates();
+    let context = base_context();
+
+    let mut entries = Vec::new();
+    for path in input_files(input_dir) {
+        let converted = convert_file(&path, input_dir, &output, &tera, &context, options)?;
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        entries.push(IndexEntry::new(url_path(&converted.path), name));
+    }
+    generate_index(&output, &entries, &tera, &context, options)?;
+
+    Ok(output.into_files())
+}
+
+/// Renders the files below `input_dir` and panics with the differences
+/// unless the result matches `golden_dir`, for regression tests of pages.
+/// With `UPDATE_GOLDEN` set in the environment, `golden_dir` is replaced
+/// with the result instead.
+pub fn assert_matches_golden(input_dir: &str, golden_dir: &Path, options: &ConvertOptions) {
+    let files = render(input_dir, options)
+        .unwrap_or_else(|e| panic!("Converting {:?} failed: {}", input_dir, e));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        update(&files, golden_dir)
+            .unwrap_or_else(|e| panic!("Updating {:?} failed: {}", golden_dir, e));
+        return;
+    }
+
+    let differences = compare(&files, golden_dir)
+        .unwrap_or_else(|e| panic!("Reading {:?} failed: {}", golden_dir, e));
+    if !differences.is_empty() {
+        let report: Vec<String> = differences.iter().map(Difference::to_string).collect();
+        panic!(
+            "{} file(s) differ from {:?}:\n{}",
+            differences.len(),
+            golden_dir,
+            report.join("\n")
+        );
+    }
+}
+
+/// Replaces the golden tree with the generated files, removing golden files
+/// that are no longer generated.
+pub fn update(
+    files: &BTreeMap<PathBuf, Vec<u8>>,
+    golden_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if golden_dir.exists() {
+        for path in golden_files(golden_dir)? {
+            if !files.contains_key(&path) {
+                fs::remove_file(golden_dir.join(path))?;
+            }
+        }
+    }
+    for (path, contents) in files {
+        let path = golden_dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+    }
+
+    Ok(())
+}
+
+fn golden_files(golden_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut paths = Vec::new();
+    for entry in WalkDir::new(golden_dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            paths.push(entry.path().strip_prefix(golden_dir)?.to_path_buf());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff between the golden (`a/`) and generated (`b/`) versions of
+/// the file `name`.
+pub fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let Some(edits) = diff_lines(&old, &new) else {
+        return format!("More than {} lines changed\n", MAX_EDITS);
+    };
+
+    // position in both files before each edit
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut a, mut b) = (0, 0);
+    for edit in &edits {
+        positions.push((a, b));
+        match edit {
+            Edit::Equal => {
+                a += 1;
+                b += 1;
+            }
+            Edit::Delete => a += 1,
+            Edit::Insert => b += 1,
+        }
+    }
+
+    // hunks span the changes plus their context, merged when they touch
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in edits.iter().enumerate().filter(|(_, e)| **e != Edit::Equal) {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(edits.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", name, name);
+    for (start, end) in hunks {
+        let hunk = &edits[start..end];
+        let old_count = hunk.iter().filter(|e| **e != Edit::Insert).count();
+        let new_count = hunk.iter().filter(|e| **e != Edit::Delete).count();
+        let (a, b) = positions[start];
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            a + usize::from(old_count > 0),
+            old_count,
+            b + usize::from(new_count > 0),
+            new_count
+        ));
+        for (edit, (a, b)) in hunk.iter().zip(&positions[start..end]) {
+            match edit {
+                Edit::Equal => diff.push_str(&format!(" {}\n", old[*a])),
+                Edit::Delete => diff.push_str(&format!("-{}\n", old[*a])),
+                Edit::Insert => diff.push_str(&format!("+{}\n", new[*b])),
+            }
+        }
+    }
+
+    diff
+}
+
+/// Shortest edit script between two sequences of lines (Myers' algorithm),
+/// or `None` when it needs more than `MAX_EDITS` edits.
+fn diff_lines(old: &[&str], new: &[&str]) -> Option<Vec<Edit>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m).min(MAX_EDITS as isize);
+    // furthest x reached on each diagonal k = x - y, offset by max + 1
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // the reachable part of `v` before each step, to backtrack through
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    let mut found = false;
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let i = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[i - 1] < v[i + 1]) {
+                v[i + 1]
+            } else {
+                v[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        // `v` holds diagonals -d..=d of the previous step
+        let at = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let previous_k = if d == 0 {
+            0
+        } else if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let (previous_x, previous_y) = if d == 0 {
+            (0, 0)
+        } else {
+            (at(previous_k), at(previous_k) - previous_k)
+        };
+
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == previous_x {
+                Edit::Insert
+            } else {
+                Edit::Delete
+            });
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+    edits.reverse();
+
+    Some(edits)
+}
@@ -0,0 +1,175 @@
+//! Syntax highlighting of Turtle, TriG, N-Triples and N-Quads sources for
+//! the source view of a page.
+
+use crate::escape::escape_html;
+use crate::parser::InputFormat;
+
+/// The SPARQL-style keywords of the Turtle family, matched without regard to
+/// case.
+const KEYWORDS: &[&str] = &["PREFIX", "BASE", "GRAPH"];
+
+/// The lines of `source` as HTML, with every token in a `span` whose class
+/// names its kind. Tokens spanning lines, such as long strings, are closed at
+/// the end of each line and reopened on the next, so that every line stands
+/// on its own. RDF/XML is not highlighted.
+pub fn highlight_lines(source: &str, format: InputFormat) -> Vec<String> {
+    if format == InputFormat::RdfXml {
+        return source
+            .lines()
+            .map(|line| escape_html(line.strip_suffix('\r').unwrap_or(line)))
+            .collect();
+    }
+    let mut lines = vec![String::new()];
+    for (class, text) in tokens(source) {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            let part = part.strip_suffix('\r').unwrap_or(part);
+            if part.is_empty() {
+                continue;
+            }
+            let line = lines.last_mut().expect("there is always a line");
+            match class {
+                Some(class) => {
+                    line.push_str(&format!(
+                        "<span class=\"syntax-{}\">{}</span>",
+                        class,
+                        escape_html(part)
+                    ));
+                }
+                None => line.push_str(&escape_html(part)),
+            }
+        }
+    }
+    // a final line break does not start another line
+    if source.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+}
+
+/// Splits `source` into tokens, each with the class it is highlighted with,
+/// if any. Anything not recognised, such as punctuation, is passed through
+/// as it is, so the tokens always add up to `source`.
+fn tokens(source: &str) -> Vec<(Option<&'static str>, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        let (class, length) = match c {
+            '#' => (Some("comment"), rest.find('\n').unwrap_or(rest.len())),
+            '<' => match rest.find(|c: char| c == '>' || c.is_whitespace()) {
+                Some(end) if rest[end..].starts_with('>') => (Some("iri"), end + 1),
+                _ => (None, 1),
+            },
+            '"' | '\'' => (Some("string"), string_length(rest, c)),
+            '@' => {
+                let length = 1 + word_length(&rest[1..], |c| c.is_ascii_alphanumeric() || c == '-');
+                let word = &rest[1..length];
+                if word == "prefix" || word == "base" {
+                    (Some("keyword"), length)
+                } else {
+                    (Some("language"), length)
+                }
+            }
+            '_' if rest[1..].starts_with(':') => (Some("blank"), 2 + name_length(&rest[2..])),
+            _ if starts_number(rest) => (Some("literal"), number_length(rest)),
+            c if c.is_alphabetic() || c == ':' => {
+                let length = name_length(rest);
+                let word = &rest[..length];
+                if word.contains(':') {
+                    (Some("name"), length)
+                } else if word == "true" || word == "false" {
+                    (Some("literal"), length)
+                } else if word == "a"
+                    || KEYWORDS
+                        .iter()
+                        .any(|keyword| keyword.eq_ignore_ascii_case(word))
+                {
+                    (Some("keyword"), length)
+                } else {
+                    (None, length)
+                }
+            }
+            c => (None, c.len_utf8()),
+        };
+        tokens.push((class, &rest[..length]));
+        rest = &rest[length..];
+    }
+    tokens
+}
+
+/// Length of the string literal `rest` starts with, quoted with `quote`,
+/// long or short, up to and including its closing quotes or, if it is not
+/// closed, the end of its line or of the source.
+fn string_length(rest: &str, quote: char) -> usize {
+    let long: String = [quote; 3].iter().collect();
+    let (open, close) = if rest.starts_with(&long) {
+        (3, long.as_str())
+    } else {
+        (1, &rest[..1])
+    };
+    let mut escaped = false;
+    for (i, c) in rest[open..].char_indices() {
+        let position = open + i;
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '\n' && open == 1 {
+            return position;
+        } else if rest[position..].starts_with(close) {
+            return position + close.len();
+        }
+    }
+    rest.len()
+}
+
+/// Length of the prefixed name, blank node label or keyword `rest` starts
+/// with. A trailing `.` ends the statement rather than the name.
+fn name_length(rest: &str) -> usize {
+    let length = word_length(rest, |c| {
+        c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.' | '%' | '\\')
+    });
+    rest[..length].trim_end_matches('.').len()
+}
+
+/// Whether `rest` starts with a number, which may have a sign and start
+/// with its decimal point.
+fn starts_number(rest: &str) -> bool {
+    let unsigned = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    let digits = unsigned.strip_prefix('.').unwrap_or(unsigned);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Length of the integer, decimal or double `rest` starts with.
+fn number_length(rest: &str) -> usize {
+    let bytes = rest.as_bytes();
+    let mut length = 0;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        length += 1;
+    }
+    length += word_length(&rest[length..], |c| c.is_ascii_digit());
+    // a `.` followed by a digit is a decimal point, otherwise it ends the
+    // statement
+    if bytes.get(length) == Some(&b'.') && bytes.get(length + 1).is_some_and(u8::is_ascii_digit) {
+        length += 1 + word_length(&rest[length + 1..], |c| c.is_ascii_digit());
+    }
+    if matches!(bytes.get(length), Some(b'e' | b'E')) {
+        let mut exponent = length + 1;
+        if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+            exponent += 1;
+        }
+        let digits = word_length(&rest[exponent..], |c| c.is_ascii_digit());
+        if digits > 0 {
+            length = exponent + digits;
+        }
+    }
+    length
+}
+
+/// Length of the run of characters matching `accept` that `rest` starts
+/// with.
+fn word_length(rest: &str, accept: impl Fn(char) -> bool) -> usize {
+    rest.find(|c: char| !accept(c)).unwrap_or(rest.len())
+}
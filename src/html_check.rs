@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use html5ever::driver::ParseOpts;
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use html5ever::tree_builder::TreeBuilderOpts;
+use log::warn;
+use markup5ever_rcdom::RcDom;
+
+use crate::output::OutputBackend;
+
+/// Wraps another backend and parses every HTML page written through it with
+/// an HTML5 parser, remembering the parse errors of malformed pages.
+pub struct HtmlCheckOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    problems: Mutex<Vec<(PathBuf, Vec<String>)>>,
+}
+
+impl<'a> HtmlCheckOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        HtmlCheckOutput {
+            inner,
+            problems: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Prints the malformed pages found so far and returns how many there were.
+    pub fn report(&self) -> usize {
+        let problems = self.problems.lock().unwrap();
+
+        for (path, errors) in problems.iter() {
+            warn!("Malformed HTML in {:?}:", path);
+            for error in errors {
+                warn!("  {}", error);
+            }
+        }
+
+        problems.len()
+    }
+}
+
+impl OutputBackend for HtmlCheckOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if relative_path.extension().and_then(|s| s.to_str()) == Some("html") {
+            let errors = html_errors(&String::from_utf8_lossy(contents));
+            if !errors.is_empty() {
+                self.problems
+                    .lock()
+                    .unwrap()
+                    .push((relative_path.to_path_buf(), errors));
+            }
+        }
+
+        self.inner.write(relative_path, contents)
+    }
+}
+
+fn html_errors(html: &str) -> Vec<String> {
+    let opts = ParseOpts {
+        tree_builder: TreeBuilderOpts {
+            exact_errors: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let dom = parse_document(RcDom::default(), opts).one(html);
+    let errors = dom.errors.iter().map(|e| e.to_string()).collect();
+    errors
+}
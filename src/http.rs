@@ -0,0 +1,78 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+/// First delay between attempts; it doubles after every failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// How requests to remote services are retried. Connection failures, `429`
+/// and `5xx` responses are retried with exponential backoff until the retries
+/// are used up or the timeout, which covers all attempts together, expires.
+#[derive(Clone, Copy)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            retries: 3,
+            timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sends `body`, if any, with a request built afresh by `request` for
+    /// every attempt. `description` names the operation in the error reported once
+    /// all attempts have failed.
+    pub fn send<F>(
+        &self,
+        description: &str,
+        body: Option<&[u8]>,
+        request: F,
+    ) -> Result<ureq::Response, Box<dyn std::error::Error>>
+    where
+        F: Fn() -> ureq::Request,
+    {
+        let deadline = Instant::now() + self.timeout;
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let request = request().timeout(remaining);
+            let result = match body {
+                Some(body) => request.send_bytes(body),
+                None => request.call(),
+            };
+            let error = match result {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            let retryable = match &error {
+                ureq::Error::Status(status, _) => *status == 429 || *status >= 500,
+                ureq::Error::Transport(_) => true,
+            };
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !retryable || attempt > self.retries || remaining <= backoff {
+                return Err(format!(
+                    "{} failed after {} attempt(s): {}",
+                    description, attempt, error
+                )
+                .into());
+            }
+
+            warn!(
+                "{} failed ({}), retrying in {:?}",
+                description, error, backoff
+            );
+            thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Statements of a page as JSON-LD, embedded for search engines and other
+//! clients reading the data along with the page.
+
+use std::collections::BTreeMap;
+
+use oxrdf::vocab::{rdf, xsd};
+use oxrdf::{BlankNode, GraphName, NamedOrBlankNode, Quad, Term};
+use serde_json::{json, Map, Value};
+
+/// Node objects of expanded JSON-LD, by graph and by the `@id` of the node
+/// they describe.
+#[derive(Default)]
+pub struct JsonLd {
+    graphs: BTreeMap<Option<String>, BTreeMap<String, Map<String, Value>>>,
+}
+
+impl JsonLd {
+    /// The node objects of `quads`, with blank nodes named by `label` so
+    /// that they match the entries of the page. Statements about triple
+    /// terms have no JSON-LD form and are left out.
+    pub fn from_quads(quads: &[Quad], mut label: impl FnMut(&BlankNode) -> String) -> Self {
+        let mut json_ld = JsonLd::default();
+        for quad in quads {
+            let object = match &quad.object {
+                Term::NamedNode(iri) => json!({ "@id": iri.as_str() }),
+                Term::BlankNode(blank) => json!({ "@id": label(blank) }),
+                Term::Literal(literal) => {
+                    let mut value = Map::new();
+                    value.insert("@value".to_string(), literal.value().into());
+                    if let Some(language) = literal.language() {
+                        value.insert("@language".to_string(), language.into());
+                        if let Some(direction) = literal.direction() {
+                            value.insert("@direction".to_string(), direction.to_string().into());
+                        }
+                    } else if literal.datatype() != xsd::STRING {
+                        value.insert("@type".to_string(), literal.datatype().as_str().into());
+                    }
+                    Value::Object(value)
+                }
+                Term::Triple(_) => continue,
+            };
+            let graph = match &quad.graph_name {
+                GraphName::NamedNode(iri) => Some(iri.as_str().to_string()),
+                GraphName::BlankNode(blank) => Some(label(blank)),
+                GraphName::DefaultGraph => None,
+            };
+            let subject = match &quad.subject {
+                NamedOrBlankNode::NamedNode(iri) => iri.as_str().to_string(),
+                NamedOrBlankNode::BlankNode(blank) => label(blank),
+            };
+            let node = json_ld
+                .graphs
+                .entry(graph)
+                .or_default()
+                .entry(subject.clone())
+                .or_insert_with(|| Map::from_iter([("@id".to_string(), subject.into())]));
+
+            let (key, value) = match &quad.object {
+                Term::NamedNode(iri) if quad.predicate == rdf::TYPE => {
+                    ("@type".to_string(), iri.as_str().into())
+                }
+                _ => (quad.predicate.as_str().to_string(), object),
+            };
+            let values = node.entry(key).or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(values) = values {
+                values.push(value);
+            }
+        }
+        json_ld
+    }
+
+    /// Every statement, as a JSON-LD document; `None` without statements.
+    pub fn document(&self) -> Option<String> {
+        self.render(|nodes| nodes.values().collect())
+    }
+
+    /// The statements about `subject` and about the blank nodes it refers
+    /// to, directly or through other blank nodes, as a JSON-LD document.
+    pub fn about(&self, subject: &str) -> Option<String> {
+        self.render(|nodes| {
+            let mut ids = vec![subject];
+            let mut position = 0;
+            while let Some(id) = ids.get(position) {
+                position += 1;
+                let Some(node) = nodes.get(*id) else {
+                    continue;
+                };
+                let referred = node
+                    .values()
+                    .filter_map(Value::as_array)
+                    .flatten()
+                    .filter_map(|value| value.get("@id").and_then(Value::as_str))
+                    .filter(|id| id.starts_with("_:"));
+                for id in referred {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+            ids.iter().filter_map(|id| nodes.get(*id)).collect()
+        })
+    }
+
+    /// The nodes `select` picks from each graph, as a JSON-LD document that
+    /// can be embedded in a `<script>` element.
+    fn render<'a>(
+        &'a self,
+        select: impl Fn(&'a BTreeMap<String, Map<String, Value>>) -> Vec<&'a Map<String, Value>>,
+    ) -> Option<String> {
+        let mut document = Vec::new();
+        for (graph, nodes) in &self.graphs {
+            let nodes: Vec<Value> = select(nodes)
+                .into_iter()
+                .map(|node| Value::Object(node.clone()))
+                .collect();
+            if nodes.is_empty() {
+                continue;
+            }
+            match graph {
+                Some(graph) => document.push(json!({ "@id": graph, "@graph": nodes })),
+                None => document.extend(nodes),
+            }
+        }
+        if document.is_empty() {
+            return None;
+        }
+        // `<` is escaped so that no value can end the script element early
+        Some(Value::Array(document).to_string().replace('<', "\\u003c"))
+    }
+}
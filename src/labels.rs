@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A label given to a term with `rdfs:label` or `skos:prefLabel`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LabelUse {
+    pub iri: String,
+    pub label: String,
+    pub language: Option<String>,
+    /// Page describing the term, relative to the site root.
+    pub page: String,
+}
+
+impl LabelUse {
+    /// The label as shown on pages, with its language tag.
+    pub fn display(&self) -> String {
+        match &self.language {
+            Some(language) => format!("{} (@{})", self.label, language),
+            None => self.label.clone(),
+        }
+    }
+}
+
+/// Different terms sharing a label in the same language.
+pub struct Collision<'a> {
+    pub uses: Vec<&'a LabelUse>,
+}
+
+impl fmt::Display for Collision<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "label \"{}\" is shared by ", self.uses[0].display())?;
+        for (i, label_use) in self.uses.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "<{}> ({})", label_use.iri, label_use.page)?;
+        }
+        Ok(())
+    }
+}
+
+/// Labels given to more than one term. Labels are compared ignoring case and
+/// surrounding whitespace; a term labelled the same way twice counts once.
+pub fn collisions(uses: &[LabelUse]) -> Vec<Collision<'_>> {
+    let mut by_label: BTreeMap<(String, Option<String>), Vec<&LabelUse>> = BTreeMap::new();
+    for label_use in uses {
+        let key = (
+            label_use.label.trim().to_lowercase(),
+            label_use.language.as_ref().map(|l| l.to_ascii_lowercase()),
+        );
+        let terms = by_label.entry(key).or_default();
+        if !terms.iter().any(|t| t.iri == label_use.iri) {
+            terms.push(label_use);
+        }
+    }
+
+    by_label
+        .into_values()
+        .filter(|uses| uses.len() > 1)
+        .map(|uses| Collision { uses })
+        .collect()
+}
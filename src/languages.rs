@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use oxrdf::vocab::xsd;
+use oxrdf::{GraphName, NamedNode, NamedOrBlankNode, Quad, Term};
+
+/// Keeps, of the text values a subject has for a predicate, only those in
+/// the first of `languages` it has values in, as in `--lang de --lang en`.
+/// A language also matches its subtags, so `en` keeps `en-GB`. Subjects
+/// with none of the languages keep their untagged values, or all of them
+/// when there are none. Other statements are kept as they are.
+pub fn filter_languages(quads: Vec<Quad>, languages: &[String]) -> Vec<Quad> {
+    if languages.is_empty() {
+        return quads;
+    }
+
+    let mut best: HashMap<(&NamedOrBlankNode, &NamedNode, &GraphName), usize> = HashMap::new();
+    for quad in &quads {
+        if let Some(rank) = rank(&quad.object, languages) {
+            best.entry((&quad.subject, &quad.predicate, &quad.graph_name))
+                .and_modify(|best| *best = rank.min(*best))
+                .or_insert(rank);
+        }
+    }
+    let keep: Vec<bool> = quads
+        .iter()
+        .map(|quad| match rank(&quad.object, languages) {
+            Some(rank) => best[&(&quad.subject, &quad.predicate, &quad.graph_name)] == rank,
+            None => true,
+        })
+        .collect();
+
+    quads
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(quad, keep)| keep.then_some(quad))
+        .collect()
+}
+
+/// How preferred a text value is: the position of its language in
+/// `languages`, then untagged values, then other languages. Values that are
+/// not text have no rank.
+fn rank(object: &Term, languages: &[String]) -> Option<usize> {
+    let Term::Literal(literal) = object else {
+        return None;
+    };
+    let Some(language) = literal.language() else {
+        return (literal.datatype() == xsd::STRING).then_some(languages.len());
+    };
+    Some(
+        languages
+            .iter()
+            .position(|wanted| matches(language, wanted))
+            .unwrap_or(languages.len() + 1),
+    )
+}
+
+/// Whether the tag `language` is `wanted` or one of its subtags.
+fn matches(language: &str, wanted: &str) -> bool {
+    language.eq_ignore_ascii_case(wanted)
+        || language
+            .get(..wanted.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(wanted))
+            && language[wanted.len()..].starts_with('-')
+}
@@ -0,0 +1,259 @@
+//! Converts RDF files in Turtle, TriG, N-Triples, N-Quads and RDF/XML into
+//! a static HTML site.
+//!
+//! The `turtle2rdf` command line tool is built on this library. To convert
+//! files from another program, render each input with [`convert_file`] into
+//! an [`OutputBackend`] and finish with [`generate_index`]:
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use turtle2rdf::{base_context, convert_file, generate_index, input_files, templates, url_path};
+//! use turtle2rdf::{ConvertOptions, IndexEntry, LocalOutput};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let output = LocalOutput::new(Path::new("site"))?;
+//! let tera = templates();
+//! let context = base_context();
+//! let options = ConvertOptions::default();
+//!
+//! let mut entries = Vec::new();
+//! for path in input_files("ontologies") {
+//!     let converted = convert_file(&path, "ontologies", &output, &tera, &context, &options)?;
+//!     let name = path.file_name().unwrap().to_string_lossy().to_string();
+//!     entries.push(IndexEntry::new(url_path(&converted.path), name));
+//! }
+//! generate_index(&output, &entries, &tera, &context, &options)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod a11y;
+mod assets;
+mod bench;
+mod cache;
+mod canonical;
+mod checksums;
+#[doc(hidden)]
+pub mod cli;
+mod collation;
+mod concordance;
+mod config;
+mod conneg;
+mod corpus;
+mod csp;
+mod datasets;
+mod datatypes;
+mod deploy;
+mod diagnostic;
+mod dry_run;
+mod error_report;
+mod escape;
+mod filenames;
+pub mod golden;
+mod highlight;
+mod html_check;
+mod http;
+mod json_ld;
+mod labels;
+mod languages;
+mod link_check;
+mod lists;
+mod logging;
+mod minify;
+mod output;
+mod output_paths;
+mod parser;
+mod pipeline;
+mod prefixes;
+mod progress;
+mod recover;
+mod s3;
+mod search;
+mod serve;
+mod sitemap;
+mod sites;
+mod specs;
+mod spill;
+mod stats;
+mod stdio;
+mod summary;
+mod usage;
+mod vocab;
+mod walk;
+mod watch;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tera::{Context, Tera};
+use walkdir::WalkDir;
+
+pub use crate::collation::LabelOrder;
+pub use crate::concordance::Subject;
+pub use crate::corpus::{Corpus, Outline};
+pub use crate::diagnostic::Diagnostic;
+pub use crate::labels::LabelUse;
+pub use crate::output::{url_path, LocalOutput, OutputBackend};
+pub use crate::parser::{
+    generate_index, group_file, parse_file, render_file, ConvertOptions, Converted, ErrorPolicy,
+    GroupedFile, IndexEntry, InputFormat, ParsedFile, SubjectGroup, Triple,
+};
+pub use crate::search::SearchEntry;
+pub use crate::usage::FileUsage;
+
+/// Parses `input_path` and writes its page to `output`, at the file's path
+/// relative to `input_dir` with an `.html` extension.
+pub fn convert_file(
+    input_path: &Path,
+    input_dir: &str,
+    output: &dyn OutputBackend,
+    tera: &Tera,
+    base_context: &Context,
+    options: &ConvertOptions,
+) -> Result<Converted, Box<dyn std::error::Error>> {
+    let parsed = parse_file(input_path, input_dir, options)?;
+    render_file(
+        parsed,
+        input_path,
+        input_dir,
+        output,
+        tera,
+        base_context,
+        options,
+    )
+}
+
+/// Context for rendering with the built-in templates and none of the
+/// site-wide features: stylesheets inline, no analytics.
+pub fn base_context() -> Context {
+    let mut context = Context::new();
+    context.insert("stylesheets", &HashMap::<String, String>::new());
+    context.insert("accessible", &false);
+    context.insert("analytics", &None::<String>);
+    context
+}
+
+/// The built-in page templates and stylesheets.
+pub fn templates() -> Tera {
+    let mut tera = Tera::default();
+    escape::configure(&mut tera);
+    tera.add_raw_template("page.html", include_str!("../templates/page.html"))
+        .expect("Failed to add template");
+    tera.add_raw_template("index.html", include_str!("../templates/index.html"))
+        .expect("Failed to add index template");
+    tera.add_raw_template("search.html", include_str!("../templates/search.html"))
+        .expect("Failed to add search template");
+    tera.add_raw_template("all.html", include_str!("../templates/all.html"))
+        .expect("Failed to add concordance template");
+    tera.add_raw_template("usage.html", include_str!("../templates/usage.html"))
+        .expect("Failed to add usage template");
+    tera.add_raw_template("datasets.html", include_str!("../templates/datasets.html"))
+        .expect("Failed to add datasets template");
+    tera.add_raw_template("stats.html", include_str!("../templates/stats.html"))
+        .expect("Failed to add stats template");
+    tera.add_raw_template("source.html", include_str!("../templates/source.html"))
+        .expect("Failed to add source template");
+    tera.add_raw_template("triples.html", include_str!("../templates/triples.html"))
+        .expect("Failed to add triples template");
+    tera.add_raw_template("object.html", include_str!("../templates/object.html"))
+        .expect("Failed to add object template");
+    tera.add_raw_template("list.html", include_str!("../templates/list.html"))
+        .expect("Failed to add list template");
+    tera.add_raw_template("quoted.html", include_str!("../templates/quoted.html"))
+        .expect("Failed to add quoted triple template");
+    tera.add_raw_template(
+        "concept_tree.html",
+        include_str!("../templates/concept_tree.html"),
+    )
+    .expect("Failed to add concept tree template");
+    tera.add_raw_template(
+        "term_link.html",
+        include_str!("../templates/term_link.html"),
+    )
+    .expect("Failed to add term link template");
+    tera.add_raw_template("page.css", include_str!("../templates/page.css"))
+        .expect("Failed to add page stylesheet");
+    tera.add_raw_template("index.css", include_str!("../templates/index.css"))
+        .expect("Failed to add index stylesheet");
+    tera.add_raw_template(
+        "accessible.css",
+        include_str!("../templates/accessible.css"),
+    )
+    .expect("Failed to add accessibility stylesheet");
+    tera.add_raw_template("switcher.css", include_str!("../templates/switcher.css"))
+        .expect("Failed to add switcher stylesheet");
+    tera.add_raw_template("print.css", include_str!("../templates/print.css"))
+        .expect("Failed to add print stylesheet");
+    tera.add_raw_template("theme.css", include_str!("../templates/theme.css"))
+        .expect("Failed to add theme stylesheet");
+    tera.add_raw_template("colors.css", include_str!("../templates/colors.css"))
+        .expect("Failed to add colors stylesheet");
+    tera.add_raw_template("palette.css", include_str!("../templates/palette.css"))
+        .expect("Failed to add palette");
+    tera.add_raw_template(
+        "palette-dark.css",
+        include_str!("../templates/palette-dark.css"),
+    )
+    .expect("Failed to add dark palette");
+
+    tera
+}
+
+/// Names of the built-in themes; `default` is the look of [`templates`].
+pub const THEMES: &[&str] = &["default", "plain", "documentation", "dark", "compact"];
+
+/// The built-in templates with those of `theme` in place of the default
+/// ones. A theme replaces `theme.css`, which both stylesheets include last.
+pub fn themed_templates(theme: &str) -> Result<Tera, Box<dyn std::error::Error>> {
+    let theme_css = match theme {
+        "default" => include_str!("../templates/theme.css"),
+        "plain" => include_str!("../templates/themes/plain.css"),
+        "documentation" => include_str!("../templates/themes/documentation.css"),
+        "dark" => include_str!("../templates/themes/dark.css"),
+        "compact" => include_str!("../templates/themes/compact.css"),
+        _ => return Err(format!("Unknown theme {:?}", theme).into()),
+    };
+
+    let mut tera = templates();
+    tera.add_raw_template("theme.css", theme_css)?;
+    Ok(tera)
+}
+
+/// The templates of `theme`, with any template of the same name found in
+/// `dir` used instead. Templates are named by their path relative to `dir`,
+/// so `dir/page.html` replaces the page template and new files can be
+/// included from the replacements.
+pub fn templates_from_dir(dir: &Path, theme: &str) -> Result<Tera, Box<dyn std::error::Error>> {
+    let mut overrides = Vec::new();
+    for entry in WalkDir::new(dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let name = entry.path().strip_prefix(dir)?;
+            let name = output::url_path(name);
+            overrides.push((name, fs::read_to_string(entry.path())?));
+        }
+    }
+
+    let mut tera = themed_templates(theme)?;
+    // added together so that templates may extend or include each other in
+    // any order
+    tera.add_raw_templates(overrides).map_err(|e| {
+        let mut message = format!("Invalid template in {:?}: {}", dir, e);
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        message
+    })?;
+
+    Ok(tera)
+}
+
+/// Turtle, TriG, N-Triples, N-Quads and RDF/XML files below `input_dir`, in
+/// the order they are found.
+pub fn input_files(input_dir: &str) -> Vec<PathBuf> {
+    walk::InputWalk::default().files(input_dir)
+}
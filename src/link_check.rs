@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use percent_encoding::percent_decode_str;
+
+use crate::output::OutputBackend;
+
+#[derive(Default)]
+struct SiteLinks {
+    /// Every file written, with the ids defined in it for HTML pages.
+    anchors: HashMap<PathBuf, HashSet<String>>,
+    /// Relative hrefs per page, in document order.
+    links: Vec<(PathBuf, String)>,
+}
+
+/// Wraps another backend and collects the files, anchors and relative links
+/// of the generated site so that broken intra-site links can be reported once
+/// everything has been written.
+pub struct LinkCheckOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    site: Mutex<SiteLinks>,
+}
+
+impl<'a> LinkCheckOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        LinkCheckOutput {
+            inner,
+            site: Mutex::new(SiteLinks::default()),
+        }
+    }
+
+    /// Returns a description of every link whose target file or fragment was
+    /// not generated.
+    pub fn broken_links(&self) -> Vec<String> {
+        let site = self.site.lock().unwrap();
+        let mut broken = Vec::new();
+
+        for (page, href) in &site.links {
+            let (path, fragment) = match href.split_once('#') {
+                Some((path, fragment)) => (path, Some(fragment)),
+                None => (href.as_str(), None),
+            };
+            let path = percent_decode_str(path).decode_utf8_lossy();
+
+            let target = if path.is_empty() {
+                page.clone()
+            } else {
+                resolve(page, &path)
+            };
+
+            match site.anchors.get(&target) {
+                None => broken.push(format!("{:?} links to missing file {:?}", page, href)),
+                Some(ids) => {
+                    if let Some(fragment) = fragment.filter(|f| !f.is_empty()) {
+                        let fragment = percent_decode_str(fragment).decode_utf8_lossy();
+                        if !ids.contains(fragment.as_ref()) {
+                            broken.push(format!("{:?} links to missing anchor {:?}", page, href));
+                        }
+                    }
+                }
+            }
+        }
+
+        broken
+    }
+}
+
+impl OutputBackend for LinkCheckOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut ids = HashSet::new();
+        let mut links = Vec::new();
+
+        if relative_path.extension().and_then(|s| s.to_str()) == Some("html") {
+            let dom = parse_document(RcDom::default(), Default::default())
+                .one(String::from_utf8_lossy(contents).as_ref());
+            collect(&dom.document, &mut ids, &mut links);
+        }
+
+        let mut site = self.site.lock().unwrap();
+        site.anchors.insert(relative_path.to_path_buf(), ids);
+        site.links.retain(|(page, _)| page != relative_path);
+        site.links.extend(
+            links
+                .into_iter()
+                .filter(|href| is_internal(href))
+                .map(|href| (relative_path.to_path_buf(), href)),
+        );
+        drop(site);
+
+        self.inner.write(relative_path, contents)
+    }
+}
+
+fn collect(node: &Handle, ids: &mut HashSet<String>, links: &mut Vec<String>) {
+    if let NodeData::Element { name, attrs, .. } = &node.data {
+        for attr in attrs.borrow().iter() {
+            match &*attr.name.local {
+                "id" => {
+                    ids.insert(attr.value.to_string());
+                }
+                "name" if &*name.local == "a" => {
+                    ids.insert(attr.value.to_string());
+                }
+                "href" | "src" => links.push(attr.value.to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    for child in node.children.borrow().iter() {
+        collect(child, ids, links);
+    }
+}
+
+/// Intra-site links are relative references: no scheme and not
+/// protocol-relative.
+fn is_internal(href: &str) -> bool {
+    if href.starts_with("//") {
+        return false;
+    }
+
+    let scheme_end = href.find(':');
+    let path_end = href.find(['/', '?', '#']).unwrap_or(href.len());
+    !matches!(scheme_end, Some(i) if i < path_end)
+}
+
+fn resolve(page: &Path, href: &str) -> PathBuf {
+    let href = href.split('?').next().unwrap_or_default();
+    let mut resolved = PathBuf::new();
+
+    let base = page.parent().unwrap_or(Path::new(""));
+    let joined = if let Some(absolute) = href.strip_prefix('/') {
+        PathBuf::from(absolute)
+    } else {
+        base.join(href)
+    };
+
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(part) => resolved.push(part),
+            _ => {}
+        }
+    }
+
+    // links to a directory are served by its index page
+    if href.ends_with('/') || resolved.as_os_str().is_empty() {
+        resolved.push("index.html");
+    }
+
+    resolved
+}
@@ -0,0 +1,111 @@
+//! RDF collections, shown as a list on the statement referring to them
+//! instead of as a chain of `rdf:first` and `rdf:rest` statements.
+
+use std::collections::{HashMap, HashSet};
+
+use oxrdf::{BlankNode, GraphName, NamedOrBlankNode, Quad, Term};
+
+use crate::vocab::{RDF_FIRST, RDF_LIST, RDF_NIL, RDF_REST, RDF_TYPE};
+
+/// The well-formed collections of a file, by the blank node heading them.
+#[derive(Default)]
+pub struct Lists {
+    members: HashMap<BlankNode, Vec<Term>>,
+    /// Positions of the statements making up the collections.
+    statements: HashSet<usize>,
+}
+
+impl Lists {
+    /// Members of the collection headed by `node`, in order.
+    pub fn members(&self, node: &BlankNode) -> Option<&[Term]> {
+        self.members.get(node).map(Vec::as_slice)
+    }
+
+    /// Whether the statement at `position` is part of a collection.
+    pub fn contains(&self, position: usize) -> bool {
+        self.statements.contains(&position)
+    }
+}
+
+/// Statements about a blank node that may be a node of a collection.
+#[derive(Default)]
+struct Node<'a> {
+    first: Vec<&'a Term>,
+    rest: Vec<&'a Term>,
+    graphs: HashSet<&'a GraphName>,
+    statements: Vec<usize>,
+    /// The node has statements other than `rdf:first`, `rdf:rest` and
+    /// `rdf:type rdf:List`.
+    other: bool,
+}
+
+/// Finds the collections of `quads` that are the object of a statement or
+/// a member of another collection. A collection is only taken when every node is a
+/// blank node with exactly one `rdf:first` and `rdf:rest`, referred to once
+/// and in the graph of the referring statement, so that nothing is hidden.
+pub fn collect_lists(quads: &[Quad]) -> Lists {
+    let mut nodes: HashMap<&BlankNode, Node> = HashMap::new();
+    let mut references: HashMap<&BlankNode, usize> = HashMap::new();
+    for (position, quad) in quads.iter().enumerate() {
+        if let Term::BlankNode(object) = &quad.object {
+            *references.entry(object).or_default() += 1;
+        }
+        let NamedOrBlankNode::BlankNode(subject) = &quad.subject else {
+            continue;
+        };
+        let node = nodes.entry(subject).or_default();
+        node.graphs.insert(&quad.graph_name);
+        node.statements.push(position);
+        match quad.predicate.as_str() {
+            RDF_FIRST => node.first.push(&quad.object),
+            RDF_REST => node.rest.push(&quad.object),
+            RDF_TYPE if matches!(&quad.object, Term::NamedNode(t) if t.as_str() == RDF_LIST) => {}
+            _ => node.other = true,
+        }
+    }
+
+    let mut lists = Lists::default();
+    for quad in quads {
+        // collections continue through `rdf:rest`; a collection that is a
+        // member of another one is found through `rdf:first`
+        if quad.predicate.as_str() == RDF_REST {
+            continue;
+        }
+        let Term::BlankNode(head) = &quad.object else {
+            continue;
+        };
+        let mut members = Vec::new();
+        let mut statements: Vec<usize> = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = head;
+        let complete = loop {
+            let Some(node) = nodes.get(current) else {
+                break false;
+            };
+            let well_formed = node.first.len() == 1
+                && node.rest.len() == 1
+                && !node.other
+                && references.get(current) == Some(&1)
+                && node.graphs.len() == 1
+                && node.graphs.contains(&quad.graph_name)
+                && visited.insert(current);
+            if !well_formed {
+                break false;
+            }
+            members.push(node.first[0].clone());
+            statements.extend(&node.statements);
+            match node.rest[0] {
+                Term::NamedNode(nil) if nil.as_str() == RDF_NIL => break true,
+                Term::BlankNode(next) => current = next,
+                _ => break false,
+            }
+        };
+
+        if complete {
+            lists.members.insert(head.clone(), members);
+            lists.statements.extend(statements);
+        }
+    }
+
+    lists
+}
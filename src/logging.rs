@@ -0,0 +1,72 @@
+//! Messages of the converter, written with the macros of the `log` crate:
+//! progress to standard output, warnings and errors to standard error.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::progress;
+
+struct Logger;
+
+static LOGGER: Logger = Logger;
+
+/// Whether progress goes to standard error too, see [`to_stderr`].
+static STDERR_ONLY: AtomicBool = AtomicBool::new(false);
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // dependencies such as html5ever log their own internals
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+            && metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // a closed pipe, as when the output is cut short with `head`, is not
+        // worth failing the run for
+        progress::suspend(|| {
+            let _ = match record.level() {
+                Level::Error | Level::Warn => writeln!(std::io::stderr(), "{}", record.args()),
+                _ if STDERR_ONLY.load(Ordering::Relaxed) => {
+                    writeln!(std::io::stderr(), "{}", record.args())
+                }
+                Level::Info | Level::Debug | Level::Trace => {
+                    writeln!(std::io::stdout(), "{}", record.args())
+                }
+            };
+        });
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// The level of the messages shown: progress by default, everything with
+/// `verbose` and only warnings and errors with `quiet`.
+pub fn level(verbose: bool, quiet: bool) -> LevelFilter {
+    if quiet {
+        LevelFilter::Warn
+    } else if verbose {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// Writes every message to standard error from now on, leaving standard
+/// output to the page written there with `-o -`.
+pub fn to_stderr() {
+    STDERR_ONLY.store(true, Ordering::Relaxed);
+}
+
+/// Writes the messages of `level` and above from now on.
+pub fn init(level: LevelFilter) {
+    // a second call only changes the level
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use crate::output::OutputBackend;
+
+/// Elements whose contents are written as they are: whitespace matters in
+/// `pre` and `textarea`, and scripts may rely on line breaks.
+const VERBATIM_ELEMENTS: &[&str] = &["pre", "textarea", "script"];
+
+/// Wraps another backend and minifies every HTML page written through it.
+pub struct MinifyOutput<'a> {
+    inner: &'a dyn OutputBackend,
+}
+
+impl<'a> MinifyOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        MinifyOutput { inner }
+    }
+}
+
+impl OutputBackend for MinifyOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let html = match std::str::from_utf8(contents) {
+            Ok(html) if relative_path.extension().and_then(|s| s.to_str()) == Some("html") => html,
+            _ => return self.inner.write(relative_path, contents),
+        };
+        self.inner
+            .write(relative_path, minify_html(html).as_bytes())
+    }
+}
+
+/// Collapses every run of whitespace in `html` to a single line break, if
+/// it held one, or a single space, which browsers render the same.
+/// Attribute values, comments and the contents of [`VERBATIM_ELEMENTS`] are
+/// left alone; inline styles are collapsed without being parsed as markup.
+pub fn minify_html(html: &str) -> String {
+    let mut minified = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        minified.push_str(&collapse(&rest[..start]));
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            minified.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = tag_end(rest);
+        let tag = &rest[..end];
+        minified.push_str(&collapse_tag(tag));
+        rest = &rest[end..];
+
+        let name: String = tag[1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        let verbatim = VERBATIM_ELEMENTS.contains(&name.as_str());
+        if verbatim || name == "style" {
+            let close = format!("</{}", name);
+            let end = rest.find(&close).unwrap_or(rest.len());
+            if verbatim {
+                minified.push_str(&rest[..end]);
+            } else {
+                minified.push_str(&collapse(&rest[..end]));
+            }
+            rest = &rest[end..];
+        }
+    }
+    minified.push_str(&collapse(rest));
+
+    minified
+}
+
+/// Length of the tag `html` starts with, up to and including its `>`, which
+/// may not be inside a quoted attribute value.
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in html.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// Collapses the whitespace between the attributes of `tag`, keeping quoted
+/// values as they are.
+fn collapse_tag(tag: &str) -> String {
+    let mut collapsed = String::with_capacity(tag.len());
+    let mut quote = None;
+    let mut space = false;
+    for c in tag.chars() {
+        match quote {
+            Some(q) => {
+                collapsed.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_ascii_whitespace() => space = true,
+            None => {
+                if space {
+                    collapsed.push(' ');
+                    space = false;
+                }
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                collapsed.push(c);
+            }
+        }
+    }
+    collapsed
+}
+
+/// Collapses each run of whitespace in `text`.
+fn collapse(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut run: Option<bool> = None;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            run = Some(run.unwrap_or(false) || c == '\n');
+            continue;
+        }
+        if let Some(newline) = run.take() {
+            collapsed.push(if newline { '\n' } else { ' ' });
+        }
+        collapsed.push(c);
+    }
+    if let Some(newline) = run {
+        collapsed.push(if newline { '\n' } else { ' ' });
+    }
+    collapsed
+}
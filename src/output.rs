@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+
+use crate::assets::is_hashed;
+use crate::http::RetryPolicy;
+use crate::s3::S3Output;
+
+/// Destination for generated files. Paths passed to `write` are relative to
+/// the root of the generated site. Backends are shared by the rendering
+/// threads.
+pub trait OutputBackend: Sync {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+pub struct LocalOutput {
+    root: PathBuf,
+}
+
+impl LocalOutput {
+    pub fn new(root: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        fs::create_dir_all(root)?;
+
+        Ok(LocalOutput {
+            root: root.to_path_buf(),
+        })
+    }
+}
+
+impl OutputBackend for LocalOutput {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let output_path = self.root.join(relative_path);
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // unchanged files keep their modification time, for incremental
+        // builds and tools syncing the output
+        let unchanged = fs::metadata(&output_path).is_ok_and(|m| m.len() == contents.len() as u64)
+            && fs::read(&output_path).is_ok_and(|existing| existing == contents);
+        if !unchanged {
+            fs::write(output_path, contents)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Wraps another backend and limits how many writes run at the same time,
+/// for destinations such as network filesystems that degrade under load.
+pub struct ThrottledOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    limit: usize,
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+impl<'a> ThrottledOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend, limit: usize) -> Self {
+        ThrottledOutput {
+            inner,
+            limit,
+            active: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+}
+
+impl OutputBackend for ThrottledOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        {
+            let mut active = self.active.lock().unwrap();
+            while *active >= self.limit {
+                active = self.released.wait(active).unwrap();
+            }
+            *active += 1;
+        }
+
+        let result = self.inner.write(relative_path, contents);
+
+        *self.active.lock().unwrap() -= 1;
+        self.released.notify_one();
+
+        result
+    }
+}
+
+/// Picks the backend for an `--output` value: `s3://bucket/prefix` writes to
+/// an S3-compatible bucket, anything else is a local directory.
+pub fn backend_from_arg(
+    output: &str,
+    retry: RetryPolicy,
+) -> Result<Box<dyn OutputBackend>, Box<dyn std::error::Error>> {
+    if output.starts_with("s3://") {
+        Ok(Box::new(S3Output::from_url(output, retry)?))
+    } else {
+        Ok(Box::new(LocalOutput::new(Path::new(output))?))
+    }
+}
+
+/// Joins the components of a site-relative path with `/`, as used in URLs
+/// and object keys regardless of the platform's separator.
+pub fn url_path(relative_path: &Path) -> String {
+    relative_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("ttl") => "text/turtle; charset=utf-8",
+        Some("trig") => "application/trig; charset=utf-8",
+        Some("nt") => "application/n-triples; charset=utf-8",
+        Some("nq") => "application/n-quads; charset=utf-8",
+        Some("rdf") | Some("owl") => "application/rdf+xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The media type of `path`, as [`content_type`] without parameters.
+pub fn media_type(path: &Path) -> &'static str {
+    let content_type = content_type(path);
+    content_type.split(';').next().unwrap_or(content_type)
+}
+
+/// Pages are revalidated often so that republished vocabularies show up
+/// quickly; everything else can be cached for a day, and assets whose names
+/// carry a content hash forever.
+pub fn cache_control(path: &Path) -> &'static str {
+    if is_hashed(path) {
+        return "public, max-age=31536000, immutable";
+    }
+    match path.extension().and_then(|s| s.to_str()) {
+        Some("html") => "public, max-age=300",
+        _ => "public, max-age=86400",
+    }
+}
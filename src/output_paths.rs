@@ -0,0 +1,109 @@
+//! Input files whose pages would be written to the same path, such as
+//! `onto.ttl` and `onto.nt`, or `Onto.ttl` and `onto.ttl` on a file system
+//! that ignores case.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use log::warn;
+
+use crate::output::url_path;
+use crate::parser::ConvertOptions;
+
+/// What happens when the pages of several input files would be written to
+/// the same path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    /// The run fails before anything is converted.
+    Fail,
+    /// The input file that comes first by path keeps the page; the pages of
+    /// the others get a number appended, as in `onto-2.html`.
+    Rename,
+}
+
+/// Finds the input files among `inputs`, below `input_dir`, whose pages
+/// would be written to the same path with `options`, compared without
+/// regard to case. Returns the output paths of the renamed input files by
+/// their paths relative to `input_dir`, for
+/// [`ConvertOptions::renamed_outputs`].
+pub fn resolve_collisions(
+    inputs: &[PathBuf],
+    input_dir: &str,
+    options: &ConvertOptions,
+    policy: CollisionPolicy,
+) -> Result<HashMap<PathBuf, PathBuf>, Box<dyn std::error::Error>> {
+    let mut by_page: BTreeMap<String, Vec<&Path>> = BTreeMap::new();
+    for input in inputs {
+        let source_path = input.strip_prefix(input_dir).unwrap_or(input);
+        by_page
+            .entry(page_key(&options.output_source_path(source_path)))
+            .or_default()
+            .push(source_path);
+    }
+
+    let mut used: HashSet<String> = by_page.keys().cloned().collect();
+    let mut renamed = HashMap::new();
+    let mut collisions = 0;
+    for sources in by_page.values_mut().filter(|sources| sources.len() > 1) {
+        sources.sort();
+        let kept = sources[0];
+        let page = options.output_source_path(kept).with_extension("html");
+        for source_path in &sources[1..] {
+            if policy == CollisionPolicy::Fail {
+                warn!(
+                    "Collision: {} would be written for both {} and {}",
+                    url_path(&page),
+                    url_path(kept),
+                    url_path(source_path)
+                );
+                collisions += 1;
+                continue;
+            }
+            let output_path = options.output_source_path(source_path);
+            let mut n = 1;
+            let renamed_path = loop {
+                n += 1;
+                let candidate = numbered(&output_path, n);
+                if used.insert(page_key(&candidate)) {
+                    break candidate;
+                }
+            };
+            warn!(
+                "Writing the page of {} to {}, since that of {} is {}",
+                url_path(source_path),
+                url_path(&renamed_path.with_extension("html")),
+                url_path(kept),
+                url_path(&page)
+            );
+            renamed.insert(source_path.to_path_buf(), renamed_path);
+        }
+    }
+
+    if collisions > 0 {
+        return Err(format!(
+            "{} output path collision(s); rename the input files or use --on-collision rename",
+            collisions
+        )
+        .into());
+    }
+    Ok(renamed)
+}
+
+/// The page written for the output path `output_source_path`, lowercased
+/// since file systems may ignore case.
+fn page_key(output_source_path: &Path) -> String {
+    url_path(&output_source_path.with_extension("html")).to_lowercase()
+}
+
+/// `path` with `-n` appended to its file stem.
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, n, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, n),
+    };
+    path.with_file_name(name)
+}
@@ -1,18 +1,51 @@
-use std::collections::HashMap;
-use std::fs;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use std::path::{Path, PathBuf};
 
-use rio_api::model::{NamedNode, Subject, Term, Literal};
-use rio_api::parser::TriplesParser;
-use rio_turtle::{TurtleError, TurtleParser};
+use oxrdf::{BlankNode, GraphName, NamedOrBlankNode, Quad, Term};
+use oxrdfxml::RdfXmlParser;
+use oxttl::{NQuadsParser, NTriplesParser, TriGParser, TurtleSyntaxError};
 
 use tera::{Context, Tera};
 
-use serde::Serialize;
+use crate::collation::LabelOrder;
+use crate::concordance;
+use crate::corpus::{Corpus, Outline};
+use crate::datatypes::{display_value, ValueKind};
+use crate::diagnostic::Diagnostic;
+use crate::filenames::{slugify_name, slugify_path};
+use crate::highlight::highlight_lines;
+use crate::json_ld::JsonLd;
+use crate::labels::{collisions, LabelUse};
+use crate::languages::filter_languages;
+use crate::lists::{collect_lists, Lists};
+use crate::output::{media_type, url_path, OutputBackend};
+use crate::recover::{self, Prefixes};
+use crate::search::SearchEntry;
+use crate::sitemap::page_url;
+use crate::specs::specification_url;
+use crate::spill::sort_spilled;
+use crate::stdio::{read_input, read_input_to_string};
+use crate::usage::{Definition, FileUsage, TermKind};
+use crate::vocab::{
+    ALT_LABEL_PREDICATES, CLASS_PREDICATES, CLASS_TYPES, DEFINITION_PREDICATES,
+    EQUIVALENCE_PREDICATES, FOAF_MBOX, LABEL_PREDICATES, OWL_ANNOTATED_PROPERTY,
+    OWL_ANNOTATED_SOURCE, OWL_ANNOTATED_TARGET, OWL_ANNOTATION_PROPERTY, OWL_AXIOM,
+    OWL_DATATYPE_PROPERTY, OWL_INVERSE_OF, OWL_NAMED_INDIVIDUAL, OWL_OBJECT_PROPERTY, OWL_ONTOLOGY,
+    OWL_SAME_AS, PROPERTY_PREDICATES, PROPERTY_TYPES, RDFS_DOMAIN, RDFS_RANGE, RDFS_SUB_CLASS_OF,
+    RDFS_SUB_PROPERTY_OF, RDF_TYPE, SHAPE_TARGET_PREDICATES, SHAPE_TYPES,
+    SHAPE_VALUE_TYPE_PREDICATES, SH_MAX_COUNT, SH_MESSAGE, SH_MIN_COUNT, SH_ORDER, SH_PATH,
+    SH_PROPERTY, SKOS_ALT_LABEL, SKOS_BROADER, SKOS_CONCEPT, SKOS_CONCEPT_SCHEME, SKOS_DEFINITION,
+    SKOS_HAS_TOP_CONCEPT, SKOS_HIDDEN_LABEL, SKOS_IN_SCHEME, SKOS_NARROWER, SKOS_NOTATION,
+    SKOS_PREF_LABEL, SKOS_RELATED, SKOS_TOP_CONCEPT_OF,
+};
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::{Deserialize, Serialize};
 use url::Url;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Triple {
     subject: String,
     predicate: String,
@@ -22,31 +55,511 @@ pub struct Triple {
     subject_label: String,
     predicate_link: Option<String>,
     object_link: Option<String>,
+    object_direction: Option<String>,
+    /// Value of a literal object as written, before formatting or
+    /// truncation.
+    lexical_form: Option<String>,
+    /// Datatype IRI of a literal object.
+    datatype: Option<String>,
+    /// Language tag of a literal object.
+    language: Option<String>,
+    /// How a literal object is shown, for the datatypes given special
+    /// treatment.
+    value_kind: Option<ValueKind>,
+    /// Members of the collection the object heads, each as a statement
+    /// holding only its object.
+    list: Vec<Triple>,
+    /// Statements about the blank node object, shown inside this one when
+    /// nothing else refers to the node.
+    nested: Vec<Triple>,
+    /// The triple term the object is, as a statement of its own.
+    quoted: Option<Box<Triple>>,
+    /// Label of the blank node object, only needed while parsing.
+    #[serde(skip)]
+    object_node: Option<String>,
+    /// Position of the untruncated value in the page's literals attachment.
+    full_value: Option<usize>,
+    /// Numbers of the footnotes holding annotations on this statement.
+    footnotes: Vec<usize>,
+    /// The statement as RDFa attributes of its object cell.
+    rdfa: Option<Rdfa>,
+    /// The object is an email address, shown obfuscated.
+    email: bool,
+    /// Named graph holding the statement; `None` for the default graph.
+    graph: Option<String>,
+}
+
+impl Triple {
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// The predicate as shown on the page, shortened with a prefix where one
+    /// applies.
+    pub fn predicate(&self) -> &str {
+        &self.predicate
+    }
+
+    /// The object as shown on the page: an IRI, or a literal value with its
+    /// language tag.
+    pub fn object(&self) -> &str {
+        &self.object
+    }
+
+    /// Where the object links to, when it is an IRI.
+    pub fn object_link(&self) -> Option<&str> {
+        self.object_link.as_deref()
+    }
+
+    /// The full predicate IRI, which `predicate` no longer holds once it has
+    /// been shortened with a prefix.
+    pub fn predicate_iri(&self) -> &str {
+        self.predicate_link.as_deref().unwrap_or(&self.predicate)
+    }
+
+    /// The full object IRI, or the displayed value of a literal.
+    fn object_key(&self) -> &str {
+        self.object_link.as_deref().unwrap_or(&self.object)
+    }
+
+    /// The predicate and object links, including those of the members of a
+    /// collection, of nested statements and of triple terms.
+    fn links_mut(&mut self) -> Vec<&mut Option<String>> {
+        self.links_with_subject_mut(false)
+    }
+
+    /// The links of `links_mut`, and the subject link with `with_subject`,
+    /// as for triple terms, whose subject is shown along with the object.
+    fn links_with_subject_mut(&mut self, with_subject: bool) -> Vec<&mut Option<String>> {
+        let Triple {
+            subject_link,
+            predicate_link,
+            object_link,
+            list,
+            nested,
+            quoted,
+            ..
+        } = self;
+        let mut links = vec![predicate_link, object_link];
+        if with_subject {
+            links.push(subject_link);
+        }
+        for item in list.iter_mut().chain(nested) {
+            links.extend(item.links_mut());
+        }
+        if let Some(quoted) = quoted {
+            links.extend(quoted.links_with_subject_mut(true));
+        }
+        links
+    }
+
+    /// This statement and the statements nested in it or in the members of
+    /// its collection, at any depth.
+    fn statements(&self) -> Vec<&Triple> {
+        let mut statements = vec![self];
+        for triple in &self.nested {
+            statements.extend(triple.statements());
+        }
+        for item in &self.list {
+            // members hold only an object
+            statements.extend(item.statements().into_iter().skip(1));
+        }
+        statements
+    }
+
+    /// Calls `f` on the members of the collection the object heads, on the
+    /// statements nested in this one and on the triple term it holds, and on
+    /// those inside them.
+    fn for_each_inner(&mut self, f: &impl Fn(&mut Triple)) {
+        let quoted = self.quoted.as_deref_mut();
+        for item in self.list.iter_mut().chain(&mut self.nested).chain(quoted) {
+            f(item);
+            item.for_each_inner(f);
+        }
+    }
+
+    /// Rough number of bytes the statement takes in memory, with the
+    /// statements inside it.
+    fn memory_size(&self) -> usize {
+        let strings = [
+            &self.subject,
+            &self.predicate,
+            &self.object,
+            &self.subject_label,
+        ];
+        let optional = [
+            &self.subject_link,
+            &self.predicate_link,
+            &self.object_link,
+            &self.object_direction,
+            &self.lexical_form,
+            &self.datatype,
+            &self.language,
+            &self.graph,
+        ];
+        let inner: usize = self
+            .list
+            .iter()
+            .chain(&self.nested)
+            .chain(self.quoted.as_deref())
+            .map(Triple::memory_size)
+            .sum();
+        std::mem::size_of::<Triple>()
+            + strings.iter().map(|s| s.capacity()).sum::<usize>()
+            + optional
+                .iter()
+                .flat_map(|s| s.as_ref())
+                .map(String::capacity)
+                .sum::<usize>()
+            + self.footnotes.capacity() * std::mem::size_of::<usize>()
+            + inner
+    }
+
+    fn statement_key(&self) -> StatementKey {
+        (
+            self.subject.clone(),
+            self.predicate_iri().to_string(),
+            self.object_key().to_string(),
+        )
+    }
 }
 
-impl Default for Triple {
-    fn default() -> Self {
-        Triple {
-            subject: String::new(),
-            predicate: String::new(),
-            object: String::new(),
-            predicate_link: None,
-            subject_link: None,
-            subject_label: String::new(),
-            object_link: None,
+/// RDFa attributes giving a statement in the markup: the full IRIs of its
+/// subject and predicate, and either the object resource or the lexical
+/// form of a literal with its language or datatype.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Rdfa {
+    about: String,
+    property: String,
+    resource: Option<String>,
+    content: Option<String>,
+    datatype: Option<String>,
+    language: Option<String>,
+}
+
+impl Rdfa {
+    /// The attributes of `triple`, read before its IRIs are shortened.
+    /// Collections and triple terms have no single object to give.
+    fn of(triple: &Triple) -> Option<Self> {
+        if !triple.list.is_empty() || triple.quoted.is_some() {
+            return None;
         }
+        let (resource, content) = match (&triple.object_node, &triple.lexical_form) {
+            (Some(node), _) => (Some(node.clone()), None),
+            (None, Some(value)) => (None, Some(value.clone())),
+            (None, None) => (Some(triple.object.clone()), None),
+        };
+        Some(Rdfa {
+            about: triple.subject.clone(),
+            property: triple.predicate.clone(),
+            resource,
+            content,
+            // a language tag makes a literal a language-tagged string
+            datatype: triple
+                .datatype
+                .clone()
+                .filter(|_| triple.language.is_none()),
+            language: triple.language.clone(),
+        })
     }
 }
 
+/// Subject, predicate and object of a statement, as annotated by an
+/// `owl:Axiom`.
+type StatementKey = (String, String, String);
+
 #[derive(Serialize)]
 pub struct SubjectGroup {
     subject: String,
+    graph: Option<String>,
+    /// The subject as its IRI names it, shortened with a prefix.
+    name: String,
     subject_label: String,
     subject_link: Option<String>,
+    /// Id of the entry on the page, unique within the page.
+    anchor: String,
+    /// Separate page of the entry, relative to the page of its file, when
+    /// every subject gets its own page.
+    page: Option<String>,
     triples: Vec<Triple>,
+    /// Position in `triples` of the `rdf:type` statement whose type the
+    /// entry is listed under when entries are grouped by type.
+    #[serde(skip)]
+    primary_type: Option<usize>,
+    /// Section of the entry in ontology mode.
+    #[serde(skip)]
+    term_section: Option<TermSection>,
+    /// Definition and related terms of the entry in ontology mode.
+    summary: TermSummary,
+    roles: Vec<&'static str>,
+    role_sections: Vec<RoleSection>,
+    equivalents: Vec<TermLink>,
+    concept: Option<ConceptNavigation>,
+    concept_details: Option<ConceptDetails>,
+    shape: Option<ShapeView>,
+    footnotes: Vec<Footnote>,
+    homonyms: Vec<Homonym>,
+    /// Other IRIs of the subject, merged into its entry by `owl:sameAs`.
+    aliases: Vec<TermLink>,
+}
+
+/// Kinds of terms that get a section of their own in ontology mode, in page
+/// order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum TermSection {
+    Ontology,
+    Class,
+    ObjectProperty,
+    DatatypeProperty,
+    AnnotationProperty,
+    Property,
+    Individual,
+    Other,
+}
+
+impl TermSection {
+    /// The section of a subject with `types`. Terms with several kinds go
+    /// to the first section.
+    fn of(subject: &str, types: Option<&Vec<String>>) -> Self {
+        let types = types.map(Vec::as_slice).unwrap_or_default();
+        let has = |iris: &[&str]| types.iter().any(|t| iris.contains(&t.as_str()));
+        if subject.starts_with("_:") || types.is_empty() {
+            TermSection::Other
+        } else if has(&[OWL_ONTOLOGY]) {
+            TermSection::Ontology
+        } else if has(CLASS_TYPES) {
+            TermSection::Class
+        } else if has(&[OWL_OBJECT_PROPERTY]) {
+            TermSection::ObjectProperty
+        } else if has(&[OWL_DATATYPE_PROPERTY]) {
+            TermSection::DatatypeProperty
+        } else if has(&[OWL_ANNOTATION_PROPERTY]) {
+            TermSection::AnnotationProperty
+        } else if has(PROPERTY_TYPES) {
+            TermSection::Property
+        } else {
+            TermSection::Individual
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            TermSection::Ontology => "Ontology",
+            TermSection::Class => "Classes",
+            TermSection::ObjectProperty => "Object Properties",
+            TermSection::DatatypeProperty => "Datatype Properties",
+            TermSection::AnnotationProperty => "Annotation Properties",
+            TermSection::Property => "Other Properties",
+            TermSection::Individual => "Individuals",
+            TermSection::Other => "Other Terms",
+        }
+    }
+}
+
+/// What ontology mode shows above the statements of a term.
+#[derive(Serialize, Default)]
+pub struct TermSummary {
+    definitions: Vec<String>,
+    fields: Vec<SummaryField>,
+}
+
+/// Terms related to a term in one way, such as its superclasses.
+#[derive(Serialize)]
+pub struct SummaryField {
+    title: &'static str,
+    terms: Vec<TermLink>,
+}
+
+/// Other terms of the site sharing one of the subject's labels.
+#[derive(Serialize)]
+pub struct Homonym {
+    label: String,
+    terms: Vec<TermLink>,
+}
+
+impl SubjectGroup {
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// The subject as shown in the entry heading.
+    pub fn subject_label(&self) -> &str {
+        &self.subject_label
+    }
+
+    /// Id of the entry on the page, to link to it with a fragment.
+    pub fn anchor(&self) -> &str {
+        &self.anchor
+    }
+
+    /// Statements of the entry, not counting those in role sections.
+    pub fn triples(&self) -> &[Triple] {
+        &self.triples
+    }
+
+    /// The `rdf:type` statement whose type the entry is listed under when
+    /// entries are grouped by type.
+    fn primary_type(&self) -> Option<&Triple> {
+        self.primary_type.map(|position| &self.triples[position])
+    }
+
+    /// Page showing the entry, relative to the site root, for an entry of
+    /// the file whose page is `relative_path`.
+    fn entry_page(&self, relative_path: &Path) -> String {
+        match &self.page {
+            Some(page) => url_path(&relative_path.with_file_name(page)),
+            None => url_path(relative_path),
+        }
+    }
+
+    /// Links to other terms shown around the statements.
+    fn term_links_mut(&mut self) -> impl Iterator<Item = &mut TermLink> {
+        let concept = self.concept.iter_mut().flat_map(|c| {
+            c.ancestors
+                .iter_mut()
+                .chain(&mut c.narrower)
+                .chain(&mut c.related)
+        });
+        let homonyms = self.homonyms.iter_mut().flat_map(|h| &mut h.terms);
+        let shapes = self
+            .shape
+            .iter_mut()
+            .flat_map(|s| &mut s.properties)
+            .filter_map(|p| p.shape.as_mut());
+        let summary = self.summary.fields.iter_mut().flat_map(|f| &mut f.terms);
+        self.equivalents
+            .iter_mut()
+            .chain(concept)
+            .chain(homonyms)
+            .chain(summary)
+            .chain(shapes)
+    }
+
+    /// Every statement of the entry, including those in role sections,
+    /// footnotes and shape constraints.
+    fn all_triples_mut(&mut self) -> impl Iterator<Item = &mut Triple> {
+        let sections = self.role_sections.iter_mut().map(|s| &mut s.triples);
+        let footnotes = self.footnotes.iter_mut().map(|f| &mut f.annotations);
+        let shape = self.shape.iter_mut().flat_map(ShapeView::triple_lists_mut);
+        std::iter::once(&mut self.triples)
+            .chain(sections)
+            .chain(footnotes)
+            .chain(shape)
+            .flatten()
+    }
+}
+
+/// Annotations on a statement from one `owl:Axiom` block.
+#[derive(Serialize)]
+pub struct Footnote {
+    number: usize,
+    annotations: Vec<Triple>,
+}
+
+/// Link to another term: its entry when it is described on the same page,
+/// its IRI otherwise.
+#[derive(Serialize, Clone, PartialEq)]
+pub struct TermLink {
+    label: String,
+    iri: String,
+    anchor: Option<String>,
+}
+
+/// Position of a `skos:Concept` in its hierarchy.
+#[derive(Serialize, Default)]
+pub struct ConceptNavigation {
+    /// Broader concepts from the top of the hierarchy down to the parent.
+    ancestors: Vec<TermLink>,
+    narrower: Vec<TermLink>,
+    related: Vec<TermLink>,
+}
+
+/// Labels, definitions and notations of a `skos:Concept`, shown above its
+/// statements.
+#[derive(Serialize, Default)]
+pub struct ConceptDetails {
+    pref_labels: Vec<String>,
+    alt_labels: Vec<String>,
+    definitions: Vec<String>,
+    notations: Vec<String>,
+}
+
+/// The concepts of a concept scheme on a page, from its top concepts down.
+#[derive(Serialize)]
+pub struct ConceptTree {
+    /// `None` for the concepts outside any scheme.
+    scheme: Option<TermLink>,
+    concepts: Vec<ConceptTreeNode>,
+}
+
+#[derive(Serialize)]
+pub struct ConceptTreeNode {
+    term: TermLink,
+    narrower: Vec<ConceptTreeNode>,
+}
+
+impl ConceptTree {
+    /// Links to the scheme and to every concept of the tree.
+    fn term_links_mut(&mut self) -> Vec<&mut TermLink> {
+        let mut terms: Vec<&mut TermLink> = self.scheme.iter_mut().collect();
+        for node in &mut self.concepts {
+            node.collect_term_links(&mut terms);
+        }
+        terms
+    }
+}
+
+impl ConceptTreeNode {
+    fn collect_term_links<'a>(&'a mut self, terms: &mut Vec<&'a mut TermLink>) {
+        terms.push(&mut self.term);
+        for node in &mut self.narrower {
+            node.collect_term_links(terms);
+        }
+    }
 }
 
+/// A SHACL shape: the nodes it targets and a row per property constraint.
 #[derive(Serialize)]
+pub struct ShapeView {
+    targets: Vec<Triple>,
+    properties: Vec<PropertyConstraint>,
+}
+
+/// A property shape as a row of a constraint table, each cell holding the
+/// statements it shows.
+#[derive(Serialize, Default, Clone)]
+pub struct PropertyConstraint {
+    path: Vec<Triple>,
+    /// `min..max` from `sh:minCount` and `sh:maxCount`.
+    cardinality: Option<String>,
+    value_type: Vec<Triple>,
+    message: Vec<Triple>,
+    /// Statements of a nested shape without a column of their own.
+    other: Vec<Triple>,
+    /// The entry of a shape described on its own.
+    shape: Option<TermLink>,
+    #[serde(skip)]
+    order: Option<f64>,
+}
+
+impl ShapeView {
+    fn triple_lists_mut(&mut self) -> impl Iterator<Item = &mut Vec<Triple>> {
+        let rows = self
+            .properties
+            .iter_mut()
+            .flat_map(|p| [&mut p.path, &mut p.value_type, &mut p.message, &mut p.other]);
+        std::iter::once(&mut self.targets).chain(rows)
+    }
+}
+
+/// Triples describing a punned subject in one of its roles.
+#[derive(Serialize)]
+pub struct RoleSection {
+    title: &'static str,
+    triples: Vec<Triple>,
+}
+
+#[derive(Serialize, Clone)]
 pub struct IndexEntry {
     path: String,
     name: String,
@@ -56,14 +569,220 @@ impl IndexEntry {
     pub fn new(path: String, name: String) -> Self {
         IndexEntry { name, path }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+/// What happens to a batch when a file fails to parse or render.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Skips invalid statements, converts the rest of the file and carries
+    /// on with the batch.
+    #[default]
+    Continue,
+    /// Stops the whole batch at the first error.
+    FailFast,
+    /// Leaves a file with any error out of the site and carries on with the
+    /// batch.
+    SkipFile,
+}
+
+/// A converted file and the errors recovered from while converting it.
+#[derive(Serialize, Deserialize)]
+pub struct Converted {
+    pub path: PathBuf,
+    /// Every file written for the input, relative to the site root.
+    pub outputs: Vec<PathBuf>,
+    pub triples: usize,
+    /// Number of statements per graph, keyed by graph IRI; `None` is the
+    /// default graph.
+    #[serde(with = "crate::cache::pairs")]
+    pub graphs: BTreeMap<Option<String>, usize>,
+    pub search_entries: Vec<SearchEntry>,
+    /// Named subjects of the file, for the concordance page.
+    pub subjects: Vec<concordance::Subject>,
+    pub usage: FileUsage,
+    /// Labels of the named subjects, to find terms sharing one across files.
+    pub labels: Vec<LabelUse>,
+    /// Namespace IRIs by prefix, as declared in the file.
+    pub namespaces: BTreeMap<String, String>,
+    pub errors: Vec<Diagnostic>,
+}
+
+/// Options controlling how input files are parsed and rendered.
+#[derive(Default)]
+pub struct ConvertOptions {
+    /// Skips validation of IRIs and language tags and keeps statements the
+    /// converter would otherwise blank out, such as blank node objects.
+    pub lenient: bool,
+    /// Shows IRIs with decoded punycode hosts and percent-escapes. Links keep
+    /// the original IRI.
+    pub readable_iris: bool,
+    /// Literals longer than this many characters are truncated on the page
+    /// and written in full to a `.literals.json` attachment.
+    pub max_literal_length: Option<usize>,
+    /// Namespace IRIs by prefix that shorten IRIs no prefix declared in
+    /// their own file covers.
+    pub prefixes: Vec<(String, String)>,
+    /// Once the index has more entries than this, it is split into pages.
+    /// A size of 0 is taken as 1.
+    pub index_page_size: Option<usize>,
+    /// Links of the index pages are made absolute against this URL.
+    pub index_base_url: Option<Url>,
+    /// Predicates whose literal values are shown in place of the names of
+    /// subjects and objects, by priority. Terms without any keep the name
+    /// their IRI gives them.
+    pub label_properties: Vec<String>,
+    /// Language tags by preference. Of the text values a subject has for a
+    /// predicate, only those in the first language it has values in are
+    /// shown. Every language is shown when empty.
+    pub languages: Vec<String>,
+    /// Sorts subjects by label and index entries by name in this order.
+    /// Without it subjects are sorted by IRI and entries are left in the
+    /// order the files were found.
+    pub label_order: Option<LabelOrder>,
+    pub on_error: ErrorPolicy,
+    /// Only statements in these named graphs are converted; `default` stands
+    /// for the default graph. All statements are converted when unset.
+    pub graphs: Option<Vec<String>>,
+    /// Pages documenting terms outside this site, by term IRI. Links to
+    /// these terms point to their documentation instead of the bare IRI.
+    pub documentation_links: HashMap<String, String>,
+    /// Page and anchor, relative to the site root, of the subjects of every
+    /// converted file by IRI. Terms described in another file link to their
+    /// entry there, ahead of `documentation_links`.
+    pub site_links: HashMap<String, String>,
+    /// Labels and concept hierarchies of every file of the site, so that
+    /// concepts link to broader, narrower and related concepts described
+    /// in other files and terms sharing a label with terms of other files
+    /// note them. Both stay within the page when empty.
+    pub corpus: Corpus,
+    /// Links terms of the core vocabularies without an entry in
+    /// `documentation_links` to their specifications.
+    pub specification_links: bool,
+    /// Also writes a print-ready `.print.html` variant of every page.
+    pub print_view: bool,
+    /// Also writes a `.source.html` view of the input of every page, with
+    /// syntax highlighting and an anchor per line.
+    pub source_view: bool,
+    /// Copies every input file next to its page, under the same relative
+    /// path, and links it from the page.
+    pub copy_sources: bool,
+    /// Writes every subject to a page of its own, and lists them on the
+    /// page of the file.
+    pub split_by_subject: bool,
+    /// Shows the entries of a page in a section per type, under the type
+    /// of their `rdf:type` statements that comes first by label.
+    pub group_by_type: bool,
+    /// Documents ontologies: the entries of a page are shown in a section
+    /// per kind of term, such as classes and object properties, each with
+    /// its definition, hierarchy, domain and range above its statements.
+    pub ontology: bool,
+    /// Embeds the statements of each page in it as JSON-LD.
+    pub json_ld: bool,
+    /// Annotates the statement tables with RDFa.
+    pub rdfa: bool,
+    /// Shows `mailto:` objects and `foaf:mbox` values entity-encoded, to
+    /// keep them from address harvesters.
+    pub obfuscate_emails: bool,
+    /// Parses one statement at a time, leaving out only the statements with
+    /// syntax errors.
+    pub recover: bool,
+    /// Names pages and copied input files after their input files in
+    /// lowercase ASCII, with dashes for spaces and reserved characters.
+    pub slugify: bool,
+    /// Output paths of the input files renamed to keep their pages apart,
+    /// by input file relative to the input directory.
+    pub renamed_outputs: HashMap<PathBuf, PathBuf>,
+    /// IRI that relative IRIs are resolved against in files that declare no
+    /// base of their own. `{path}` stands for the path of the file relative
+    /// to the input directory and `{name}` for its name without extension.
+    pub base_iri: Option<String>,
+    /// Shows the subjects connected by `owl:sameAs` as one entry, with the
+    /// statements of all of them.
+    pub merge_same_as: bool,
+    /// Files whose statements take more than this many bytes are grouped by
+    /// sorting them in runs of that size on disk instead of all at once in
+    /// memory.
+    pub group_memory: Option<usize>,
+}
+
+impl ConvertOptions {
+    fn sectioning(&self) -> Sectioning {
+        if self.ontology {
+            Sectioning::ByTermSection
+        } else if self.group_by_type {
+            Sectioning::ByType
+        } else {
+            Sectioning::None
+        }
+    }
+
+    /// Where the input file at `source_path`, relative to the input
+    /// directory, is copied to, relative to the site root. Its page is named
+    /// after this path too.
+    pub fn output_source_path(&self, source_path: &Path) -> PathBuf {
+        if let Some(renamed) = self.renamed_outputs.get(source_path) {
+            renamed.clone()
+        } else if self.slugify {
+            slugify_path(source_path)
+        } else {
+            source_path.to_path_buf()
+        }
+    }
+
+    /// The base IRI of the input file at `source_path`, relative to the
+    /// input directory.
+    pub fn base_iri_for(&self, source_path: &Path) -> Option<String> {
+        let template = self.base_iri.as_ref()?;
+        let name = source_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(
+            template
+                .replace("{path}", &iri_path(&url_path(source_path)))
+                .replace("{name}", &iri_path(&name)),
+        )
+    }
+
+    fn includes_graph(&self, graph: &GraphName) -> bool {
+        let Some(graphs) = &self.graphs else {
+            return true;
+        };
+
+        match graph {
+            GraphName::NamedNode(iri) => graphs.iter().any(|g| g == iri.as_str()),
+            GraphName::BlankNode(_) => false,
+            GraphName::DefaultGraph => graphs.iter().any(|g| g == "default"),
+        }
+    }
+}
+
+/// Untruncated literal value, as written to a page's literals attachment.
+#[derive(Serialize)]
+struct FullLiteral {
+    subject: String,
+    predicate: String,
+    value: String,
 }
 
-fn update_triple_with_links(triple: &mut Triple, prefixes: &Vec<&String>) {
+fn update_triple_with_links(triple: &mut Triple, prefixes: &[String], options: &ConvertOptions) {
+    let object_is_iri = is_valid_url(&triple.object);
+    triple.email = options.obfuscate_emails
+        && (triple.object.starts_with("mailto:") || triple.predicate == FOAF_MBOX);
+
     if is_valid_url(&triple.subject) {
         for prefix in prefixes {
-            if triple.subject.starts_with(*prefix) {
+            if triple.subject.starts_with(prefix.as_str()) {
                 triple.subject_link = Some(triple.subject.clone());
-                triple.subject_label = triple.subject.replace(*prefix, "");
+                triple.subject_label = triple.subject.replace(prefix.as_str(), "");
 
                 break;
             }
@@ -72,154 +791,2625 @@ fn update_triple_with_links(triple: &mut Triple, prefixes: &Vec<&String>) {
 
     if is_valid_url(&triple.predicate) {
         for prefix in prefixes {
-            if triple.predicate.starts_with(*prefix) {
+            if triple.predicate.starts_with(prefix.as_str()) {
                 triple.predicate_link = Some(triple.predicate.clone());
-                triple.predicate = triple.predicate.replace(*prefix, "");
+                triple.predicate = triple.predicate.replace(prefix.as_str(), "");
 
                 break;
             }
         }
     }
 
-    if is_valid_url(&triple.object) {
+    if object_is_iri {
+        // the object links to its full IRI, whether or not a prefix shortens
+        // its label; without a prefix this is usually an external link
+        triple.object_link = Some(triple.object.clone());
+
         for prefix in prefixes {
-            if triple.object.starts_with(*prefix) {
-                triple.object_link = Some(triple.object.clone());
-                triple.object = triple.object.replace(*prefix, "");
+            if triple.object.starts_with(prefix.as_str()) {
+                triple.object = triple.object.replace(prefix.as_str(), "");
 
                 break;
             }
         }
+    }
 
-        // we may still have a valid url, but not a prefix
-        // this could be an external link
-        triple.object_link = Some(triple.object.clone());
+    if options.readable_iris {
+        triple.subject_label = readable_iri(&triple.subject_label);
+        triple.predicate = readable_iri(&triple.predicate);
+        if object_is_iri {
+            triple.object = readable_iri(&triple.object);
+        }
     }
 }
 
-pub fn convert_file(
-    input_path: &Path,
-    input_dir: &str,
-    output_dir: &str,
-    tera: &Tera,
-) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let input = fs::read_to_string(input_path)?;
-    let mut triples = Vec::new();
+/// Display form of an IRI (or prefixed name) with punycode hosts converted to
+/// Unicode and percent-escapes decoded.
+fn readable_iri(iri: &str) -> String {
+    let decoded = percent_decode_str(iri)
+        .decode_utf8()
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| iri.to_string());
 
-    let mut parser = TurtleParser::new(input.as_bytes(), None);
+    let host = Url::parse(iri)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+    match host {
+        Some(host) if host.contains("xn--") => {
+            let (unicode, _) = idna::domain_to_unicode(&host);
+            decoded.replacen(&host, &unicode, 1)
+        }
+        _ => decoded,
+    }
+}
 
-    loop {
-        let mut unparsed_triples = Vec::new();
+/// Moves the statements about blank nodes that are the object of a single
+/// statement into that statement, so that the structure they describe is
+/// shown in place. Blank nodes only referred to from such structures in a
+/// cycle stay subjects of their own.
+fn fold_blank_nodes(triples: Vec<Triple>) -> Vec<Triple> {
+    fn count(triple: &Triple, references: &mut HashMap<String, usize>) {
+        if let Some(node) = &triple.object_node {
+            *references.entry(node.clone()).or_default() += 1;
+        }
+        for item in &triple.list {
+            count(item, references);
+        }
+    }
+    fn attach(triple: &mut Triple, nested: &mut BTreeMap<String, Vec<Triple>>) {
+        if let Some(statements) = triple.object_node.as_ref().and_then(|n| nested.remove(n)) {
+            // the nested statements stand for the node
+            triple.object.clear();
+            triple.nested = statements;
+            triple.nested.sort_by(|a, b| a.predicate.cmp(&b.predicate));
+        }
+        for inner in triple.nested.iter_mut().chain(&mut triple.list) {
+            attach(inner, nested);
+        }
+    }
 
-        let _ = parser.parse_step(&mut |t| {
-            let subject = match t.subject {
-                Subject::NamedNode(NamedNode { iri }) => iri.to_string(),
-                Subject::BlankNode(blank) => blank.to_string(),
-                Subject::Triple(_) => String::new(),
-            };
+    let mut references = HashMap::new();
+    for triple in &triples {
+        count(triple, &mut references);
+    }
+    let mut nested: BTreeMap<String, Vec<Triple>> = BTreeMap::new();
+    let mut top = Vec::new();
+    for triple in triples {
+        if references.get(&triple.subject) == Some(&1) {
+            nested
+                .entry(triple.subject.clone())
+                .or_default()
+                .push(triple);
+        } else {
+            top.push(triple);
+        }
+    }
 
-            let predicate = t.predicate.iri.to_string();
-            let object = match t.object {
-                Term::NamedNode(NamedNode { iri }) => iri.to_string(),
-                Term::Literal(Literal::Simple {value}) => value.to_string().trim_matches('"').to_string(),
-                Term::Literal(Literal::Typed {value, datatype}) => {
-                    format!("{}", value)
-                },
-                Term::Literal(Literal::LanguageTaggedString { value, language }) => {
-                    format!("{} (@{})", value, language)
+    for triple in &mut top {
+        attach(triple, &mut nested);
+    }
+    while let Some((_, mut statements)) = nested.pop_first() {
+        for triple in &mut statements {
+            attach(triple, &mut nested);
+        }
+        top.extend(statements);
+    }
+
+    top
+}
+
+/// A statement holding only `member` as its object, for the members of
+/// collections and the objects of triple terms.
+fn term_item(
+    member: &Term,
+    lists: &Lists,
+    blank_nodes: &mut BlankNodeLabels,
+    options: &ConvertOptions,
+) -> Triple {
+    let mut item = Triple::default();
+    if let Term::BlankNode(head) = member {
+        if let Some(members) = lists.members(head) {
+            item.list = members
+                .iter()
+                .map(|member| term_item(member, lists, blank_nodes, options))
+                .collect();
+            return item;
+        }
+    }
+    if let Term::BlankNode(blank) = member {
+        item.object_node = Some(blank_nodes.label(blank));
+    }
+    item.object = match member {
+        Term::NamedNode(iri) => iri.as_str().to_string(),
+        Term::Literal(literal) => {
+            item.lexical_form = Some(literal.value().to_string());
+            item.datatype = Some(literal.datatype().as_str().to_string());
+            item.language = literal.language().map(str::to_string);
+            let value = match display_value(literal.value(), literal.datatype().as_str()) {
+                Some((shown, kind)) => {
+                    item.value_kind = Some(kind);
+                    shown
                 }
-                _ => String::new(),
+                None => literal.value().to_string(),
             };
+            match literal.language() {
+                Some(language) => format!("{} (@{})", value, language),
+                None => value,
+            }
+        }
+        Term::Triple(triple) => {
+            item.quoted = Some(Box::new(quoted_triple(triple, lists, blank_nodes, options)));
+            triple_term_to_string(triple)
+        }
+        Term::BlankNode(blank) => blank_nodes.label(blank),
+    };
+    item
+}
 
-            let triple = Triple {
-                subject_label: subject.clone(),
-                subject,
-                predicate,
-                object,
-                subject_link: None,
-                predicate_link: None,
-                object_link: None,
-            };
+/// A triple term, as a statement of its own so that its terms are linked
+/// and shortened like those of the statements of the page.
+fn quoted_triple(
+    triple: &oxrdf::Triple,
+    lists: &Lists,
+    blank_nodes: &mut BlankNodeLabels,
+    options: &ConvertOptions,
+) -> Triple {
+    let mut quoted = term_item(&triple.object, lists, blank_nodes, options);
+    quoted.subject = match &triple.subject {
+        NamedOrBlankNode::NamedNode(iri) => {
+            quoted.subject_link = Some(iri.as_str().to_string());
+            iri.as_str().to_string()
+        }
+        NamedOrBlankNode::BlankNode(blank) => blank_nodes.label(blank),
+    };
+    quoted.subject_label = quoted.subject.clone();
+    quoted.predicate = triple.predicate.as_str().to_string();
+    quoted
+}
 
-            unparsed_triples.push(triple);
+/// Relabels blank nodes `_:b0`, `_:b1`, ... in order of appearance. The
+/// parser gives anonymous blank nodes random identifiers, which would make
+/// pages differ between runs, and those cannot be told apart from labels
+/// written in the input; relabeling every blank node keeps the two from
+/// ending up with the same label.
+#[derive(Default)]
+struct BlankNodeLabels {
+    labels: HashMap<String, String>,
+}
 
-            Ok::<(), TurtleError>(())
-        });
+impl BlankNodeLabels {
+    fn label(&mut self, blank: &BlankNode) -> String {
+        let next = self.labels.len();
+        self.labels
+            .entry(blank.as_str().to_string())
+            .or_insert_with(|| format!("_:b{}", next))
+            .clone()
+    }
+}
+
+/// Statements of an input file, ready to be grouped and rendered.
+pub struct ParsedFile {
+    triples: Vec<Triple>,
+    /// The statements as JSON-LD, to embed in the pages.
+    json_ld: Option<JsonLd>,
+    types: HashMap<String, Vec<String>>,
+    full_literals: Vec<FullLiteral>,
+    graphs: BTreeMap<Option<String>, usize>,
+    /// Literal values of alternative and hidden label statements by subject.
+    alt_labels: HashMap<String, Vec<String>>,
+    hidden_labels: HashMap<String, Vec<String>>,
+    /// Literal values of comment, definition and description statements by
+    /// subject, for full-text search.
+    descriptions: HashMap<String, Vec<String>>,
+    /// Values and language tags of label statements by subject.
+    labels: HashMap<String, Vec<(String, Option<String>)>>,
+    /// Label shown for each term, with the priority of its predicate.
+    display_labels: HashMap<String, (usize, String)>,
+    namespaces: BTreeMap<String, String>,
+    errors: Vec<Diagnostic>,
+    /// Statements left out by recovery-mode parsing.
+    skipped_statements: usize,
+}
+
+impl ParsedFile {
+    pub fn triples(&self) -> &[Triple] {
+        &self.triples
+    }
+
+    /// Errors recovered from while parsing.
+    pub fn errors(&self) -> &[Diagnostic] {
+        &self.errors
+    }
+}
 
-        let prefixes = parser.prefixes().values().collect::<Vec<&String>>();
+/// Reads the statements of the input file at `input_path`, below
+/// `input_dir`.
+pub fn parse_file(
+    input_path: &Path,
+    input_dir: &str,
+    options: &ConvertOptions,
+) -> Result<ParsedFile, Box<dyn std::error::Error>> {
+    let input = read_input_to_string(input_path)?;
+    let base_iri = options.base_iri_for(input_path.strip_prefix(input_dir)?);
+    let base_iri = base_iri.as_deref();
+    let mut errors = Vec::new();
+    let mut triples = Vec::new();
+    let mut types: HashMap<String, Vec<String>> = HashMap::new();
 
-        for mut triple in unparsed_triples {
-            update_triple_with_links(&mut triple, &prefixes);
+    let mut unparsed_triples = Vec::new();
+    let mut full_literals = Vec::new();
+    let mut graphs = BTreeMap::new();
+    let mut alt_labels: HashMap<String, Vec<String>> = HashMap::new();
+    let mut hidden_labels: HashMap<String, Vec<String>> = HashMap::new();
+    let mut descriptions: HashMap<String, Vec<String>> = HashMap::new();
+    let mut labels: HashMap<String, Vec<(String, Option<String>)>> = HashMap::new();
+    let mut display_labels: HashMap<String, (usize, String)> = HashMap::new();
+    let mut blank_nodes = BlankNodeLabels::default();
+    let format = InputFormat::from_path(input_path).unwrap_or(InputFormat::TriG);
+    // statements are split and parsed as TriG, which also reads N-Triples
+    let splittable = matches!(format, InputFormat::TriG | InputFormat::NTriples);
+    // generalized RDF is read by retrying statements with their unusual
+    // terms replaced
+    let (quads, prefixes, skipped_statements, generalized) =
+        if (options.recover || options.lenient) && splittable {
+            let mut recovered = recover::parse(&input, options.lenient, base_iri)?;
+            if !options.recover && options.on_error != ErrorPolicy::Continue {
+                if let Some(error) = recovered.errors.drain(..).next() {
+                    return Err(error.into());
+                }
+            }
+            errors.extend(recovered.errors);
+            let skipped = if options.recover {
+                recovered.skipped
+            } else {
+                0
+            };
+            (
+                recovered.quads,
+                recovered.prefixes,
+                skipped,
+                recovered.generalized,
+            )
+        } else {
+            let (quads, prefixes) = parse_quads(&input, format, base_iri, options, &mut errors)?;
+            (quads, prefixes, 0, BTreeMap::new())
+        };
+    // terms of generalized statements are shown as written, without links,
+    // and the blank nodes among them labelled like the others
+    let written = |term: String, blank_nodes: &mut BlankNodeLabels| match generalized.get(&term) {
+        Some(text) => match text.strip_prefix("_:").map(BlankNode::new) {
+            Some(Ok(blank)) => blank_nodes.label(&blank),
+            _ => text.clone(),
+        },
+        None => term,
+    };
+    let quads = filter_languages(quads, &options.languages);
 
-            triples.push(triple);
+    let lists = collect_lists(&quads);
+    let linked_quads: Vec<Quad> = if options.json_ld {
+        // JSON-LD has no place for generalized statements
+        quads
+            .iter()
+            .filter(|quad| options.includes_graph(&quad.graph_name))
+            .filter(|quad| !recover::is_generalized(quad))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    for (position, t) in quads.into_iter().enumerate() {
+        if !options.includes_graph(&t.graph_name) {
+            continue;
         }
+        let graph = match &t.graph_name {
+            GraphName::NamedNode(iri) => Some(written(iri.as_str().to_string(), &mut blank_nodes)),
+            GraphName::BlankNode(blank) => Some(blank_nodes.label(blank)),
+            GraphName::DefaultGraph => None,
+        };
+        *graphs.entry(graph.clone()).or_default() += 1;
+        // collections are shown on the statement referring to them
+        if lists.contains(position) {
+            continue;
+        }
+        let subject = match t.subject {
+            NamedOrBlankNode::NamedNode(iri) => written(iri.into_string(), &mut blank_nodes),
+            NamedOrBlankNode::BlankNode(blank) => blank_nodes.label(&blank),
+        };
 
-        if parser.is_end() {
-            break;
+        let predicate = written(t.predicate.into_string(), &mut blank_nodes);
+        if let Term::Literal(literal) = &t.object {
+            // values search finds the subject by
+            let search_values = if ALT_LABEL_PREDICATES.contains(&predicate.as_str()) {
+                Some(&mut alt_labels)
+            } else if predicate == SKOS_HIDDEN_LABEL {
+                Some(&mut hidden_labels)
+            } else if DEFINITION_PREDICATES.contains(&predicate.as_str()) {
+                Some(&mut descriptions)
+            } else {
+                None
+            };
+            if let Some(search_values) = search_values {
+                search_values
+                    .entry(subject.clone())
+                    .or_default()
+                    .push(literal.value().to_string());
+            }
+            if LABEL_PREDICATES.contains(&predicate.as_str()) {
+                labels.entry(subject.clone()).or_default().push((
+                    literal.value().to_string(),
+                    literal.language().map(str::to_string),
+                ));
+            }
+            if let Some(priority) = options
+                .label_properties
+                .iter()
+                .position(|property| *property == predicate)
+            {
+                // the first value of the highest-priority predicate wins
+                match display_labels.get(&subject) {
+                    Some((current, _)) if *current <= priority => {}
+                    _ => {
+                        display_labels
+                            .insert(subject.clone(), (priority, literal.value().to_string()));
+                    }
+                }
+            }
         }
-    }
+        let mut object_direction = None;
+        let mut full_value = None;
+        let mut lexical_form = None;
+        let mut datatype = None;
+        let mut language = None;
+        let mut value_kind = None;
+        let list = match &t.object {
+            Term::BlankNode(head) => lists
+                .members(head)
+                .map(|members| {
+                    members
+                        .iter()
+                        .map(|member| term_item(member, &lists, &mut blank_nodes, options))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let object_node = match &t.object {
+            Term::BlankNode(blank) => Some(blank_nodes.label(blank)),
+            _ => None,
+        };
+        let quoted = match &t.object {
+            Term::Triple(triple) => Some(Box::new(quoted_triple(
+                triple,
+                &lists,
+                &mut blank_nodes,
+                options,
+            ))),
+            _ => None,
+        };
+        let object = match t.object {
+            Term::NamedNode(iri) => iri.into_string(),
+            Term::Literal(literal) => {
+                lexical_form = Some(literal.value().to_string());
+                datatype = Some(literal.datatype().as_str().to_string());
+                language = literal.language().map(str::to_string);
+                let mut value = match display_value(literal.value(), literal.datatype().as_str()) {
+                    Some((shown, kind)) => {
+                        value_kind = Some(kind);
+                        shown
+                    }
+                    None => literal.value().to_string(),
+                };
+                if let Some(max) = options.max_literal_length {
+                    if let Some((cut, _)) = value.char_indices().nth(max) {
+                        full_value = Some(full_literals.len());
+                        full_literals.push(FullLiteral {
+                            subject: subject.clone(),
+                            predicate: predicate.clone(),
+                            value: value.clone(),
+                        });
+                        value.truncate(cut);
+                        value.push('…');
+                    }
+                }
 
-    let mut subject_groups_map = HashMap::new();
-    for triple in triples {
-        subject_groups_map
-            .entry(triple.subject.clone())
-            .or_insert_with(Vec::new)
-            .push(triple)
+                match (literal.language(), literal.direction()) {
+                    (Some(language), Some(direction)) => {
+                        object_direction = Some(direction.to_string());
+                        format!("{} (@{}--{})", value, language, direction)
+                    }
+                    (Some(language), None) => format!("{} (@{})", value, language),
+                    _ => value,
+                }
+            }
+            Term::Triple(triple) => triple_term_to_string(&triple),
+            Term::BlankNode(blank) => blank_nodes.label(&blank),
+        };
+
+        unparsed_triples.push(Triple {
+            subject_label: subject.clone(),
+            subject,
+            predicate,
+            object,
+            object_direction,
+            lexical_form,
+            datatype,
+            language,
+            value_kind,
+            list,
+            quoted,
+            object_node,
+            full_value,
+            graph,
+            ..Default::default()
+        });
     }
 
-    let mut subject_groups: Vec<SubjectGroup> = subject_groups_map
+    let namespaces = prefixes.iter().cloned().collect();
+    // the file's own prefixes come first
+    let mut prefixes = prefixes
         .into_iter()
-        .map(|(subject, mut triples)| {
-            //  sort by predicate
-            triples.sort_by(|a, b| a.predicate.cmp(&b.predicate));
+        .map(|(_, iri)| iri)
+        .collect::<Vec<String>>();
+    for (_, iri) in &options.prefixes {
+        if !prefixes.contains(iri) {
+            prefixes.push(iri.clone());
+        }
+    }
 
-            SubjectGroup {
-                subject,
-                subject_link: triples[0].subject_link.clone(),
-                subject_label: triples[0].subject_label.clone(),
-                triples,
-            }
-        })
-        .collect();
+    for mut triple in unparsed_triples {
+        if options.rdfa {
+            triple.rdfa = Rdfa::of(&triple);
+        }
+        if triple.predicate == RDF_TYPE {
+            types
+                .entry(triple.subject.clone())
+                .or_default()
+                .push(triple.object.clone());
+        }
 
-    // sort by subject
-    subject_groups.sort_by(|a, b| a.subject.cmp(&b.subject));
+        update_triple_with_links(&mut triple, &prefixes, options);
+        triple.for_each_inner(&|item| update_triple_with_links(item, &prefixes, options));
 
-    let mut context = Context::new();
-    context.insert("title", "Definitions");
-    context.insert("subject_groups", &subject_groups);
+        triples.push(triple);
+    }
+    let triples = fold_blank_nodes(triples);
+    // blank nodes get the labels they have on the page
+    let json_ld = options
+        .json_ld
+        .then(|| JsonLd::from_quads(&linked_quads, |blank| blank_nodes.label(blank)));
 
-    let html = tera.render("page", &context)?;
+    Ok(ParsedFile {
+        triples,
+        json_ld,
+        types,
+        full_literals,
+        graphs,
+        alt_labels,
+        hidden_labels,
+        descriptions,
+        labels,
+        display_labels,
+        namespaces,
+        errors,
+        skipped_statements,
+    })
+}
 
-    let relative_path = input_path.strip_prefix(input_dir)?;
-    let output_path = Path::new(output_dir)
-        .join(relative_path)
-        .with_extension("html");
+/// Syntax of an input file, chosen by its extension.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Turtle or TriG, which is a superset of Turtle, so one parser reads
+    /// both.
+    TriG,
+    NTriples,
+    NQuads,
+    RdfXml,
+}
 
-    if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent)?;
+impl InputFormat {
+    /// The format of `path`, or `None` for files that are not RDF input.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ttl" | "trig" => Some(InputFormat::TriG),
+            "nt" => Some(InputFormat::NTriples),
+            "nq" => Some(InputFormat::NQuads),
+            "rdf" | "owl" => Some(InputFormat::RdfXml),
+            _ => None,
+        }
     }
+}
+
+/// Reads all statements of a file with one parser, along with the declared
+/// prefix names and namespace IRIs.
+fn parse_quads(
+    input: &str,
+    format: InputFormat,
+    base_iri: Option<&str>,
+    options: &ConvertOptions,
+    errors: &mut Vec<Diagnostic>,
+) -> Result<(Vec<Quad>, Prefixes), Box<dyn std::error::Error>> {
+    let mut quads = Vec::new();
+    let mut keep = |quad: Result<Quad, TurtleSyntaxError>| match quad {
+        Ok(quad) => {
+            quads.push(quad);
+            Ok(())
+        }
+        // the parser resumes after the invalid statement
+        Err(e) if options.on_error == ErrorPolicy::Continue => {
+            errors.push(Diagnostic::from_syntax_error(&e, input));
+            Ok(())
+        }
+        Err(e) => Err(Diagnostic::from_syntax_error(&e, input)),
+    };
 
-    fs::write(output_path, html)?;
+    let prefixes = match format {
+        InputFormat::TriG => {
+            let mut parser = if options.lenient {
+                TriGParser::new().lenient()
+            } else {
+                TriGParser::new()
+            };
+            if let Some(base_iri) = base_iri {
+                parser = parser.with_base_iri(base_iri)?;
+            }
+            let mut parser = parser.for_slice(input);
+            for quad in parser.by_ref() {
+                keep(quad)?;
+            }
+            parser
+                .prefixes()
+                .map(|(name, iri)| (name.to_string(), iri.to_string()))
+                .collect()
+        }
+        InputFormat::NTriples => {
+            let parser = if options.lenient {
+                NTriplesParser::new().lenient()
+            } else {
+                NTriplesParser::new()
+            };
+            for triple in parser.for_slice(input) {
+                keep(triple.map(|t| t.in_graph(GraphName::DefaultGraph)))?;
+            }
+            Vec::new()
+        }
+        InputFormat::NQuads => {
+            let parser = if options.lenient {
+                NQuadsParser::new().lenient()
+            } else {
+                NQuadsParser::new()
+            };
+            for quad in parser.for_slice(input) {
+                keep(quad)?;
+            }
+            Vec::new()
+        }
+        InputFormat::RdfXml => {
+            let mut parser = if options.lenient {
+                RdfXmlParser::new().lenient()
+            } else {
+                RdfXmlParser::new()
+            };
+            if let Some(base_iri) = base_iri {
+                parser = parser.with_base_iri(base_iri)?;
+            }
+            let mut parser = parser.for_slice(input);
+            while let Some(triple) = parser.next() {
+                match triple {
+                    Ok(triple) => keep(Ok(triple.in_graph(GraphName::DefaultGraph)))?,
+                    // malformed XML leaves nothing to resume from
+                    Err(e) => {
+                        let offset = parser.buffer_position() as usize;
+                        let diagnostic = Diagnostic::at_offset(input, offset, &e.to_string());
+                        if options.on_error != ErrorPolicy::Continue {
+                            return Err(diagnostic.into());
+                        }
+                        errors.push(diagnostic);
+                        break;
+                    }
+                }
+            }
+            parser
+                .prefixes()
+                .map(|(name, iri)| (name.to_string(), iri.to_string()))
+                .collect()
+        }
+    };
 
-    Ok(relative_path.with_extension("html").to_path_buf())
+    Ok((quads, prefixes))
 }
 
-pub fn generate_index(
-    output_dir: &str,
-    entries: &[IndexEntry],
-    tera: &Tera,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut context = Context::new();
-    context.insert("title", "Index of RDF Files");
-    context.insert("entries", entries);
+/// A parsed file with its statements grouped into the entries of its page.
+pub struct GroupedFile {
+    relative_path: PathBuf,
+    triple_count: usize,
+    subject_groups: Vec<SubjectGroup>,
+    concept_trees: Vec<ConceptTree>,
+    json_ld: Option<JsonLd>,
+    full_literals: Vec<FullLiteral>,
+    graphs: BTreeMap<Option<String>, usize>,
+    alt_labels: HashMap<String, Vec<String>>,
+    hidden_labels: HashMap<String, Vec<String>>,
+    descriptions: HashMap<String, Vec<String>>,
+    subjects: Vec<concordance::Subject>,
+    usage: FileUsage,
+    label_uses: Vec<LabelUse>,
+    namespaces: BTreeMap<String, String>,
+    errors: Vec<Diagnostic>,
+    skipped_statements: usize,
+    /// Named concepts and their broader concepts on the page.
+    broader: Vec<(String, String)>,
+    /// Named concepts related to each other on the page, in both orders.
+    related: Vec<(String, String)>,
+}
 
-    let html = tera.render("index", &context)?;
+impl GroupedFile {
+    /// Entries of the page, one per subject, in page order.
+    pub fn subject_groups(&self) -> &[SubjectGroup] {
+        &self.subject_groups
+    }
 
-    let index_path = Path::new(output_dir).join("index.html");
-    fs::write(index_path, html)?;
+    /// Named subjects of the file.
+    pub fn subjects(&self) -> &[concordance::Subject] {
+        &self.subjects
+    }
 
-    Ok(())
+    /// What the pages of other files need to know about this one.
+    pub fn into_outline(self) -> Outline {
+        Outline {
+            subjects: self.subjects,
+            labels: self.label_uses,
+            broader: self.broader,
+            related: self.related,
+        }
+    }
+}
+
+/// Groups the statements of a parsed file by subject. `source_path` is the
+/// path of the input file relative to the input directory. Fails only when
+/// the statements of a file past [`ConvertOptions::group_memory`] cannot be
+/// sorted on disk.
+pub fn group_file(
+    parsed: ParsedFile,
+    source_path: &Path,
+    options: &ConvertOptions,
+) -> Result<GroupedFile, Box<dyn std::error::Error>> {
+    let ParsedFile {
+        mut triples,
+        json_ld,
+        mut types,
+        full_literals,
+        graphs,
+        mut alt_labels,
+        mut hidden_labels,
+        mut descriptions,
+        labels,
+        display_labels,
+        namespaces,
+        errors,
+        skipped_statements,
+    } = parsed;
+    let mut aliases = HashMap::new();
+    if options.merge_same_as {
+        aliases = merge_same_as(&mut triples, &display_labels);
+        for values in [&mut alt_labels, &mut hidden_labels, &mut descriptions] {
+            merge_values(values, &aliases);
+        }
+        merge_values(&mut types, &aliases);
+    }
+    let output_source_path = options.output_source_path(source_path);
+    let relative_path = output_source_path.with_extension("html");
+    let triple_count = triples.len();
+    let mut usage = FileUsage::default();
+    for triple in triples.iter().flat_map(Triple::statements) {
+        *usage
+            .predicates
+            .entry(triple.predicate_iri().to_string())
+            .or_default() += 1;
+    }
+    for class in types.values().flatten() {
+        *usage.instances.entry(class.clone()).or_default() += 1;
+    }
+    let axioms = extract_axioms(&mut triples, &types);
+    let relabel = |triple: &mut Triple| {
+        if let Some((_, label)) = triple
+            .object_link
+            .as_ref()
+            .and_then(|iri| display_labels.get(iri))
+        {
+            triple.object = label.clone();
+        }
+    };
+    for triple in &mut triples {
+        relabel(triple);
+        triple.for_each_inner(&relabel);
+    }
+    let links = term_links(&triples, &display_labels);
+    let mut equivalents = collect_equivalents(&triples, &links);
+    let hierarchy = ConceptHierarchy::new(&triples);
+    let broader = named_pairs(&hierarchy.broader);
+    let related = named_pairs(&hierarchy.related);
+    let mut concepts = concept_navigation(
+        &hierarchy.with_corpus(&options.corpus),
+        &types,
+        &links,
+        &options.corpus,
+    );
+    let mut concept_details = concept_details(&triples, &types, !options.ontology);
+    let mut concept_trees = concept_trees(&triples, &hierarchy, &types, &links);
+    let mut label_uses: Vec<LabelUse> = labels
+        .into_iter()
+        .filter(|(subject, _)| !subject.starts_with("_:"))
+        .flat_map(|(iri, labels)| {
+            let page = url_path(&relative_path);
+            labels.into_iter().map(move |(label, language)| LabelUse {
+                iri: iri.clone(),
+                label,
+                language,
+                page: page.clone(),
+            })
+        })
+        .collect();
+    label_uses.sort_by(|a, b| a.iri.cmp(&b.iri).then_with(|| a.label.cmp(&b.label)));
+    // terms sharing a label across files are told apart as well
+    let shared_labels = if options.corpus.is_empty() {
+        &label_uses
+    } else {
+        options.corpus.shared_labels()
+    };
+    let mut homonyms = homonyms(shared_labels, &links);
+    let mut summaries = if options.ontology {
+        term_summaries(&triples, &links)
+    } else {
+        HashMap::new()
+    };
+    let named_shapes = named_property_shapes(&triples, &links);
+    drop(links);
+
+    // sorting puts each subject's statements in one run, sorted by
+    // predicate, without holding a map of per-subject vectors next to them;
+    // files past the memory budget are sorted in runs on disk
+    let compare = |a: &Triple, b: &Triple| {
+        a.graph
+            .cmp(&b.graph)
+            .then_with(|| a.subject.cmp(&b.subject))
+            .then_with(|| a.predicate.cmp(&b.predicate))
+    };
+    let mut spill_error = None;
+    let sorted: Box<dyn Iterator<Item = Triple>> = match options.group_memory {
+        Some(budget) if triples.iter().map(Triple::memory_size).sum::<usize>() > budget => {
+            let merged = sort_spilled(triples, budget, Triple::memory_size, compare)?;
+            Box::new(merged.map_while(|triple| triple.map_err(|e| spill_error = Some(e)).ok()))
+        }
+        _ => {
+            triples.sort_by(compare);
+            Box::new(triples.into_iter())
+        }
+    };
+
+    let mut subject_groups = Vec::new();
+    let mut triples = sorted.peekable();
+    while let Some(first) = triples.next() {
+        let subject = first.subject.clone();
+        let subject_link = first.subject_link.clone();
+        let name = first.subject_label.clone();
+        let subject_label = display_label(&display_labels, &subject, &name);
+        let graph = first.graph.clone();
+        let mut group = vec![first];
+        while let Some(triple) = triples.next_if(|t| t.subject == subject && t.graph == graph) {
+            group.push(triple);
+        }
+        // equivalences get their own section instead of table rows
+        group.retain(|t| !is_equivalence(t));
+        let shape = shape_view(&mut group, types.get(&subject), &named_shapes);
+        let equivalents = equivalents.remove(&subject).unwrap_or_default();
+        let concept = concepts.remove(&subject);
+        let concept_details = concept_details.remove(&subject);
+        let homonyms = homonyms.remove(&subject).unwrap_or_default();
+        let aliases = aliases.get(&subject).cloned().unwrap_or_default();
+
+        let roles = subject_roles(types.get(&subject));
+        // a punned subject gets one section per role, so that a single
+        // entry documents all of them
+        let role_sections = if roles.len() > 1 {
+            split_role_sections(&mut group, &roles)
+        } else {
+            Vec::new()
+        };
+        let primary_type = group
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.predicate_iri() == RDF_TYPE && t.object_link.is_some())
+            .min_by(|(_, a), (_, b)| compare_types(a, b, &options.label_order))
+            .map(|(position, _)| position);
+        let term_section = options
+            .ontology
+            .then(|| TermSection::of(&subject, types.get(&subject)));
+        let summary = summaries.remove(&subject).unwrap_or_default();
+
+        subject_groups.push(SubjectGroup {
+            subject,
+            graph,
+            name,
+            subject_link,
+            subject_label,
+            anchor: String::new(),
+            page: None,
+            triples: group,
+            primary_type,
+            term_section,
+            summary,
+            roles,
+            role_sections,
+            equivalents,
+            concept,
+            concept_details,
+            shape,
+            footnotes: Vec::new(),
+            homonyms,
+            aliases,
+        });
+    }
+    drop(triples);
+    if let Some(e) = spill_error {
+        return Err(format!("Cannot read back statements sorted on disk: {}", e).into());
+    }
+
+    // groups come out sorted by graph and subject; re-sort by label within
+    // each graph when a label order is configured
+    if let Some(label_order) = &options.label_order {
+        subject_groups.sort_by(|a, b| {
+            a.graph
+                .cmp(&b.graph)
+                .then_with(|| label_order.compare(&a.subject_label, &b.subject_label))
+                .then_with(|| a.subject.cmp(&b.subject))
+        });
+    }
+
+    // the sort is stable, so entries keep their order within each section;
+    // entries without a type come last
+    match options.sectioning() {
+        Sectioning::ByType => subject_groups.sort_by(|a, b| {
+            a.graph
+                .cmp(&b.graph)
+                .then_with(|| match (a.primary_type(), b.primary_type()) {
+                    (Some(a), Some(b)) => compare_types(a, b, &options.label_order),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                })
+        }),
+        Sectioning::ByTermSection => subject_groups.sort_by(|a, b| {
+            a.graph
+                .cmp(&b.graph)
+                .then_with(|| a.term_section.cmp(&b.term_section))
+        }),
+        Sectioning::None => {}
+    }
+
+    attach_footnotes(&mut subject_groups, axioms);
+    let mut anchors = assign_anchors(&mut subject_groups);
+    if options.split_by_subject {
+        assign_pages(&mut subject_groups, &relative_path, options.slugify);
+    }
+    // where statements link to for the subjects of the file; split pages
+    // are siblings of each other
+    let mut entry_links = HashMap::new();
+    for group in &subject_groups {
+        let link = match &group.page {
+            Some(page) => format!("{}#{}", page.rsplit('/').next().unwrap(), group.anchor),
+            None => format!("#{}", group.anchor),
+        };
+        for alias in &group.aliases {
+            entry_links
+                .entry(alias.iri.clone())
+                .or_insert_with(|| link.clone());
+            anchors
+                .entry(alias.iri.clone())
+                .or_insert_with(|| group.anchor.clone());
+        }
+        entry_links.entry(group.subject.clone()).or_insert(link);
+    }
+    let tree_links = concept_trees
+        .iter_mut()
+        .flat_map(ConceptTree::term_links_mut);
+    for term in subject_groups
+        .iter_mut()
+        .flat_map(SubjectGroup::term_links_mut)
+        .chain(tree_links)
+    {
+        term.anchor = None;
+        if let Some(anchor) = anchors.get(&term.iri) {
+            if options.split_by_subject {
+                term.iri = entry_links[&term.iri].clone();
+            } else {
+                term.anchor = Some(anchor.clone());
+            }
+        }
+    }
+
+    // collected before linking to documentation, so types keep their IRIs;
+    // merged IRIs lead to the entry they were merged into
+    let subjects = subject_groups
+        .iter()
+        .filter(|group| !group.subject.starts_with("_:"))
+        .flat_map(|group| {
+            let aliases = group.aliases.iter().map(|alias| &alias.iri);
+            std::iter::once(&group.subject)
+                .chain(aliases)
+                .map(move |iri| (group, iri))
+        })
+        .map(|(group, iri)| concordance::Subject {
+            label: group.subject_label.clone(),
+            iri: iri.clone(),
+            types: group
+                .triples
+                .iter()
+                .filter(|t| t.predicate_iri() == RDF_TYPE)
+                .filter_map(|t| Some((t.object.clone(), t.object_link.clone()?)))
+                .collect(),
+            page: group.entry_page(&relative_path),
+            anchor: Some(group.anchor.clone()),
+            source: url_path(&output_source_path),
+        })
+        .collect();
+
+    for group in &subject_groups {
+        for role in &group.roles {
+            usage.definitions.push(Definition {
+                iri: group.subject.clone(),
+                label: group.subject_label.clone(),
+                kind: match *role {
+                    ROLE_CLASS => TermKind::Class,
+                    _ => TermKind::Property,
+                },
+                page: group.entry_page(&relative_path),
+                anchor: Some(group.anchor.clone()),
+            });
+        }
+    }
+
+    // terms described in the file link to their entry
+    for group in &mut subject_groups {
+        for triple in group.all_triples_mut() {
+            for link in triple.links_mut() {
+                if let Some(entry) = link.as_deref().and_then(|iri| entry_links.get(iri)) {
+                    *link = Some(entry.clone());
+                }
+            }
+        }
+    }
+
+    if !options.documentation_links.is_empty()
+        || !options.site_links.is_empty()
+        || options.specification_links
+    {
+        // statements are shown on the split pages, a level further down
+        let root = match subject_groups.first().and_then(|group| group.page.as_ref()) {
+            Some(page) => root_prefix(&relative_path.with_file_name(page)),
+            None => root_prefix(&relative_path),
+        };
+        link_documentation(
+            &mut subject_groups,
+            &mut concept_trees,
+            &url_path(&relative_path),
+            &root,
+            options,
+        );
+    }
+
+    Ok(GroupedFile {
+        relative_path,
+        triple_count,
+        subject_groups,
+        concept_trees,
+        json_ld,
+        full_literals,
+        graphs,
+        alt_labels,
+        hidden_labels,
+        descriptions,
+        subjects,
+        usage,
+        label_uses,
+        namespaces,
+        errors,
+        skipped_statements,
+        broader,
+        related,
+    })
+}
+
+/// Groups the statements of a parsed file by subject and writes its page.
+pub fn render_file(
+    parsed: ParsedFile,
+    input_path: &Path,
+    input_dir: &str,
+    output: &dyn OutputBackend,
+    tera: &Tera,
+    base_context: &Context,
+    options: &ConvertOptions,
+) -> Result<Converted, Box<dyn std::error::Error>> {
+    let source_path = input_path.strip_prefix(input_dir)?;
+    let GroupedFile {
+        relative_path,
+        triple_count,
+        mut subject_groups,
+        mut concept_trees,
+        json_ld,
+        full_literals,
+        graphs,
+        mut alt_labels,
+        mut hidden_labels,
+        mut descriptions,
+        subjects,
+        usage,
+        label_uses,
+        namespaces,
+        errors,
+        skipped_statements,
+        ..
+    } = group_file(parsed, source_path, options)?;
+
+    // statements link to documentation by now, so the types are those
+    // collected for the concordance
+    let type_names: HashMap<&str, Vec<String>> = subjects
+        .iter()
+        .map(|subject| {
+            let names = subject.types.iter().map(|(name, _)| name.clone());
+            (subject.iri.as_str(), names.collect())
+        })
+        .collect();
+    // blank nodes have no IRI to look up
+    let search_entries = subject_groups
+        .iter()
+        .filter(|group| !group.subject.starts_with("_:"))
+        .map(|group| SearchEntry {
+            label: group.subject_label.clone(),
+            iri: group.subject.clone(),
+            page: group.entry_page(&relative_path),
+            anchor: group.anchor.clone(),
+            types: type_names
+                .get(group.subject.as_str())
+                .cloned()
+                .unwrap_or_default(),
+            alt_labels: alt_labels.remove(&group.subject).unwrap_or_default(),
+            hidden_labels: hidden_labels.remove(&group.subject).unwrap_or_default(),
+            descriptions: descriptions.remove(&group.subject).unwrap_or_default(),
+        })
+        .collect();
+
+    let mut outputs = Vec::new();
+    let mut literals_attachment = None;
+    if !full_literals.is_empty() {
+        let attachment_path = relative_path.with_extension("literals.json");
+        output.write(
+            &attachment_path,
+            serde_json::to_string_pretty(&full_literals)?.as_bytes(),
+        )?;
+        outputs.push(attachment_path.clone());
+        literals_attachment = attachment_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+    }
+
+    let print_path = relative_path.with_extension("print.html");
+    let print_link = options.print_view.then(|| {
+        print_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    });
+    let source_view_path = relative_path.with_extension("source.html");
+    let source_link = options
+        .source_view
+        .then(|| source_view_path.file_name())
+        .flatten()
+        .map(|name| name.to_string_lossy().to_string());
+
+    // links shown on the page of the file lead from the entry pages
+    let entry_directory = options
+        .split_by_subject
+        .then(|| relative_path.file_stem())
+        .flatten()
+        .map(|stem| stem.to_string_lossy().to_string());
+    let entry_directory = entry_directory.as_deref();
+    if let Some(directory) = entry_directory {
+        for tree in &mut concept_trees {
+            for term in tree.term_links_mut() {
+                term.iri = link_from_parent(&term.iri, directory);
+            }
+        }
+    }
+    let source_file = if options.copy_sources {
+        let copy_path = options.output_source_path(source_path);
+        output.write(&copy_path, &read_input(input_path)?)?;
+        outputs.push(copy_path.clone());
+        copy_path.file_name().map(|name| SourceFile {
+            href: name.to_string_lossy().to_string(),
+            name: name.to_string_lossy().to_string(),
+            media_type: media_type(source_path),
+        })
+    } else {
+        None
+    };
+
+    let mut context = base_context.clone();
+    context.insert("literals_attachment", &literals_attachment);
+    context.insert("source_file", &source_file);
+    // an ontology documented on the page gives it its title
+    let title = subject_groups
+        .iter()
+        .find(|group| group.term_section == Some(TermSection::Ontology))
+        .map_or("Definitions", |group| group.subject_label.as_str());
+    context.insert("title", title);
+    context.insert("root", &root_prefix(&relative_path));
+    context.insert("subject_groups", &subject_groups);
+    context.insert(
+        "graph_sections",
+        &graph_sections(&subject_groups, options.sectioning(), entry_directory),
+    );
+    context.insert("concept_trees", &concept_trees);
+    context.insert("json_ld", &json_ld.as_ref().and_then(JsonLd::document));
+    context.insert("print_link", &print_link.flatten());
+    context.insert("source_link", &source_link);
+    context.insert("skipped_statements", &skipped_statements);
+    context.insert("parse_errors", &errors);
+
+    if options.split_by_subject {
+        context.insert("overview", &true);
+    }
+    context.insert("section_nav", &(options.sectioning() != Sectioning::None));
+
+    let html = tera.render("page.html", &context)?;
+
+    output.write(&relative_path, html.as_bytes())?;
+    outputs.push(relative_path.clone());
+
+    if options.split_by_subject {
+        let overview_link = relative_path
+            .file_name()
+            .map(|name| format!("../{}", name.to_string_lossy()));
+        let mut context = base_context.clone();
+        context.insert(
+            "literals_attachment",
+            &literals_attachment
+                .as_ref()
+                .map(|name| format!("../{}", name)),
+        );
+        context.insert("overview_link", &overview_link);
+        context.insert(
+            "source_file",
+            &source_file.as_ref().map(|file| SourceFile {
+                href: format!("../{}", file.href),
+                ..file.clone()
+            }),
+        );
+        context.insert("print_link", &None::<String>);
+        context.insert(
+            "source_link",
+            &source_link.as_ref().map(|name| format!("../{}", name)),
+        );
+        for group in &subject_groups {
+            let Some(page) = &group.page else { continue };
+            let page_path = relative_path.with_file_name(page);
+            let groups = std::slice::from_ref(group);
+            context.insert("title", &group.subject_label);
+            context.insert("root", &root_prefix(&page_path));
+            context.insert("subject_groups", groups);
+            context.insert(
+                "graph_sections",
+                &graph_sections(groups, Sectioning::None, None),
+            );
+            context.insert(
+                "json_ld",
+                &json_ld
+                    .as_ref()
+                    .and_then(|json_ld| json_ld.about(&group.subject)),
+            );
+
+            let html = tera.render("page.html", &context)?;
+            output.write(&page_path, html.as_bytes())?;
+            outputs.push(page_path);
+        }
+    }
+
+    if options.print_view {
+        expand_literals(&mut subject_groups, &full_literals);
+        context.insert("subject_groups", &subject_groups);
+        context.insert(
+            "graph_sections",
+            &graph_sections(&subject_groups, options.sectioning(), None),
+        );
+        context.insert("print_link", &None::<String>);
+        context.insert("source_link", &None::<String>);
+        context.insert("print_view", &true);
+
+        let html = tera.render("page.html", &context)?;
+        output.write(&print_path, html.as_bytes())?;
+        outputs.push(print_path);
+    }
+
+    if options.source_view {
+        let source = read_input_to_string(input_path)?;
+        let format = InputFormat::from_path(input_path).unwrap_or(InputFormat::TriG);
+        let error_lines: HashSet<u64> = errors.iter().map(|error| error.line).collect();
+        let lines: Vec<SourceLine> = highlight_lines(&source, format)
+            .into_iter()
+            .zip(1..)
+            .map(|(html, number)| SourceLine {
+                number,
+                html,
+                error: error_lines.contains(&number),
+            })
+            .collect();
+        let mut context = base_context.clone();
+        context.insert("title", &url_path(source_path));
+        context.insert("root", &root_prefix(&source_view_path));
+        context.insert(
+            "page_link",
+            &relative_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+        );
+        context.insert("lines", &lines);
+
+        let html = tera.render("source.html", &context)?;
+        output.write(&source_view_path, html.as_bytes())?;
+        outputs.push(source_view_path);
+    }
+
+    Ok(Converted {
+        path: relative_path,
+        outputs,
+        triples: triple_count,
+        graphs,
+        search_entries,
+        subjects,
+        usage,
+        labels: label_uses,
+        namespaces,
+        errors,
+    })
+}
+
+/// The input file of a page, copied next to it.
+#[derive(Serialize, Clone)]
+struct SourceFile {
+    href: String,
+    name: String,
+    /// Media type of the file, without parameters.
+    media_type: &'static str,
+}
+
+/// A line of the source view of a page, as highlighted HTML.
+#[derive(Serialize)]
+struct SourceLine {
+    number: u64,
+    html: String,
+    /// Whether a parse error was reported on the line.
+    error: bool,
+}
+
+/// Entries of one graph of a page, shown under a heading for named graphs.
+#[derive(Serialize)]
+struct GraphSection<'a> {
+    graph: Option<&'a str>,
+    subject_groups: &'a [SubjectGroup],
+    sections: Vec<EntrySection<'a>>,
+}
+
+/// Entries of a graph sharing a type, or a kind of term in ontology mode,
+/// shown under a heading. Without sections a graph has a single one without
+/// `id`.
+#[derive(Serialize)]
+struct EntrySection<'a> {
+    id: Option<String>,
+    /// The heading, and where it links to; `None` for the entries without a
+    /// type.
+    title: Option<&'a str>,
+    link: Option<String>,
+    subject_groups: &'a [SubjectGroup],
+}
+
+/// How the entries of a graph are split into sections.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sectioning {
+    None,
+    ByType,
+    ByTermSection,
+}
+
+/// Splits entries sorted by graph into one section per graph, and those
+/// into sections as set by `sectioning`. Links of the entries are relative
+/// to the pages in `entry_directory`, when the sections are shown on the
+/// page above it.
+fn graph_sections<'a>(
+    groups: &'a [SubjectGroup],
+    sectioning: Sectioning,
+    entry_directory: Option<&str>,
+) -> Vec<GraphSection<'a>> {
+    let mut count = 0;
+    let mut section = |title, link, subject_groups| {
+        count += 1;
+        EntrySection {
+            id: Some(format!("section-{}", count)),
+            title,
+            link,
+            subject_groups,
+        }
+    };
+    groups
+        .chunk_by(|a, b| a.graph == b.graph)
+        .map(|groups| {
+            let sections = match sectioning {
+                Sectioning::ByType => groups
+                    .chunk_by(|a, b| {
+                        a.primary_type().map(Triple::object_key)
+                            == b.primary_type().map(Triple::object_key)
+                    })
+                    .map(|groups| {
+                        let primary_type = groups[0].primary_type();
+                        let link = primary_type.and_then(Triple::object_link).map(|link| {
+                            match entry_directory {
+                                Some(directory) => link_from_parent(link, directory),
+                                None => link.to_string(),
+                            }
+                        });
+                        section(primary_type.map(Triple::object), link, groups)
+                    })
+                    .collect(),
+                Sectioning::ByTermSection => groups
+                    .chunk_by(|a, b| a.term_section == b.term_section)
+                    .map(|groups| {
+                        let title = groups[0].term_section.map(TermSection::title);
+                        section(title, None, groups)
+                    })
+                    .collect(),
+                Sectioning::None => vec![EntrySection {
+                    id: None,
+                    title: None,
+                    link: None,
+                    subject_groups: groups,
+                }],
+            };
+            GraphSection {
+                graph: groups[0].graph.as_deref(),
+                subject_groups: groups,
+                sections,
+            }
+        })
+        .collect()
+}
+
+/// A relative `link` on a page in `directory`, as seen from the page above.
+fn link_from_parent(link: &str, directory: &str) -> String {
+    if is_valid_url(link) {
+        return link.to_string();
+    }
+    match link.strip_prefix("../") {
+        Some(rest) => rest.to_string(),
+        None => format!("{}/{}", directory, link),
+    }
+}
+
+/// Orders `rdf:type` statements by the type they name, as shown.
+fn compare_types(a: &Triple, b: &Triple, label_order: &Option<LabelOrder>) -> Ordering {
+    match label_order {
+        Some(label_order) => label_order.compare(&a.object, &b.object),
+        None => a.object.cmp(&b.object),
+    }
+    .then_with(|| a.object_key().cmp(b.object_key()))
+}
+
+/// Writes `index.html`. When there are more than `index_page_size` entries,
+/// they are split into `index-N.html` pages and `index.html` only lists the
+/// pages with the range of names each one covers.
+pub fn generate_index(
+    output: &dyn OutputBackend,
+    entries: &[IndexEntry],
+    tera: &Tera,
+    base_context: &Context,
+    options: &ConvertOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let page_size = match options.index_page_size {
+        Some(page_size) if entries.len() > page_size => Some(page_size.max(1)),
+        _ => None,
+    };
+    let link = |path: &str| -> Result<String, url::ParseError> {
+        match &options.index_base_url {
+            Some(base_url) => Ok(page_url(base_url, Path::new(path))?.into()),
+            None => Ok(path.to_string()),
+        }
+    };
+
+    // entries keep their discovery order on a single page unless a label
+    // order is configured; pages are always sorted so their ranges make sense
+    let mut entries = entries
+        .iter()
+        .map(|entry| Ok(IndexEntry::new(link(&entry.path)?, entry.name.clone())))
+        .collect::<Result<Vec<_>, url::ParseError>>()?;
+    if let Some(label_order) = &options.label_order {
+        entries.sort_by(|a, b| {
+            label_order
+                .compare(&a.name, &b.name)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+    } else if page_size.is_some() {
+        entries.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+    }
+
+    let Some(page_size) = page_size else {
+        return write_index_page(
+            output,
+            "index.html",
+            "Index of RDF Files",
+            &entries,
+            None,
+            tera,
+            base_context,
+        );
+    };
+
+    let pages: Vec<&[IndexEntry]> = entries.chunks(page_size).collect();
+    let mut page_entries = Vec::new();
+
+    for (i, page) in pages.iter().enumerate() {
+        let path = format!("index-{}.html", i + 1);
+        let pagination = Pagination {
+            current: i + 1,
+            total: pages.len(),
+            previous: (i > 0)
+                .then(|| link(&format!("index-{}.html", i)))
+                .transpose()?,
+            next: (i + 1 < pages.len())
+                .then(|| link(&format!("index-{}.html", i + 2)))
+                .transpose()?,
+        };
+
+        let title = format!("Index of RDF Files ({}/{})", i + 1, pages.len());
+        write_index_page(
+            output,
+            &path,
+            &title,
+            page,
+            Some(&pagination),
+            tera,
+            base_context,
+        )?;
+
+        let first = &page[0].name;
+        let last = &page[page.len() - 1].name;
+        page_entries.push(IndexEntry::new(
+            link(&path)?,
+            format!("{} – {}", first, last),
+        ));
+    }
+
+    write_index_page(
+        output,
+        "index.html",
+        "Index of RDF Files",
+        &page_entries,
+        None,
+        tera,
+        base_context,
+    )
+}
+
+#[derive(Serialize)]
+pub struct Pagination {
+    pub current: usize,
+    pub total: usize,
+    pub previous: Option<String>,
+    pub next: Option<String>,
+}
+
+fn write_index_page(
+    output: &dyn OutputBackend,
+    path: &str,
+    title: &str,
+    entries: &[IndexEntry],
+    pagination: Option<&Pagination>,
+    tera: &Tera,
+    base_context: &Context,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut context = base_context.clone();
+    context.insert("title", title);
+    context.insert("root", "");
+    context.insert("entries", entries);
+    context.insert("pagination", &pagination);
+
+    let html = tera.render("index.html", &context)?;
+
+    output.write(Path::new(path), html.as_bytes())?;
+
+    Ok(())
+}
+
+const ROLE_CLASS: &str = "a class";
+const ROLE_PROPERTY: &str = "a property";
+
+fn subject_roles(types: Option<&Vec<String>>) -> Vec<&'static str> {
+    let types = types.map(Vec::as_slice).unwrap_or_default();
+    let mut roles = Vec::new();
+
+    if types.iter().any(|t| CLASS_TYPES.contains(&t.as_str())) {
+        roles.push(ROLE_CLASS);
+    }
+    if types.iter().any(|t| PROPERTY_TYPES.contains(&t.as_str())) {
+        roles.push(ROLE_PROPERTY);
+    }
+
+    roles
+}
+
+/// Moves the role-specific triples out of `triples` into one section per role;
+/// shared triples (types, labels, comments) stay in `triples`.
+fn split_role_sections(triples: &mut Vec<Triple>, roles: &[&'static str]) -> Vec<RoleSection> {
+    let mut sections = Vec::new();
+
+    for role in roles {
+        let predicates = match *role {
+            ROLE_CLASS => CLASS_PREDICATES,
+            _ => PROPERTY_PREDICATES,
+        };
+
+        let (role_triples, rest): (Vec<Triple>, Vec<Triple>) = std::mem::take(triples)
+            .into_iter()
+            .partition(|t| predicates.contains(&t.predicate_iri()));
+        *triples = rest;
+
+        if !role_triples.is_empty() {
+            sections.push(RoleSection {
+                title: role,
+                triples: role_triples,
+            });
+        }
+    }
+
+    sections
+}
+
+/// Property shapes described on their own, as the rows they give the
+/// node shapes referring to them, by IRI.
+fn named_property_shapes(
+    triples: &[Triple],
+    links: &HashMap<&str, TermLink>,
+) -> HashMap<String, PropertyConstraint> {
+    let mut statements: HashMap<&str, Vec<Triple>> = HashMap::new();
+    for t in triples {
+        if t.predicate_iri() == SH_PATH && !t.subject.starts_with("_:") {
+            statements.entry(&t.subject).or_default();
+        }
+    }
+    for t in triples {
+        if let Some(shape) = statements.get_mut(t.subject.as_str()) {
+            shape.push(t.clone());
+        }
+    }
+
+    statements
+        .into_iter()
+        .map(|(subject, mut statements)| {
+            let mut row = property_constraint(&mut statements);
+            row.shape = Some(links[subject].clone());
+            (subject.to_string(), row)
+        })
+        .collect()
+}
+
+/// Moves the statements of a property shape that have a column of the
+/// constraint table out of `statements` into a row.
+fn property_constraint(statements: &mut Vec<Triple>) -> PropertyConstraint {
+    let mut row = PropertyConstraint::default();
+    let mut min_count = None;
+    let mut max_count = None;
+    let mut rest = Vec::new();
+    for t in std::mem::take(statements) {
+        match t.predicate_iri() {
+            SH_PATH => row.path.push(t),
+            SH_MIN_COUNT => min_count = t.lexical_form,
+            SH_MAX_COUNT => max_count = t.lexical_form,
+            SH_MESSAGE => row.message.push(t),
+            p if SHAPE_VALUE_TYPE_PREDICATES.contains(&p) => row.value_type.push(t),
+            p => {
+                if p == SH_ORDER {
+                    row.order = t
+                        .lexical_form
+                        .as_deref()
+                        .and_then(|v| v.trim().parse().ok());
+                }
+                rest.push(t);
+            }
+        }
+    }
+    row.cardinality = match (min_count, max_count) {
+        (None, None) => None,
+        (min, max) => {
+            let min = min.unwrap_or_else(|| "0".to_string());
+            let max = max.unwrap_or_else(|| "*".to_string());
+            Some(if min == max {
+                min
+            } else {
+                format!("{}..{}", min, max)
+            })
+        }
+    };
+    *statements = rest;
+    row
+}
+
+/// Moves the targets and property constraints of a SHACL shape out of its
+/// statements. Property shapes nested in the shape become rows with the
+/// rest of their statements in the last column; those described on their
+/// own are looked up in `named`. A property shape gets a row for itself.
+fn shape_view(
+    statements: &mut Vec<Triple>,
+    types: Option<&Vec<String>>,
+    named: &HashMap<String, PropertyConstraint>,
+) -> Option<ShapeView> {
+    let is_shape = types
+        .is_some_and(|types| types.iter().any(|t| SHAPE_TYPES.contains(&t.as_str())))
+        || statements
+            .iter()
+            .any(|t| matches!(t.predicate_iri(), SH_PROPERTY | SH_PATH));
+    if !is_shape {
+        return None;
+    }
+
+    let mut targets = Vec::new();
+    let mut properties = Vec::new();
+    let mut rest = Vec::new();
+    for mut t in std::mem::take(statements) {
+        let predicate = t.predicate_iri();
+        if SHAPE_TARGET_PREDICATES.contains(&predicate) {
+            targets.push(t);
+        } else if predicate == SH_PROPERTY && !t.nested.is_empty() {
+            let mut row = property_constraint(&mut t.nested);
+            // the order of the rows already shows `sh:order`
+            t.nested.retain(|t| t.predicate_iri() != SH_ORDER);
+            row.other = t.nested;
+            properties.push(row);
+        } else if let Some(row) = (predicate == SH_PROPERTY)
+            .then(|| t.object_link.as_ref().and_then(|iri| named.get(iri)))
+            .flatten()
+        {
+            properties.push(row.clone());
+        } else {
+            rest.push(t);
+        }
+    }
+    *statements = rest;
+    if statements.iter().any(|t| t.predicate_iri() == SH_PATH) {
+        properties.push(property_constraint(statements));
+    }
+
+    // rows in `sh:order`, then by path
+    properties.sort_by(|a, b| {
+        match (a.order, b.order) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        }
+        .then_with(|| {
+            let path = |row: &PropertyConstraint| row.path.first().map(|t| t.object.clone());
+            path(a).cmp(&path(b))
+        })
+    });
+    Some(ShapeView {
+        targets,
+        properties,
+    })
+}
+
+/// Points links to terms documented elsewhere at their documentation. This
+/// runs after grouping, which relies on the links holding the term IRIs.
+/// Entries in other files of the site are reached through `root` from the
+/// pages of the file `page`.
+fn link_documentation(
+    groups: &mut [SubjectGroup],
+    trees: &mut [ConceptTree],
+    page: &str,
+    root: &str,
+    options: &ConvertOptions,
+) {
+    // terms described on this page keep their IRI
+    let site_link = |iri: &str| {
+        options
+            .site_links
+            .get(iri)
+            .filter(|link| link.split('#').next() != Some(page))
+            .map(|link| format!("{}{}", root, link))
+    };
+    let documentation = |iri: &str| {
+        site_link(iri)
+            .or_else(|| options.documentation_links.get(iri).cloned())
+            .or_else(|| {
+                options
+                    .specification_links
+                    .then(|| specification_url(iri))
+                    .flatten()
+            })
+    };
+    let relink = |link: &mut Option<String>| {
+        if let Some(url) = link.as_deref().and_then(documentation) {
+            *link = Some(url);
+        }
+    };
+
+    for triple in groups.iter_mut().flat_map(SubjectGroup::all_triples_mut) {
+        for link in triple.links_mut() {
+            relink(link);
+        }
+    }
+
+    let tree_links = trees.iter_mut().flat_map(ConceptTree::term_links_mut);
+    for term in groups
+        .iter_mut()
+        .flat_map(SubjectGroup::term_links_mut)
+        .chain(tree_links)
+    {
+        if term.anchor.is_none() {
+            if let Some(url) = documentation(&term.iri) {
+                term.iri = url;
+            }
+        }
+    }
+}
+
+/// Puts the untruncated values of long literals back into the statements,
+/// for pages meant to be read without their literals attachment.
+fn expand_literals(groups: &mut [SubjectGroup], full_literals: &[FullLiteral]) {
+    let expand = |triple: &mut Triple| {
+        let Some(position) = triple.full_value.take() else {
+            return;
+        };
+        // the truncated value ends with an ellipsis, followed by the
+        // language tag if there is one
+        let suffix = triple
+            .object
+            .rsplit_once('…')
+            .map_or("", |(_, suffix)| suffix);
+        triple.object = format!("{}{}", full_literals[position].value, suffix);
+    };
+    for triple in groups.iter_mut().flat_map(SubjectGroup::all_triples_mut) {
+        expand(triple);
+        triple.for_each_inner(&expand);
+    }
+}
+
+/// Takes the `owl:Axiom` blocks annotating statements on the page out of
+/// `triples`, keyed by the statement they annotate. Axioms about statements
+/// that are not on the page stay subjects of their own.
+fn extract_axioms(
+    triples: &mut Vec<Triple>,
+    types: &HashMap<String, Vec<String>>,
+) -> HashMap<StatementKey, Vec<Vec<Triple>>> {
+    let is_axiom = |subject: &str| {
+        types
+            .get(subject)
+            .is_some_and(|types| types.iter().any(|t| t == OWL_AXIOM))
+    };
+    let (axiom_triples, rest): (Vec<Triple>, Vec<Triple>) = std::mem::take(triples)
+        .into_iter()
+        .partition(|t| is_axiom(&t.subject));
+    *triples = rest;
+
+    // blocks in the order they appear in the file, so that footnotes on the
+    // same statement keep that order
+    let mut blocks: Vec<Vec<Triple>> = Vec::new();
+    let mut positions = HashMap::new();
+    for triple in axiom_triples {
+        let position = *positions.entry(triple.subject.clone()).or_insert_with(|| {
+            blocks.push(Vec::new());
+            blocks.len() - 1
+        });
+        blocks[position].push(triple);
+    }
+
+    let statements: HashSet<StatementKey> = triples.iter().map(Triple::statement_key).collect();
+    let mut axioms: HashMap<StatementKey, Vec<Vec<Triple>>> = HashMap::new();
+    for block in blocks {
+        let target = |predicate: &str| {
+            block
+                .iter()
+                .find(|t| t.predicate_iri() == predicate)
+                .map(|t| t.object_key().to_string())
+        };
+        let key = match (
+            target(OWL_ANNOTATED_SOURCE),
+            target(OWL_ANNOTATED_PROPERTY),
+            target(OWL_ANNOTATED_TARGET),
+        ) {
+            (Some(source), Some(property), Some(target)) => (source, property, target),
+            _ => {
+                triples.extend(block);
+                continue;
+            }
+        };
+        if !statements.contains(&key) {
+            triples.extend(block);
+            continue;
+        }
+
+        let annotations: Vec<Triple> = block
+            .into_iter()
+            .filter(|t| {
+                ![
+                    RDF_TYPE,
+                    OWL_ANNOTATED_SOURCE,
+                    OWL_ANNOTATED_PROPERTY,
+                    OWL_ANNOTATED_TARGET,
+                ]
+                .contains(&t.predicate_iri())
+            })
+            .collect();
+        if !annotations.is_empty() {
+            axioms.entry(key).or_default().push(annotations);
+        }
+    }
+
+    axioms
+}
+
+/// Numbers the annotations on each statement in page order and moves them
+/// into footnotes of the entry describing the statement.
+fn attach_footnotes(
+    groups: &mut [SubjectGroup],
+    mut axioms: HashMap<StatementKey, Vec<Vec<Triple>>>,
+) {
+    let mut number = 0;
+    for group in groups {
+        let sections = group.role_sections.iter_mut().map(|s| &mut s.triples);
+        for triple in std::iter::once(&mut group.triples)
+            .chain(sections)
+            .flatten()
+        {
+            for annotations in axioms.remove(&triple.statement_key()).unwrap_or_default() {
+                number += 1;
+                triple.footnotes.push(number);
+                group.footnotes.push(Footnote {
+                    number,
+                    annotations,
+                });
+            }
+        }
+    }
+}
+
+/// Gives every entry a unique id on the page: its name with whitespace
+/// replaced by dashes, numbered from the second entry using it on. Returns
+/// the id of each subject's first entry, by subject.
+fn assign_anchors(groups: &mut [SubjectGroup]) -> HashMap<String, String> {
+    let mut used = HashSet::new();
+    let mut anchors = HashMap::new();
+
+    for group in groups {
+        let base: String = group
+            .name
+            .chars()
+            .map(|c| if c.is_whitespace() { '-' } else { c })
+            .collect();
+        let base = if base.is_empty() {
+            "entry".to_string()
+        } else {
+            base
+        };
+        let mut anchor = base.clone();
+        let mut n = 1;
+        while !used.insert(anchor.clone()) {
+            n += 1;
+            anchor = format!("{}-{}", base, n);
+        }
+
+        anchors
+            .entry(group.subject.clone())
+            .or_insert_with(|| anchor.clone());
+        group.anchor = anchor;
+    }
+
+    anchors
+}
+
+/// Gives every entry its own page in a directory named after the page of
+/// its file, named after its anchor with characters unsafe in file names
+/// replaced by `_`, or slugified with `slugify`.
+fn assign_pages(groups: &mut [SubjectGroup], relative_path: &Path, slugify: bool) {
+    let directory = relative_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut used = HashSet::new();
+
+    for group in groups {
+        let base: String = if slugify {
+            slugify_name(&group.anchor)
+        } else {
+            group
+                .anchor
+                .trim_start_matches('.')
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect()
+        };
+        let mut name = base.clone();
+        let mut n = 1;
+        // file systems may ignore case
+        while !used.insert(name.to_lowercase()) {
+            n += 1;
+            name = format!("{}-{}", base, n);
+        }
+        group.page = Some(format!("{}/{}.html", directory, name));
+    }
+}
+
+fn is_equivalence(triple: &Triple) -> bool {
+    triple.object_link.is_some() && EQUIVALENCE_PREDICATES.contains(&triple.predicate_iri())
+}
+
+/// The label shown for `subject`, falling back to its `name`.
+fn display_label(
+    display_labels: &HashMap<String, (usize, String)>,
+    subject: &str,
+    name: &str,
+) -> String {
+    display_labels
+        .get(subject)
+        .map_or(name, |(_, label)| label)
+        .to_string()
+}
+
+/// Links for every IRI on a page, by IRI: subjects link to their entry,
+/// other IRIs to themselves under their displayed name.
+fn term_links<'a>(
+    triples: &'a [Triple],
+    display_labels: &HashMap<String, (usize, String)>,
+) -> HashMap<&'a str, TermLink> {
+    let mut links = HashMap::new();
+
+    for t in triples {
+        links.insert(
+            t.subject.as_str(),
+            TermLink {
+                label: display_label(display_labels, &t.subject, &t.subject_label),
+                iri: t.subject.clone(),
+                anchor: t.subject_link.as_ref().map(|_| t.subject_label.clone()),
+            },
+        );
+    }
+    for t in triples {
+        if let Some(iri) = &t.object_link {
+            links.entry(iri.as_str()).or_insert_with(|| TermLink {
+                label: t.object.clone(),
+                iri: iri.clone(),
+                anchor: None,
+            });
+        }
+    }
+
+    links
+}
+
+/// For each subject sharing one of `label_uses` with other subjects, those
+/// other subjects.
+fn homonyms(
+    label_uses: &[LabelUse],
+    links: &HashMap<&str, TermLink>,
+) -> HashMap<String, Vec<Homonym>> {
+    let mut homonyms: HashMap<String, Vec<Homonym>> = HashMap::new();
+
+    for collision in collisions(label_uses) {
+        for label_use in &collision.uses {
+            let terms = collision
+                .uses
+                .iter()
+                .filter(|other| other.iri != label_use.iri)
+                .map(|other| {
+                    links.get(other.iri.as_str()).cloned().unwrap_or(TermLink {
+                        label: other.iri.clone(),
+                        iri: other.iri.clone(),
+                        anchor: None,
+                    })
+                })
+                .collect();
+            homonyms
+                .entry(label_use.iri.clone())
+                .or_default()
+                .push(Homonym {
+                    label: label_use.display(),
+                    terms,
+                });
+        }
+    }
+
+    homonyms
+}
+
+fn push_unique(list: &mut Vec<TermLink>, link: &TermLink) {
+    if !list.contains(link) {
+        list.push(link.clone());
+    }
+}
+
+fn is_same_as(triple: &Triple) -> bool {
+    triple.predicate_iri() == OWL_SAME_AS
+        && !triple.subject.starts_with("_:")
+        && triple
+            .object_link
+            .as_ref()
+            .is_some_and(|object| !object.starts_with("_:"))
+}
+
+/// Merges every set of subjects connected by `owl:sameAs` into one of them:
+/// the first with a label, then with the most statements, then by IRI. The
+/// statements of the others become its own, and the `owl:sameAs` statements
+/// between them are left out. Returns the merged IRIs by the subject they
+/// were merged into.
+fn merge_same_as(
+    triples: &mut Vec<Triple>,
+    display_labels: &HashMap<String, (usize, String)>,
+) -> HashMap<String, Vec<TermLink>> {
+    let mut neighbours: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for triple in triples.iter().filter(|t| is_same_as(t)) {
+        let object = triple.object_link.as_deref().unwrap_or_default();
+        neighbours.entry(&triple.subject).or_default().push(object);
+        neighbours.entry(object).or_default().push(&triple.subject);
+    }
+    let mut statement_counts: HashMap<&str, usize> = HashMap::new();
+    for triple in triples.iter().filter(|t| !is_same_as(t)) {
+        *statement_counts.entry(&triple.subject).or_default() += 1;
+    }
+
+    let mut merged = BTreeMap::new();
+    let mut visited = HashSet::new();
+    for &start in neighbours.keys() {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut next = 0;
+        while let Some(&iri) = component.get(next) {
+            for &neighbour in &neighbours[iri] {
+                if visited.insert(neighbour) {
+                    component.push(neighbour);
+                }
+            }
+            next += 1;
+        }
+        let rank = |iri: &str| {
+            (
+                display_labels.contains_key(iri),
+                statement_counts.get(iri).copied().unwrap_or_default(),
+            )
+        };
+        let kept = component
+            .iter()
+            .copied()
+            .max_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| b.cmp(a)))
+            .unwrap_or(start);
+        for iri in component.into_iter().filter(|iri| *iri != kept) {
+            merged.insert(iri.to_string(), kept.to_string());
+        }
+    }
+    if merged.is_empty() {
+        return HashMap::new();
+    }
+
+    // subjects are named as in their own statements, other IRIs as objects
+    let mut subject_fields: HashMap<String, (Option<String>, String)> = HashMap::new();
+    for triple in triples.iter() {
+        subject_fields
+            .entry(triple.subject.clone())
+            .or_insert_with(|| (triple.subject_link.clone(), triple.subject_label.clone()));
+    }
+    let object_names: HashMap<&str, &str> = triples
+        .iter()
+        .filter_map(|t| Some((t.object_link.as_deref()?, t.object.as_str())))
+        .collect();
+    let mut aliases: HashMap<String, Vec<TermLink>> = HashMap::new();
+    for (iri, kept) in &merged {
+        let label = match subject_fields.get(iri) {
+            Some((_, label)) => label.clone(),
+            None => object_names
+                .get(iri.as_str())
+                .unwrap_or(&iri.as_str())
+                .to_string(),
+        };
+        aliases.entry(kept.clone()).or_default().push(TermLink {
+            label,
+            iri: iri.clone(),
+            anchor: None,
+        });
+    }
+
+    // a subject described by nothing else keeps its `owl:sameAs` statements,
+    // so that it still has an entry
+    let kept_subject = |iri: &str| merged.get(iri).map_or(iri, String::as_str).to_string();
+    let described: HashSet<String> = triples
+        .iter()
+        .filter(|t| !is_same_as(t))
+        .map(|t| kept_subject(&t.subject))
+        .collect();
+    triples.retain(|t| !is_same_as(t) || !described.contains(&kept_subject(&t.subject)));
+    for triple in triples.iter_mut() {
+        let Some(kept) = merged.get(&triple.subject) else {
+            continue;
+        };
+        let (link, label) = subject_fields
+            .get(kept)
+            .cloned()
+            .unwrap_or_else(|| (Some(kept.clone()), kept.clone()));
+        triple.subject = kept.clone();
+        triple.subject_link = link;
+        triple.subject_label = label;
+    }
+    aliases
+}
+
+/// Moves the values of the IRIs in `aliases` to the subjects they were
+/// merged into.
+fn merge_values<T: PartialEq>(
+    values: &mut HashMap<String, Vec<T>>,
+    aliases: &HashMap<String, Vec<TermLink>>,
+) {
+    for (kept, merged) in aliases {
+        for alias in merged {
+            let Some(moved) = values.remove(&alias.iri) else {
+                continue;
+            };
+            let kept_values = values.entry(kept.clone()).or_default();
+            for value in moved {
+                if !kept_values.contains(&value) {
+                    kept_values.push(value);
+                }
+            }
+        }
+    }
+}
+
+/// Equivalent terms by subject, with every equivalence also recorded on the
+/// object when the object is described on the same page.
+fn collect_equivalents(
+    triples: &[Triple],
+    links: &HashMap<&str, TermLink>,
+) -> HashMap<String, Vec<TermLink>> {
+    let mut equivalents: HashMap<String, Vec<TermLink>> = HashMap::new();
+
+    for triple in triples.iter().filter(|t| is_equivalence(t)) {
+        let object_iri = triple.object_link.as_deref().unwrap();
+        let subject_list = equivalents.entry(triple.subject.clone()).or_default();
+        push_unique(subject_list, &links[object_iri]);
+
+        if links[object_iri].anchor.is_some() {
+            let object_list = equivalents.entry(object_iri.to_string()).or_default();
+            push_unique(object_list, &links[triple.subject.as_str()]);
+        }
+    }
+
+    equivalents
+}
+
+/// Relations shown in the summary of a term in ontology mode: the
+/// predicate, the title on the subject's entry and the title on the
+/// object's entry, when the object is described on the same page.
+const SUMMARY_RELATIONS: &[(&str, &str, &str)] = &[
+    (RDF_TYPE, "Type", "Instances"),
+    (RDFS_SUB_CLASS_OF, "Subclass of", "Superclass of"),
+    (RDFS_SUB_PROPERTY_OF, "Subproperty of", "Superproperty of"),
+    (RDFS_DOMAIN, "Domain", "In domain of"),
+    (RDFS_RANGE, "Range", "In range of"),
+    (OWL_INVERSE_OF, "Inverse of", "Inverse of"),
+];
+
+/// Definitions and related terms of every subject on a page, for ontology
+/// mode. Types that only say what kind of term a subject is are left out,
+/// as the section of the entry already tells.
+fn term_summaries(
+    triples: &[Triple],
+    links: &HashMap<&str, TermLink>,
+) -> HashMap<String, TermSummary> {
+    let mut definitions: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+    let mut fields: HashMap<&str, BTreeMap<usize, Vec<TermLink>>> = HashMap::new();
+
+    for t in triples {
+        let predicate = t.predicate_iri();
+        if let Some(priority) = DEFINITION_PREDICATES.iter().position(|p| *p == predicate) {
+            if let Some(value) = &t.lexical_form {
+                definitions
+                    .entry(&t.subject)
+                    .or_default()
+                    .push((priority, value));
+            }
+            continue;
+        }
+        let Some(object) = t.object_link.as_deref() else {
+            continue;
+        };
+        let Some(relation) = SUMMARY_RELATIONS.iter().position(|(p, ..)| *p == predicate) else {
+            continue;
+        };
+        if predicate == RDF_TYPE
+            && (CLASS_TYPES.contains(&object)
+                || PROPERTY_TYPES.contains(&object)
+                || [OWL_ONTOLOGY, OWL_NAMED_INDIVIDUAL].contains(&object))
+        {
+            continue;
+        }
+        // forward relations come first, in the order of the table
+        let field = fields.entry(&t.subject).or_default();
+        push_unique(field.entry(relation).or_default(), &links[object]);
+        if links[object].anchor.is_some() {
+            let field = fields.entry(object).or_default();
+            let inverse = SUMMARY_RELATIONS.len() + relation;
+            push_unique(
+                field.entry(inverse).or_default(),
+                &links[t.subject.as_str()],
+            );
+        }
+    }
+
+    let mut summaries: HashMap<String, TermSummary> = HashMap::new();
+    for (subject, mut values) in definitions {
+        values.sort_by_key(|(priority, _)| *priority);
+        let summary = summaries.entry(subject.to_string()).or_default();
+        for (_, value) in values {
+            if !summary.definitions.iter().any(|d| d == value) {
+                summary.definitions.push(value.to_string());
+            }
+        }
+    }
+    for (subject, relations) in fields {
+        let summary = summaries.entry(subject.to_string()).or_default();
+        for (relation, terms) in relations {
+            let (_, title, inverse_title) = SUMMARY_RELATIONS[relation % SUMMARY_RELATIONS.len()];
+            let title = if relation < SUMMARY_RELATIONS.len() {
+                title
+            } else {
+                inverse_title
+            };
+            // an inverse relation titled as the forward one, as
+            // `owl:inverseOf` is, shares its field
+            match summary.fields.iter_mut().find(|f| f.title == title) {
+                Some(field) => terms.iter().for_each(|t| push_unique(&mut field.terms, t)),
+                None => summary.fields.push(SummaryField { title, terms }),
+            }
+        }
+        for field in &mut summary.fields {
+            field
+                .terms
+                .sort_by(|a, b| a.label.cmp(&b.label).then_with(|| a.iri.cmp(&b.iri)));
+        }
+    }
+
+    summaries
+}
+
+/// Broader, narrower and related concepts on a page, from `skos:broader`,
+/// `skos:narrower` and `skos:related` in either direction.
+#[derive(Default)]
+struct ConceptHierarchy<'a> {
+    broader: HashMap<&'a str, BTreeSet<&'a str>>,
+    narrower: HashMap<&'a str, BTreeSet<&'a str>>,
+    related: HashMap<&'a str, BTreeSet<&'a str>>,
+}
+
+impl<'a> ConceptHierarchy<'a> {
+    fn new(triples: &'a [Triple]) -> Self {
+        let mut hierarchy = ConceptHierarchy::default();
+        for t in triples {
+            let Some(object) = t.object_link.as_deref() else {
+                continue;
+            };
+            let subject = t.subject.as_str();
+            let (child, parent) = match t.predicate_iri() {
+                SKOS_BROADER => (subject, object),
+                SKOS_NARROWER => (object, subject),
+                SKOS_RELATED => {
+                    hierarchy.related.entry(subject).or_default().insert(object);
+                    hierarchy.related.entry(object).or_default().insert(subject);
+                    continue;
+                }
+                _ => continue,
+            };
+            hierarchy.broader.entry(child).or_default().insert(parent);
+            hierarchy.narrower.entry(parent).or_default().insert(child);
+        }
+        hierarchy
+    }
+
+    /// The hierarchy with the edges between concepts given in every file of
+    /// `corpus` added.
+    fn with_corpus(&self, corpus: &'a Corpus) -> ConceptHierarchy<'a> {
+        let mut hierarchy = ConceptHierarchy {
+            broader: self.broader.clone(),
+            narrower: self.narrower.clone(),
+            related: self.related.clone(),
+        };
+        for (child, parent) in corpus.broader() {
+            hierarchy.broader.entry(child).or_default().insert(parent);
+            hierarchy.narrower.entry(parent).or_default().insert(child);
+        }
+        for (concept, other) in corpus.related() {
+            hierarchy.related.entry(concept).or_default().insert(other);
+        }
+        hierarchy
+    }
+}
+
+/// The pairs of named concepts in one of the maps of a [`ConceptHierarchy`],
+/// sorted.
+fn named_pairs(map: &HashMap<&str, BTreeSet<&str>>) -> Vec<(String, String)> {
+    let named = |iri: &str| !iri.starts_with("_:");
+    let mut pairs: Vec<(String, String)> = map
+        .iter()
+        .filter(|(concept, _)| named(concept))
+        .flat_map(|(concept, others)| {
+            others
+                .iter()
+                .filter(|other| named(other))
+                .map(|other| (concept.to_string(), other.to_string()))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+/// Link to `iri`: the one on the page, or else to the IRI under the label
+/// it has in the corpus.
+fn concept_link(iri: &str, links: &HashMap<&str, TermLink>, corpus: &Corpus) -> TermLink {
+    links.get(iri).cloned().unwrap_or_else(|| TermLink {
+        label: corpus.label(iri).unwrap_or(iri).to_string(),
+        iri: iri.to_string(),
+        anchor: None,
+    })
+}
+
+/// Links to `iris`, sorted by label.
+fn links_by_label(
+    iris: Option<&BTreeSet<&str>>,
+    links: &HashMap<&str, TermLink>,
+    corpus: &Corpus,
+) -> Vec<TermLink> {
+    let mut terms: Vec<TermLink> = iris
+        .into_iter()
+        .flatten()
+        .map(|iri| concept_link(iri, links, corpus))
+        .collect();
+    terms.sort_by(|a, b| a.label.cmp(&b.label));
+    terms
+}
+
+fn has_type(types: &HashMap<String, Vec<String>>, subject: &str, class: &str) -> bool {
+    types
+        .get(subject)
+        .is_some_and(|types| types.iter().any(|t| t == class))
+}
+
+/// Hierarchy navigation for every `skos:Concept` on a page, through
+/// concepts on other pages too when `hierarchy` includes the corpus.
+fn concept_navigation(
+    hierarchy: &ConceptHierarchy,
+    types: &HashMap<String, Vec<String>>,
+    links: &HashMap<&str, TermLink>,
+    corpus: &Corpus,
+) -> HashMap<String, ConceptNavigation> {
+    let mut navigation = HashMap::new();
+    for subject in types.keys() {
+        if !has_type(types, subject, SKOS_CONCEPT) {
+            continue;
+        }
+
+        // with several broader concepts, the breadcrumb follows the first
+        let mut ancestors = Vec::new();
+        let mut current = subject.as_str();
+        let mut seen = HashSet::from([current]);
+        while let Some(parent) = hierarchy.broader.get(current).and_then(|p| p.first()) {
+            if !seen.insert(parent) {
+                break;
+            }
+            ancestors.push(concept_link(parent, links, corpus));
+            current = parent;
+        }
+        ancestors.reverse();
+
+        let concept = ConceptNavigation {
+            ancestors,
+            narrower: links_by_label(hierarchy.narrower.get(subject.as_str()), links, corpus),
+            related: links_by_label(hierarchy.related.get(subject.as_str()), links, corpus),
+        };
+        if !concept.ancestors.is_empty()
+            || !concept.narrower.is_empty()
+            || !concept.related.is_empty()
+        {
+            navigation.insert(subject.clone(), concept);
+        }
+    }
+
+    navigation
+}
+
+/// Labels, definitions and notations of every `skos:Concept` on a page
+/// that has any. Definitions are left out when the entries already show
+/// them, as in ontology mode.
+fn concept_details(
+    triples: &[Triple],
+    types: &HashMap<String, Vec<String>>,
+    with_definitions: bool,
+) -> HashMap<String, ConceptDetails> {
+    let mut details: HashMap<String, ConceptDetails> = HashMap::new();
+    for t in triples {
+        if t.object_link.is_some() || !has_type(types, &t.subject, SKOS_CONCEPT) {
+            continue;
+        }
+        let values: fn(&mut ConceptDetails) -> &mut Vec<String> = match t.predicate_iri() {
+            SKOS_PREF_LABEL => |d| &mut d.pref_labels,
+            SKOS_ALT_LABEL => |d| &mut d.alt_labels,
+            SKOS_DEFINITION if with_definitions => |d| &mut d.definitions,
+            SKOS_NOTATION => |d| &mut d.notations,
+            _ => continue,
+        };
+        values(details.entry(t.subject.clone()).or_default()).push(t.object.clone());
+    }
+    details
+}
+
+/// The concept trees of a page: one per concept scheme, starting from its
+/// top concepts and the concepts of the scheme without a broader concept in
+/// it, then one for the concepts outside any scheme. A concept with several
+/// broader concepts appears under each of them. Pages without a concept
+/// scheme or a concept hierarchy get no trees.
+fn concept_trees(
+    triples: &[Triple],
+    hierarchy: &ConceptHierarchy,
+    types: &HashMap<String, Vec<String>>,
+    links: &HashMap<&str, TermLink>,
+) -> Vec<ConceptTree> {
+    let mut schemes: BTreeSet<&str> = types
+        .keys()
+        .filter(|subject| has_type(types, subject, SKOS_CONCEPT_SCHEME))
+        .map(String::as_str)
+        .collect();
+    if schemes.is_empty() && hierarchy.broader.is_empty() {
+        return Vec::new();
+    }
+
+    let mut concepts: BTreeSet<&str> = types
+        .keys()
+        .filter(|subject| has_type(types, subject, SKOS_CONCEPT))
+        .map(String::as_str)
+        .collect();
+    concepts.extend(hierarchy.broader.keys());
+    concepts.extend(hierarchy.narrower.keys());
+    let mut in_scheme: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    let mut top_concepts: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for t in triples {
+        let Some(object) = t.object_link.as_deref() else {
+            continue;
+        };
+        let (concept, scheme, top) = match t.predicate_iri() {
+            SKOS_IN_SCHEME => (t.subject.as_str(), object, false),
+            SKOS_TOP_CONCEPT_OF => (t.subject.as_str(), object, true),
+            SKOS_HAS_TOP_CONCEPT => (object, t.subject.as_str(), true),
+            _ => continue,
+        };
+        schemes.insert(scheme);
+        concepts.insert(concept);
+        in_scheme.entry(concept).or_default().insert(scheme);
+        if top {
+            top_concepts.entry(scheme).or_default().insert(concept);
+        }
+    }
+
+    let in_scheme_of = |concept: &str, scheme: &str| {
+        in_scheme
+            .get(concept)
+            .is_some_and(|schemes| schemes.contains(scheme))
+    };
+    let mut trees = Vec::new();
+    for scheme in schemes {
+        let mut roots = top_concepts.remove(scheme).unwrap_or_default();
+        roots.extend(concepts.iter().filter(|concept| {
+            in_scheme_of(concept, scheme)
+                && !hierarchy
+                    .broader
+                    .get(*concept)
+                    .is_some_and(|broader| broader.iter().any(|b| in_scheme_of(b, scheme)))
+        }));
+        trees.push(ConceptTree {
+            scheme: links.get(scheme).cloned(),
+            concepts: tree_nodes(&roots, hierarchy, links, &mut Vec::new()),
+        });
+    }
+    let roots: BTreeSet<&str> = concepts
+        .iter()
+        .copied()
+        .filter(|concept| {
+            !in_scheme.contains_key(concept) && !hierarchy.broader.contains_key(concept)
+        })
+        .collect();
+    if !roots.is_empty() {
+        trees.push(ConceptTree {
+            scheme: None,
+            concepts: tree_nodes(&roots, hierarchy, links, &mut Vec::new()),
+        });
+    }
+
+    trees
+}
+
+/// Tree nodes for `concepts` and their narrower concepts, sorted by label.
+/// `path` holds the concepts above them, whose appearance again would be a
+/// cycle.
+fn tree_nodes<'a>(
+    concepts: &BTreeSet<&'a str>,
+    hierarchy: &ConceptHierarchy<'a>,
+    links: &HashMap<&str, TermLink>,
+    path: &mut Vec<&'a str>,
+) -> Vec<ConceptTreeNode> {
+    let mut nodes = Vec::new();
+    for &concept in concepts {
+        let Some(term) = links.get(concept).filter(|_| !path.contains(&concept)) else {
+            continue;
+        };
+        path.push(concept);
+        let narrower = match hierarchy.narrower.get(concept) {
+            Some(narrower) => tree_nodes(narrower, hierarchy, links, path),
+            None => Vec::new(),
+        };
+        path.pop();
+        nodes.push(ConceptTreeNode {
+            term: term.clone(),
+            narrower,
+        });
+    }
+    nodes.sort_by(|a, b| a.term.label.cmp(&b.term.label));
+    nodes
+}
+
+/// Renders an RDF 1.2 triple term as `<< s p o >>`.
+fn triple_term_to_string(triple: &oxrdf::Triple) -> String {
+    let subject = match &triple.subject {
+        NamedOrBlankNode::NamedNode(iri) => iri.as_str().to_string(),
+        NamedOrBlankNode::BlankNode(blank) => blank.to_string(),
+    };
+    let object = match &triple.object {
+        Term::NamedNode(iri) => iri.as_str().to_string(),
+        Term::Triple(nested) => triple_term_to_string(nested),
+        term => term.to_string(),
+    };
+
+    format!("<< {} {} {} >>", subject, triple.predicate.as_str(), object)
+}
+
+/// Characters of file paths that cannot appear literally in an IRI.
+const IRI_PATH_ESCAPES: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// `path` percent-encoded for use in an IRI.
+fn iri_path(path: &str) -> String {
+    utf8_percent_encode(path, IRI_PATH_ESCAPES).to_string()
+}
+
+/// Relative prefix leading from a generated page back to the site root,
+/// e.g. `../../` for `a/b/page.html`.
+fn root_prefix(relative_path: &Path) -> String {
+    let depth = relative_path.components().count().saturating_sub(1);
+    "../".repeat(depth)
 }
 
 fn is_valid_url(s: &str) -> bool {
@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Condvar, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use log::debug;
+use tera::{Context, Tera};
+
+use crate::cache::BuildCache;
+use crate::corpus::Outline;
+use crate::error_report::FileError;
+use crate::output::OutputBackend;
+use crate::parser::{group_file, parse_file, render_file, ConvertOptions, Converted, ErrorPolicy};
+use crate::progress;
+
+/// Number of threads for each stage of the conversion. Parsing and rendering
+/// are CPU-bound, writing depends on the output backend.
+pub struct Jobs {
+    pub parse: usize,
+    pub render: usize,
+    pub write: usize,
+    /// Most files between starting to be parsed and having their page
+    /// written, across all stages; unlimited when `None`.
+    pub files: Option<usize>,
+}
+
+impl Jobs {
+    /// The same number of threads for every stage, and no more than `n`
+    /// files in flight.
+    pub fn uniform(n: usize) -> Self {
+        Jobs {
+            parse: n,
+            render: n,
+            write: n,
+            files: Some(n),
+        }
+    }
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        let n = thread::available_parallelism().map_or(1, |n| n.get());
+        Jobs {
+            files: None,
+            ..Jobs::uniform(n)
+        }
+    }
+}
+
+/// Counts the files in flight, to keep them within [`Jobs::files`].
+struct Slots {
+    limit: usize,
+    taken: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Slots {
+    fn new(limit: Option<usize>) -> Self {
+        Slots {
+            limit: limit.unwrap_or(usize::MAX),
+            taken: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Waits until fewer than `limit` files are in flight and counts one
+    /// more.
+    fn take(&self) {
+        let mut taken = self.taken.lock().unwrap();
+        while *taken >= self.limit {
+            taken = self.released.wait(taken).unwrap();
+        }
+        *taken += 1;
+    }
+
+    fn release(&self) {
+        *self.taken.lock().unwrap() -= 1;
+        self.released.notify_one();
+    }
+}
+
+/// An input file and the result of converting it.
+pub type FileResult = (PathBuf, Result<Converted, FileError>);
+
+/// Parses and renders `inputs` on separate thread pools, connected by a
+/// bounded queue so parsed files do not pile up when rendering is slower.
+/// Results are returned in the order of `inputs`; with
+/// [`ErrorPolicy::FailFast`] no new files are started after the first error,
+/// so files after it may be missing from the results.
+pub fn convert_files(
+    inputs: &[PathBuf],
+    input_dir: &str,
+    output: &dyn OutputBackend,
+    tera: &Tera,
+    base_context: &Context,
+    options: &ConvertOptions,
+    jobs: &Jobs,
+) -> Vec<FileResult> {
+    let next_input = Mutex::new(inputs.iter().enumerate());
+    let slots = Slots::new(jobs.files);
+    let failed = AtomicBool::new(false);
+    let stop = || options.on_error == ErrorPolicy::FailFast && failed.load(Ordering::Relaxed);
+
+    let (parsed_tx, parsed_rx) = mpsc::sync_channel(jobs.render);
+    let parsed_rx = Mutex::new(parsed_rx);
+    let (result_tx, result_rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.parse {
+            let parsed_tx = parsed_tx.clone();
+            let result_tx = result_tx.clone();
+            let (next_input, slots, failed, stop) = (&next_input, &slots, &failed, &stop);
+            scope.spawn(move || loop {
+                slots.take();
+                let next = next_input.lock().unwrap().next();
+                let Some((i, path)) = next.filter(|_| !stop()) else {
+                    slots.release();
+                    break;
+                };
+
+                progress::file_started(path);
+                let start = Instant::now();
+                match parse_file(path, input_dir, options) {
+                    Ok(parsed) => {
+                        debug!("Parsed {:?} in {:.1?}", path, start.elapsed());
+                        if parsed_tx.send((i, path, parsed)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        failed.store(true, Ordering::Relaxed);
+                        progress::file_done();
+                        slots.release();
+                        let _ = result_tx.send((i, path, Err(FileError::new(&*e))));
+                    }
+                }
+            });
+        }
+        drop(parsed_tx);
+
+        for _ in 0..jobs.render {
+            let result_tx = result_tx.clone();
+            let (parsed_rx, slots, failed, stop) = (&parsed_rx, &slots, &failed, &stop);
+            scope.spawn(move || loop {
+                let next = parsed_rx.lock().unwrap().recv();
+                let Ok((i, path, parsed)) = next else { break };
+                if stop() {
+                    slots.release();
+                    continue;
+                }
+
+                let start = Instant::now();
+                let result =
+                    render_file(parsed, path, input_dir, output, tera, base_context, options)
+                        .map_err(|e| FileError::new(&*e));
+                match &result {
+                    Ok(converted) => debug!(
+                        "Rendered {:?}: {} triple(s), {} file(s) written in {:.1?}",
+                        path,
+                        converted.triples,
+                        converted.outputs.len(),
+                        start.elapsed()
+                    ),
+                    Err(_) => failed.store(true, Ordering::Relaxed),
+                }
+                progress::file_done();
+                slots.release();
+                let _ = result_tx.send((i, path, result));
+            });
+        }
+        drop(result_tx);
+    });
+
+    let mut results: Vec<_> = result_rx.into_iter().collect();
+    results.sort_by_key(|(i, _, _)| *i);
+    results
+        .into_iter()
+        .map(|(_, path, result)| (path.clone(), result))
+        .collect()
+}
+
+/// Outlines of `inputs`, in their order, for the pages to link across
+/// files. Outlines of unchanged inputs are taken from `cache`; the other
+/// files are parsed ahead of the conversion on `jobs` threads, and those that
+/// fail to parse are left out.
+pub fn outline_files(
+    inputs: &[PathBuf],
+    input_dir: &str,
+    options: &ConvertOptions,
+    jobs: usize,
+    mut cache: Option<&mut BuildCache>,
+) -> Vec<Outline> {
+    let mut outlines: Vec<Option<Outline>> = inputs
+        .iter()
+        .map(|path| {
+            let source_path = path.strip_prefix(input_dir).ok()?;
+            cache.as_deref_mut()?.outline(path, source_path)
+        })
+        .collect();
+    let missing: Vec<usize> = (0..inputs.len())
+        .filter(|&i| outlines[i].is_none())
+        .collect();
+    debug!(
+        "Outlining {} file(s), {} from the cache",
+        missing.len(),
+        inputs.len() - missing.len()
+    );
+    let next_input = Mutex::new(missing.into_iter());
+    let found = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let next = next_input.lock().unwrap().next();
+                let Some(i) = next else { break };
+                let path = &inputs[i];
+                let (Ok(source_path), Ok(parsed)) = (
+                    path.strip_prefix(input_dir),
+                    parse_file(path, input_dir, options),
+                ) else {
+                    continue;
+                };
+                let Ok(grouped) = group_file(parsed, source_path, options) else {
+                    continue;
+                };
+
+                let outline = grouped.into_outline();
+                found.lock().unwrap().push((i, outline));
+            });
+        }
+    });
+
+    for (i, outline) in found.into_inner().unwrap() {
+        if let (Some(cache), Ok(source_path)) =
+            (cache.as_deref_mut(), inputs[i].strip_prefix(input_dir))
+        {
+            cache.record_outline(source_path, &outline);
+        }
+        outlines[i] = Some(outline);
+    }
+    outlines.into_iter().flatten().collect()
+}
@@ -0,0 +1,80 @@
+//! Prefix maps that shorten IRIs for which the input files declare no
+//! prefix of their own.
+
+use std::fs;
+use std::path::Path;
+
+use oxttl::TriGParser;
+use serde_json::Value;
+
+/// Prefixes of widely used vocabularies, for files that use them without
+/// declaring them.
+pub const BUILT_IN: &[(&str, &str)] = &[
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("skosxl", "http://www.w3.org/2008/05/skos-xl#"),
+    ("dct", "http://purl.org/dc/terms/"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("dcat", "http://www.w3.org/ns/dcat#"),
+    ("foaf", "http://xmlns.com/foaf/0.1/"),
+    ("schema", "https://schema.org/"),
+    ("sh", "http://www.w3.org/ns/shacl#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+    ("void", "http://rdfs.org/ns/void#"),
+    ("vann", "http://purl.org/vocab/vann/"),
+    ("org", "http://www.w3.org/ns/org#"),
+    ("vcard", "http://www.w3.org/2006/vcard/ns#"),
+    ("geo", "http://www.opengis.net/ont/geosparql#"),
+    ("time", "http://www.w3.org/2006/time#"),
+    ("sosa", "http://www.w3.org/ns/sosa/"),
+    ("ssn", "http://www.w3.org/ns/ssn/"),
+    ("qb", "http://purl.org/linked-data/cube#"),
+    ("odrl", "http://www.w3.org/ns/odrl/2/"),
+];
+
+/// The built-in prefixes, as owned pairs.
+pub fn built_in() -> Vec<(String, String)> {
+    BUILT_IN
+        .iter()
+        .map(|(prefix, iri)| (prefix.to_string(), iri.to_string()))
+        .collect()
+}
+
+/// Reads the prefix map in `path`: a JSON object of namespace IRIs by
+/// prefix, optionally wrapped in a JSON-LD `@context`, when the file ends in
+/// `.json` or `.jsonld`, and Turtle `@prefix` declarations otherwise.
+pub fn load(path: &Path) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let input = fs::read(path)?;
+    let invalid = |e: &dyn std::fmt::Display| format!("Invalid prefix file {:?}: {}", path, e);
+
+    match path.extension().and_then(|s| s.to_str()) {
+        Some("json" | "jsonld") => {
+            let mut map: Value = serde_json::from_slice(&input).map_err(|e| invalid(&e))?;
+            if let Some(context) = map.get_mut("@context") {
+                map = context.take();
+            }
+            let Value::Object(map) = map else {
+                return Err(invalid(&"expected an object of namespace IRIs by prefix").into());
+            };
+            // JSON-LD keywords and term definitions are not prefixes
+            Ok(map
+                .into_iter()
+                .filter(|(prefix, _)| !prefix.starts_with('@'))
+                .filter_map(|(prefix, iri)| Some((prefix, iri.as_str()?.to_string())))
+                .collect())
+        }
+        _ => {
+            let mut parser = TriGParser::new().for_slice(&input);
+            for quad in parser.by_ref() {
+                quad.map_err(|e| invalid(&e))?;
+            }
+            Ok(parser
+                .prefixes()
+                .map(|(prefix, iri)| (prefix.to_string(), iri.to_string()))
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! A progress bar for converting many files, drawn on standard error when
+//! it is a terminal. Elsewhere every file is logged as it is converted.
+
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{info, LevelFilter};
+
+/// Width of the bar itself, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// Shortest time between two redraws, so that fast conversions are not
+/// slowed down by the terminal.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+struct Bar {
+    total: usize,
+    done: usize,
+    current: String,
+    start: Instant,
+    drawn: Option<Instant>,
+}
+
+impl Bar {
+    /// The line of the bar: the files done out of `total`, the time left
+    /// and the file being converted.
+    fn line(&self) -> String {
+        let filled = (self.done * BAR_WIDTH)
+            .checked_div(self.total)
+            .unwrap_or(BAR_WIDTH);
+        let mut line = format!(
+            "[{}{}] {}/{} files",
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.done,
+            self.total
+        );
+        if self.done > 0 && self.done < self.total {
+            let left = self.start.elapsed() / self.done as u32 * (self.total - self.done) as u32;
+            line.push_str(&format!(", ETA {}", duration(left)));
+        }
+        if !self.current.is_empty() {
+            line.push_str(&format!(": {}", self.current));
+        }
+        line.chars()
+            .take(terminal_width().saturating_sub(1))
+            .collect()
+    }
+
+    fn draw(&mut self, force: bool) {
+        if !force
+            && self
+                .drawn
+                .is_some_and(|drawn| drawn.elapsed() < REDRAW_INTERVAL)
+        {
+            return;
+        }
+        self.drawn = Some(Instant::now());
+        let _ = write!(std::io::stderr(), "\r\x1b[2K{}", self.line());
+    }
+}
+
+static BAR: Mutex<Option<Bar>> = Mutex::new(None);
+
+/// Shows a progress bar for the conversion of `total` files, if standard
+/// error is a terminal and only the usual messages are logged: `--verbose`
+/// logs every file and `--quiet` nothing but warnings. Returns whether the
+/// bar is shown.
+pub fn start(total: usize) -> bool {
+    if !std::io::stderr().is_terminal() || log::max_level() != LevelFilter::Info {
+        return false;
+    }
+    let mut bar = Bar {
+        total,
+        done: 0,
+        current: String::new(),
+        start: Instant::now(),
+        drawn: None,
+    };
+    bar.draw(true);
+    *BAR.lock().unwrap() = Some(bar);
+    true
+}
+
+/// Records that converting `path` began, on the bar or in the log.
+pub fn file_started(path: &Path) {
+    let mut bar = BAR.lock().unwrap();
+    match bar.as_mut() {
+        Some(bar) => {
+            bar.current = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            bar.draw(false);
+        }
+        None => {
+            drop(bar);
+            info!("Converting file: {:?}", path);
+        }
+    }
+}
+
+/// Records that a file was converted, or failed to.
+pub fn file_done() {
+    if let Some(bar) = BAR.lock().unwrap().as_mut() {
+        bar.done += 1;
+        if bar.done == bar.total {
+            bar.current.clear();
+        }
+        bar.draw(bar.done == bar.total);
+    }
+}
+
+/// Removes the bar; returns whether it was shown.
+pub fn finish() -> bool {
+    let shown = BAR.lock().unwrap().take().is_some();
+    if shown {
+        let _ = write!(std::io::stderr(), "\r\x1b[2K");
+    }
+    shown
+}
+
+/// Runs `print` with the bar cleared from its line, and draws it again
+/// below whatever `print` wrote.
+pub(crate) fn suspend(print: impl FnOnce()) {
+    let mut bar = BAR.lock().unwrap();
+    if bar.is_some() {
+        let _ = write!(std::io::stderr(), "\r\x1b[2K");
+    }
+    print();
+    if let Some(bar) = bar.as_mut() {
+        bar.draw(true);
+    }
+}
+
+/// `duration` in hours, minutes and seconds, leaving out leading zeros.
+fn duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Width of the terminal as the shell reports it in `COLUMNS`, or the
+/// usual 80 characters.
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use oxrdf::{GraphName, NamedOrBlankNode, Quad};
+use oxttl::{TextPosition, TriGParser, TurtleSyntaxError};
+
+use crate::diagnostic::Diagnostic;
+
+/// Prefix names and namespace IRIs, in declaration order.
+pub type Prefixes = Vec<(String, String)>;
+
+/// Statements read from a file in recovery mode, with the statements that
+/// could not be parsed left out.
+pub struct Recovered {
+    pub quads: Vec<Quad>,
+    /// Prefixes declared by the parsed statements.
+    pub prefixes: Prefixes,
+    /// The first error of each skipped statement, positioned in the file.
+    pub errors: Vec<Diagnostic>,
+    pub skipped: usize,
+    /// Terms in positions RDF does not allow them in, read in lenient mode,
+    /// as they were written by the IRIs standing in for them in `quads`.
+    pub generalized: BTreeMap<String, String>,
+}
+
+/// Stands in for the terms of generalized RDF statements, numbered in order
+/// of appearance. No input is expected to use IRIs of this scheme.
+const GENERALIZED_TERM: &str = "urn:x-ttl2html:generalized-term:";
+
+/// Parses Turtle or TriG one statement at a time, so that a syntax error
+/// only costs the statement it is in. Prefixes and the base IRI declared by
+/// earlier statements carry over to later ones, starting from `base_iri`.
+/// When `lenient`, statements of generalized RDF, such as those with a
+/// literal subject or a blank node predicate, are read too.
+pub fn parse(
+    input: &str,
+    lenient: bool,
+    base_iri: Option<&str>,
+) -> Result<Recovered, Box<dyn std::error::Error>> {
+    let mut recovered = Recovered {
+        quads: Vec::new(),
+        prefixes: Vec::new(),
+        errors: Vec::new(),
+        skipped: 0,
+        generalized: BTreeMap::new(),
+    };
+    let mut base_iri: Option<String> = base_iri.map(str::to_string);
+    let mut position = Position::default();
+    let mut previous_end = 0;
+
+    for range in statements(input) {
+        position.advance(&input[previous_end..range.start]);
+        previous_end = range.start;
+        let mut statement = input[range].to_string();
+
+        let mut first_error = None;
+        let parsed = loop {
+            let error = match parse_statement(
+                &statement,
+                lenient,
+                base_iri.as_deref(),
+                &recovered.prefixes,
+            )? {
+                Ok(parsed) => break Some(parsed),
+                Err(e) => e,
+            };
+            let generalized = lenient
+                .then(|| generalize(&statement, &error, &mut recovered.generalized))
+                .flatten();
+            // errors are located in the statement as written
+            first_error.get_or_insert(error);
+            match generalized {
+                Some(generalized) => statement = generalized,
+                None => break None,
+            }
+        };
+
+        match parsed {
+            Some(parsed) => {
+                recovered.quads.extend(parsed.quads);
+                recovered.prefixes = parsed.prefixes;
+                base_iri = parsed.base_iri.or(base_iri);
+            }
+            None => {
+                if let Some(e) = first_error {
+                    recovered.errors.push(position.locate(&e, input));
+                }
+                recovered.skipped += 1;
+            }
+        }
+    }
+
+    Ok(recovered)
+}
+
+/// Whether `quad` is a generalized RDF statement read by [`parse`].
+pub fn is_generalized(quad: &Quad) -> bool {
+    let generalized = |iri: &str| iri.starts_with(GENERALIZED_TERM);
+    matches!(&quad.subject, NamedOrBlankNode::NamedNode(iri) if generalized(iri.as_str()))
+        || generalized(quad.predicate.as_str())
+        || matches!(&quad.graph_name, GraphName::NamedNode(iri) if generalized(iri.as_str()))
+}
+
+/// A statement read by [`parse_statement`], and the prefixes and base IRI
+/// in effect after it.
+struct Parsed {
+    quads: Vec<Quad>,
+    prefixes: Prefixes,
+    base_iri: Option<String>,
+}
+
+fn parse_statement(
+    statement: &str,
+    lenient: bool,
+    base_iri: Option<&str>,
+    prefixes: &Prefixes,
+) -> Result<Result<Parsed, TurtleSyntaxError>, Box<dyn std::error::Error>> {
+    let mut parser = TriGParser::new();
+    if lenient {
+        parser = parser.lenient();
+    }
+    if let Some(base_iri) = base_iri {
+        parser = parser.with_base_iri(base_iri)?;
+    }
+    for (name, iri) in prefixes {
+        parser = parser.with_prefix(name.as_str(), iri.as_str())?;
+    }
+    let mut parser = parser.for_slice(statement);
+
+    let mut quads = Vec::new();
+    for quad in parser.by_ref() {
+        match quad {
+            Ok(quad) => quads.push(quad),
+            Err(e) => return Ok(Err(e)),
+        }
+    }
+    Ok(Ok(Parsed {
+        quads,
+        prefixes: parser
+            .prefixes()
+            .map(|(name, iri)| (name.to_string(), iri.to_string()))
+            .collect(),
+        base_iri: parser.base_iri().map(str::to_string),
+    }))
+}
+
+/// `statement` with the term `error` rejects in its position replaced by an
+/// IRI standing in for it, recorded in `terms`, or `None` when the error is
+/// not about a term out of place.
+fn generalize(
+    statement: &str,
+    error: &TurtleSyntaxError,
+    terms: &mut BTreeMap<String, String>,
+) -> Option<String> {
+    if !error.message().contains("is not a valid") {
+        return None;
+    }
+    let location = error.location();
+    let start = location.start.offset as usize;
+    let bytes = statement.as_bytes();
+    let end = match bytes.get(start)? {
+        quote @ (b'"' | b'\'') => {
+            let mut end = string_end(bytes, start, *quote)?;
+            // a language tag or datatype belongs to the literal
+            if bytes.get(end) == Some(&b'@') || bytes[end..].starts_with(b"^^") {
+                end += bytes[end..]
+                    .iter()
+                    .position(|b| b.is_ascii_whitespace() || matches!(b, b';' | b',' | b'}'))
+                    .unwrap_or(bytes.len() - end);
+            }
+            end
+        }
+        // an empty anonymous blank node
+        b'[' => {
+            start
+                + 1
+                + statement[start + 1..]
+                    .find(']')
+                    .filter(|close| statement[start + 1..start + 1 + close].trim().is_empty())?
+                + 1
+        }
+        b'_' | b'0'..=b'9' | b'+' | b'-' | b't' | b'f' => location.end.offset as usize,
+        _ => return None,
+    };
+    let term = statement.get(start..end)?;
+
+    let next = terms.len();
+    let iri = terms
+        .iter()
+        .find(|(_, written)| *written == term)
+        .map(|(iri, _)| iri.clone())
+        .unwrap_or_else(|| format!("{}{}", GENERALIZED_TERM, next));
+    terms.insert(iri.clone(), term.to_string());
+    Some(format!(
+        "{}<{}>{}",
+        &statement[..start],
+        iri,
+        &statement[end..]
+    ))
+}
+
+/// Line, column and byte offset, counted from 0, where a statement starts in the file.
+#[derive(Default)]
+struct Position {
+    line: u64,
+    column: u64,
+    offset: u64,
+}
+
+impl Position {
+    fn advance(&mut self, text: &str) {
+        self.offset += text.len() as u64;
+        for c in text.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += 1;
+            }
+        }
+    }
+
+    /// Locates an error found in the statement starting here within the
+    /// whole file.
+    fn locate(&self, error: &TurtleSyntaxError, input: &str) -> Diagnostic {
+        let location = error.location();
+        Diagnostic::new(
+            input,
+            self.shift(location.start),
+            self.shift(location.end),
+            error.message(),
+        )
+    }
+
+    fn shift(&self, position: TextPosition) -> TextPosition {
+        TextPosition {
+            line: self.line + position.line,
+            column: if position.line == 0 {
+                self.column + position.column
+            } else {
+                position.column
+            },
+            offset: self.offset + position.offset,
+        }
+    }
+}
+
+/// Splits the input into statements: directives, triples ending with a dot
+/// and TriG graph blocks. The scan follows strings, IRIs, comments and
+/// brackets well enough to find where a broken statement ends; a dot ending
+/// a line inside unclosed brackets also ends the statement.
+fn statements(input: &str) -> Vec<Range<usize>> {
+    let bytes = input.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut brackets = 0usize;
+    let mut braces = 0usize;
+    // where the first token of the current statement starts
+    let mut content_start = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() {
+            // SPARQL-style PREFIX and BASE have no dot; they end with the IRI
+            let directive = content_start.is_some_and(|s| is_directive(&input[s..i]));
+            if let Some(end) = input[i..].find('>').filter(|_| directive) {
+                i += end + 1;
+                statements.push(start..i);
+                start = i;
+                content_start = None;
+                continue;
+            }
+        } else if bytes[i] != b'#' && content_start.is_none() {
+            content_start = Some(i);
+        }
+
+        match bytes[i] {
+            b'#' => i = line_end(bytes, i),
+            b'<' => {
+                let iri_end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| *b == b'>' || b.is_ascii_whitespace());
+                i += match iri_end {
+                    Some(end) if bytes[i + 1 + end] == b'>' => end + 2,
+                    _ => 1,
+                };
+            }
+            quote @ (b'"' | b'\'') => match string_end(bytes, i, quote) {
+                Some(end) => i = end,
+                // an unterminated string takes the rest of its line along
+                None => {
+                    i = line_end(bytes, i);
+                    brackets = 0;
+                    statements.push(start..i);
+                    start = i;
+                    content_start = None;
+                }
+            },
+            b'[' | b'(' => {
+                brackets += 1;
+                i += 1;
+            }
+            b']' | b')' => {
+                brackets = brackets.saturating_sub(1);
+                i += 1;
+            }
+            b'{' => {
+                braces += 1;
+                i += 1;
+            }
+            b'}' => {
+                braces = braces.saturating_sub(1);
+                i += 1;
+                if braces == 0 && brackets == 0 {
+                    statements.push(start..i);
+                    start = i;
+                    content_start = None;
+                }
+            }
+            b'.' => {
+                i += 1;
+                let next = bytes.get(i).copied();
+                let ends_statement = next.is_none_or(|b| b.is_ascii_whitespace() || b == b'#');
+                let ends_line = next.is_none_or(|b| b == b'\n' || b == b'\r');
+                if ends_statement && braces == 0 && (brackets == 0 || ends_line) {
+                    brackets = 0;
+                    statements.push(start..i);
+                    start = i;
+                    content_start = None;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    if !input[start..].trim().is_empty() {
+        statements.push(start..input.len());
+    }
+    statements
+}
+
+fn is_directive(keyword: &str) -> bool {
+    keyword.eq_ignore_ascii_case("prefix") || keyword.eq_ignore_ascii_case("base")
+}
+
+fn line_end(bytes: &[u8], from: usize) -> usize {
+    bytes[from..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(bytes.len(), |end| from + end)
+}
+
+/// Index just past the string literal opening at `from`, or `None` when a
+/// short string has no closing quote on its line.
+fn string_end(bytes: &[u8], from: usize, quote: u8) -> Option<usize> {
+    let long = bytes.get(from + 1) == Some(&quote) && bytes.get(from + 2) == Some(&quote);
+    let mut i = if long { from + 3 } else { from + 1 };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote && !long => return Some(i + 1),
+            b if b == quote && bytes[i..].starts_with(&[quote; 3]) => return Some(i + 3),
+            b'\n' if !long => return None,
+            _ => i += 1,
+        }
+    }
+    long.then_some(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The statements of `input`, without surrounding whitespace.
+    fn split(input: &str) -> Vec<&str> {
+        statements(input)
+            .into_iter()
+            .map(|range| input[range].trim())
+            .collect()
+    }
+
+    #[test]
+    fn dots_inside_iris_do_not_end_statements() {
+        let input = "<http://example.org/a.b> <http://example.org/p> <http://example.org/c.d> .\n\
+                     <http://example.org/e> <http://example.org/p> \"x\" .\n";
+        assert_eq!(
+            split(input),
+            [
+                "<http://example.org/a.b> <http://example.org/p> <http://example.org/c.d> .",
+                "<http://example.org/e> <http://example.org/p> \"x\" .",
+            ]
+        );
+    }
+
+    #[test]
+    fn decimals_do_not_end_statements() {
+        let input = "ex:s ex:p 1.5, .5, 2.0e3 .\nex:t ex:p 3 .";
+        assert_eq!(
+            split(input),
+            ["ex:s ex:p 1.5, .5, 2.0e3 .", "ex:t ex:p 3 ."]
+        );
+    }
+
+    #[test]
+    fn long_strings_may_hold_dots_quotes_and_newlines() {
+        let input = "ex:s ex:p \"\"\"one. \"two\" .\nthree.\"\"\" .\nex:t ex:p 'x.' .";
+        assert_eq!(
+            split(input),
+            [
+                "ex:s ex:p \"\"\"one. \"two\" .\nthree.\"\"\" .",
+                "ex:t ex:p 'x.' ."
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_end_strings() {
+        let input = r#"ex:s ex:p "a \" . b" . ex:t ex:p "c" ."#;
+        assert_eq!(
+            split(input),
+            [r#"ex:s ex:p "a \" . b" ."#, r#"ex:t ex:p "c" ."#]
+        );
+    }
+
+    #[test]
+    fn unterminated_strings_end_with_their_line() {
+        let input = "ex:s ex:p \"broken .\nex:t ex:p \"fine\" .";
+        assert_eq!(
+            split(input),
+            ["ex:s ex:p \"broken .", "ex:t ex:p \"fine\" ."]
+        );
+    }
+
+    #[test]
+    fn sparql_style_directives_end_with_their_iri() {
+        let input = "PREFIX ex: <http://example.org/>\nbase <http://example.org/base/>\n\
+                     ex:s ex:p ex:o .";
+        assert_eq!(
+            split(input),
+            [
+                "PREFIX ex: <http://example.org/>",
+                "base <http://example.org/base/>",
+                "ex:s ex:p ex:o .",
+            ]
+        );
+    }
+
+    #[test]
+    fn turtle_directives_end_with_a_dot() {
+        let input =
+            "@prefix ex: <http://example.org/> .\n@base <http://example.org/> .\nex:s ex:p ex:o .";
+        assert_eq!(split(input).len(), 3);
+    }
+
+    #[test]
+    fn trig_blocks_are_one_statement() {
+        let input = "ex:g { ex:s ex:p ex:o . ex:t ex:p [ ex:q \"a.b\" ] . }\n\
+                     GRAPH ex:h { ex:u ex:p ex:o }\nex:v ex:p ex:o .";
+        assert_eq!(
+            split(input),
+            [
+                "ex:g { ex:s ex:p ex:o . ex:t ex:p [ ex:q \"a.b\" ] . }",
+                "GRAPH ex:h { ex:u ex:p ex:o }",
+                "ex:v ex:p ex:o .",
+            ]
+        );
+    }
+
+    #[test]
+    fn dots_ending_a_line_end_unclosed_brackets() {
+        let input = "ex:s ex:p [ ex:q ex:o .\nex:t ex:p ex:o .";
+        assert_eq!(
+            split(input),
+            ["ex:s ex:p [ ex:q ex:o .", "ex:t ex:p ex:o ."]
+        );
+    }
+
+    #[test]
+    fn comments_may_hold_dots() {
+        let input = "# one. two.\nex:s ex:p ex:o . # three.\nex:t ex:p ex:o .";
+        assert_eq!(
+            split(input),
+            [
+                "# one. two.\nex:s ex:p ex:o .",
+                "# three.\nex:t ex:p ex:o ."
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_text_is_a_statement() {
+        assert_eq!(
+            split("ex:s ex:p ex:o .\nex:t ex:p"),
+            ["ex:s ex:p ex:o .", "ex:t ex:p"]
+        );
+        assert_eq!(split("ex:s ex:p ex:o .\n  \n"), ["ex:s ex:p ex:o ."]);
+    }
+
+    #[test]
+    fn positions_count_lines_and_characters() {
+        let mut position = Position::default();
+        position.advance("é\nab");
+        assert_eq!((position.line, position.column, position.offset), (1, 2, 5));
+
+        // on the statement's first line, columns continue from its start
+        let shifted = position.shift(TextPosition {
+            line: 0,
+            column: 3,
+            offset: 3,
+        });
+        assert_eq!((shifted.line, shifted.column, shifted.offset), (1, 5, 8));
+        let shifted = position.shift(TextPosition {
+            line: 2,
+            column: 4,
+            offset: 20,
+        });
+        assert_eq!((shifted.line, shifted.column, shifted.offset), (3, 4, 25));
+    }
+
+    #[test]
+    fn errors_are_located_in_the_file() {
+        let input = "@prefix ex: <http://example.org/> .\n\
+                     ex:s ex:p ex:o .\n\
+                     ex:t ex:p ex:o ex:extra .\n\
+                     ex:u ex:p ex:o .\n";
+        let recovered = parse(input, false, None).unwrap();
+
+        assert_eq!(recovered.quads.len(), 2);
+        assert_eq!(recovered.skipped, 1);
+        let [error] = recovered.errors.as_slice() else {
+            panic!("expected one error, got {:?}", recovered.errors);
+        };
+        assert_eq!((error.line, error.column), (3, 16));
+    }
+
+    #[test]
+    fn prefixes_carry_over_to_later_statements() {
+        let input = "PREFIX ex: <http://example.org/>\nex:s ex:p ex:o .";
+        let recovered = parse(input, false, None).unwrap();
+
+        assert_eq!(recovered.quads.len(), 1);
+        assert_eq!(
+            recovered.quads[0].subject.to_string(),
+            "<http://example.org/s>"
+        );
+    }
+}
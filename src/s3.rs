@@ -0,0 +1,172 @@
+use std::env;
+use std::path::Path;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use url::Url;
+
+use crate::checksums::{hex, sha256_hex};
+use crate::http::RetryPolicy;
+use crate::output::{cache_control, content_type, url_path, OutputBackend};
+
+/// Writes generated files to an S3-compatible bucket using path-style
+/// requests signed with AWS Signature Version 4.
+///
+/// Credentials and endpoint are read from the usual environment variables:
+/// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`,
+/// `AWS_REGION` and `AWS_ENDPOINT_URL`.
+pub struct S3Output {
+    endpoint: Url,
+    region: String,
+    bucket: String,
+    prefix: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+    retry: RetryPolicy,
+}
+
+impl S3Output {
+    pub fn from_url(output: &str, retry: RetryPolicy) -> Result<Self, Box<dyn std::error::Error>> {
+        let url = Url::parse(output)?;
+        let bucket = url
+            .host_str()
+            .ok_or_else(|| format!("Missing bucket in {}", output))?
+            .to_string();
+        let prefix = url.path().trim_matches('/').to_string();
+
+        let region = env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+        let endpoint = env::var("AWS_ENDPOINT_URL")
+            .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region));
+
+        Ok(S3Output {
+            endpoint: Url::parse(&endpoint)?,
+            region,
+            bucket,
+            prefix,
+            access_key: env::var("AWS_ACCESS_KEY_ID")
+                .map_err(|_| "AWS_ACCESS_KEY_ID must be set for S3 output")?,
+            secret_key: env::var("AWS_SECRET_ACCESS_KEY")
+                .map_err(|_| "AWS_SECRET_ACCESS_KEY must be set for S3 output")?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok(),
+            retry,
+        })
+    }
+
+    fn key(&self, relative_path: &Path) -> String {
+        let path = url_path(relative_path);
+
+        if self.prefix.is_empty() {
+            path
+        } else {
+            format!("{}/{}", self.prefix, path)
+        }
+    }
+}
+
+impl OutputBackend for S3Output {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let canonical_uri = format!(
+            "{}/{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            uri_encode(&self.bucket),
+            uri_encode(&self.key(relative_path))
+        );
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", self.endpoint.host_str().unwrap_or_default(), port),
+            None => self.endpoint.host_str().unwrap_or_default().to_string(),
+        };
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = sha256_hex(contents);
+
+        // header names must be lowercase and sorted for the canonical request
+        let mut headers = vec![
+            ("cache-control", cache_control(relative_path).to_string()),
+            ("content-type", content_type(relative_path).to_string()),
+            ("host", host),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "PUT\n{}\n\n{}\n{}\n{}",
+            canonical_uri, canonical_headers, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let signing_key = [date.as_str(), self.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part.as_bytes()),
+            );
+        let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&canonical_uri);
+
+        self.retry
+            .send(&format!("PUT {}", url), Some(contents), || {
+                let mut request = ureq::put(url.as_str()).set("Authorization", &authorization);
+                for (name, value) in &headers {
+                    if *name != "host" {
+                        request = request.set(name, value);
+                    }
+                }
+                request
+            })?;
+
+        Ok(())
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes everything except unreserved characters and `/`, as
+/// required for S3 canonical URIs.
+fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
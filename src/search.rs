@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+
+use crate::assets::write_asset;
+use crate::output::OutputBackend;
+
+/// Term entry in `search-index.json`.
+#[derive(Serialize, Deserialize)]
+pub struct SearchEntry {
+    pub label: String,
+    pub iri: String,
+    /// Page describing the term, relative to the site root.
+    pub page: String,
+    pub anchor: String,
+    /// Names of the `rdf:type`s of the term, as its page shows them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    /// Synonyms that find the term and are shown when they matched.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alt_labels: Vec<String>,
+    /// Synonyms that find the term but are never shown, such as common
+    /// misspellings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_labels: Vec<String>,
+    /// Comments, definitions and descriptions of the term, searched word by
+    /// word.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub descriptions: Vec<String>,
+}
+
+/// Writes `search-index.json`, which the search page and the quick switcher
+/// look terms up in.
+pub fn write_search_index(
+    output: &dyn OutputBackend,
+    entries: &[SearchEntry],
+) -> Result<(), Box<dyn std::error::Error>> {
+    output.write(
+        Path::new("search-index.json"),
+        serde_json::to_string(entries)?.as_bytes(),
+    )
+}
+
+/// Writes the `search.js` script and the `search.html` page that uses it
+/// with the search index.
+pub fn write_search(
+    output: &dyn OutputBackend,
+    tera: &Tera,
+    base_context: &Context,
+    hash_assets: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let script = write_asset(
+        output,
+        "search.js",
+        include_bytes!("../templates/search.js"),
+        hash_assets,
+    )?;
+
+    let mut context = base_context.clone();
+    context.insert("title", "Search");
+    context.insert("root", "");
+    context.insert("script", &script);
+
+    let html = tera.render("search.html", &context)?;
+    output.write(Path::new("search.html"), html.as_bytes())
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use percent_encoding::percent_decode_str;
+
+use crate::checksums::sha256_hex;
+use crate::output::{cache_control, content_type};
+
+const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Event stream that `live-reload.js` listens to.
+pub const LIVE_RELOAD_PATH: &str = "/__live-reload";
+
+/// Tells the pages open in browsers to reload when the site was rebuilt.
+#[derive(Clone, Default)]
+pub struct LiveReload {
+    /// Number of rebuilds so far.
+    generation: Arc<(Mutex<u64>, Condvar)>,
+}
+
+impl LiveReload {
+    /// Reloads every page listening for changes.
+    pub fn notify(&self) {
+        let (generation, changed) = &*self.generation;
+        *generation.lock().unwrap() += 1;
+        changed.notify_all();
+    }
+
+    /// Sends a reload event on `stream` after the next rebuild.
+    fn stream_to(&self, mut stream: TcpStream) -> Result<(), Box<dyn std::error::Error>> {
+        let headers = [
+            ("Content-Type", "text/event-stream".to_string()),
+            ("Cache-Control", "no-store".to_string()),
+        ];
+        respond(&mut stream, "200 OK", &headers, None)?;
+
+        let generation = self.generation.clone();
+        thread::spawn(move || {
+            let (current, changed) = &*generation;
+            let seen = *current.lock().unwrap();
+            let _unchanged = changed
+                .wait_while(current.lock().unwrap(), |current| *current == seen)
+                .unwrap();
+            // the page may have been closed in the meantime
+            let _ = stream.write_all(b"data: reload\n\n");
+        });
+
+        Ok(())
+    }
+}
+
+/// Minimal static file server for previewing a generated site. It sends the
+/// same `Cache-Control` headers as the S3 backend, along with `ETag` and
+/// `Last-Modified`, and answers conditional requests with `304 Not Modified`,
+/// so caching problems show up before the site is published.
+pub struct Server {
+    root: PathBuf,
+    /// `Cache-Control` values by file extension, replacing the defaults.
+    cache_control: HashMap<String, String>,
+    live_reload: Option<LiveReload>,
+}
+
+impl Server {
+    pub fn new(root: &Path, cache_control: HashMap<String, String>) -> Self {
+        Server {
+            root: root.to_path_buf(),
+            cache_control,
+            live_reload: None,
+        }
+    }
+
+    /// Also serves the event stream telling pages to reload on `live_reload`.
+    pub fn with_live_reload(mut self, live_reload: LiveReload) -> Self {
+        self.live_reload = Some(live_reload);
+        self
+    }
+
+    pub fn run(&self, address: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind(address)?;
+        info!(
+            "Serving {:?} on http://{}/",
+            self.root,
+            listener.local_addr()?
+        );
+
+        // browsers open several connections at once, so a slow one must not
+        // hold up the others
+        thread::scope(|scope| {
+            for stream in listener.incoming() {
+                scope.spawn(move || {
+                    if let Err(e) = stream.map_err(|e| e.into()).and_then(|s| self.handle(s)) {
+                        error!("Error serving request: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(())
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> Result<(), Box<dyn std::error::Error>> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method, target),
+            _ => return respond(&mut stream, "400 Bad Request", &[], None),
+        };
+        if method != "GET" && method != "HEAD" {
+            return respond(
+                &mut stream,
+                "405 Method Not Allowed",
+                &[("Allow", "GET, HEAD".to_string())],
+                None,
+            );
+        }
+
+        if let Some(live_reload) = &self.live_reload {
+            if target == LIVE_RELOAD_PATH {
+                return live_reload.stream_to(stream);
+            }
+        }
+
+        let path = match self.resolve(target) {
+            Some(path) => path,
+            None => return respond(&mut stream, "404 Not Found", &[], None),
+        };
+        let contents = fs::read(&path)?;
+        let modified: DateTime<Utc> = fs::metadata(&path)?.modified()?.into();
+        let etag = format!("\"{}\"", sha256_hex(&contents));
+        let last_modified = modified.format(HTTP_DATE).to_string();
+
+        let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+        let mut response_headers = vec![
+            ("Content-Type", content_type(&path).to_string()),
+            (
+                "Cache-Control",
+                self.cache_control
+                    .get(extension)
+                    .cloned()
+                    .unwrap_or_else(|| cache_control(&path).to_string()),
+            ),
+            ("ETag", etag.clone()),
+            ("Last-Modified", last_modified),
+        ];
+
+        let unchanged = not_modified(&headers, &etag, modified);
+        // responses without the body describe the one a GET would receive
+        if unchanged || method == "HEAD" {
+            response_headers.push(("Content-Length", contents.len().to_string()));
+        }
+
+        if unchanged {
+            return respond(&mut stream, "304 Not Modified", &response_headers, None);
+        }
+        let body = (method == "GET").then_some(contents.as_slice());
+        respond(&mut stream, "200 OK", &response_headers, body)
+    }
+
+    /// Maps a request target to a file below the root, serving `index.html`
+    /// for directories. Paths escaping the root are not served.
+    fn resolve(&self, target: &str) -> Option<PathBuf> {
+        let path = target.split(['?', '#']).next().unwrap_or("");
+        let path = percent_decode_str(path).decode_utf8().ok()?;
+
+        let mut resolved = self.root.clone();
+        for component in Path::new(path.trim_start_matches('/')).components() {
+            match component {
+                Component::Normal(part) => resolved.push(part),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+
+        if resolved.is_dir() {
+            resolved.push("index.html");
+        }
+        resolved.is_file().then_some(resolved)
+    }
+}
+
+/// Evaluates `If-None-Match`, or `If-Modified-Since` when there is no entity
+/// tag to compare, as specified by RFC 9110.
+fn not_modified(headers: &HashMap<String, String>, etag: &str, modified: DateTime<Utc>) -> bool {
+    if let Some(if_none_match) = headers.get("if-none-match") {
+        return if_none_match
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag);
+    }
+
+    match headers
+        .get("if-modified-since")
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+    {
+        // HTTP dates have a resolution of one second
+        Some(since) => modified.timestamp() <= since.timestamp(),
+        None => false,
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    headers: &[(&str, String)],
+    body: Option<&[u8]>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut response = format!("HTTP/1.1 {}\r\nConnection: close\r\n", status);
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if let Some(body) = body {
+        response.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    response.push_str("\r\n");
+
+    stream.write_all(response.as_bytes())?;
+    if let Some(body) = body {
+        stream.write_all(body)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use url::Url;
+
+use crate::output::{url_path, OutputBackend};
+
+/// Limits of a single sitemap file imposed by the sitemap protocol.
+const MAX_URLS: usize = 50_000;
+const MAX_BYTES: usize = 50 * 1024 * 1024;
+
+const URLSET_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+    <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n";
+const URLSET_FOOTER: &str = "</urlset>\n";
+
+/// Wraps another backend and remembers every HTML page written through it so
+/// that sitemaps can be generated once the site is complete.
+pub struct SitemapOutput<'a> {
+    inner: &'a dyn OutputBackend,
+    pages: Mutex<Vec<PathBuf>>,
+}
+
+impl<'a> SitemapOutput<'a> {
+    pub fn new(inner: &'a dyn OutputBackend) -> Self {
+        SitemapOutput {
+            inner,
+            pages: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Writes `sitemap.xml`, or several `sitemap-N.xml` files referenced from
+    /// `sitemap_index.xml` when the pages do not fit in a single sitemap.
+    /// Returns the path of the file crawlers should be pointed at.
+    pub fn write_sitemaps(&self, base_url: &Url) -> Result<String, Box<dyn std::error::Error>> {
+        let mut pages = self.pages.lock().unwrap().clone();
+        pages.sort();
+        pages.dedup();
+
+        let mut sitemaps = Vec::new();
+        let mut current = String::from(URLSET_HEADER);
+        let mut count = 0;
+
+        for page in &pages {
+            let entry = format!(
+                "  <url><loc>{}</loc></url>\n",
+                xml_escape(page_url(base_url, page)?.as_str())
+            );
+
+            if count == MAX_URLS || current.len() + entry.len() + URLSET_FOOTER.len() > MAX_BYTES {
+                current.push_str(URLSET_FOOTER);
+                sitemaps.push(std::mem::replace(&mut current, String::from(URLSET_HEADER)));
+                count = 0;
+            }
+
+            current.push_str(&entry);
+            count += 1;
+        }
+        current.push_str(URLSET_FOOTER);
+        sitemaps.push(current);
+
+        if sitemaps.len() == 1 {
+            self.inner
+                .write(Path::new("sitemap.xml"), sitemaps[0].as_bytes())?;
+            return Ok("sitemap.xml".to_string());
+        }
+
+        let mut index = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for (i, sitemap) in sitemaps.iter().enumerate() {
+            let name = format!("sitemap-{}.xml", i + 1);
+            self.inner.write(Path::new(&name), sitemap.as_bytes())?;
+            index.push_str(&format!(
+                "  <sitemap><loc>{}</loc></sitemap>\n",
+                xml_escape(base_url.join(&name)?.as_str())
+            ));
+        }
+        index.push_str("</sitemapindex>\n");
+
+        self.inner
+            .write(Path::new("sitemap_index.xml"), index.as_bytes())?;
+
+        Ok("sitemap_index.xml".to_string())
+    }
+}
+
+impl OutputBackend for SitemapOutput<'_> {
+    fn write(
+        &self,
+        relative_path: &Path,
+        contents: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if relative_path.extension().and_then(|s| s.to_str()) == Some("html") {
+            self.pages.lock().unwrap().push(relative_path.to_path_buf());
+        }
+
+        self.inner.write(relative_path, contents)
+    }
+}
+
+/// Parses `--base-url`, making sure relative paths are joined below it.
+pub fn parse_base_url(base_url: &str) -> Result<Url, url::ParseError> {
+    if base_url.ends_with('/') {
+        Url::parse(base_url)
+    } else {
+        Url::parse(&format!("{}/", base_url))
+    }
+}
+
+pub fn page_url(base_url: &Url, page: &Path) -> Result<Url, url::ParseError> {
+    base_url.join(&url_path(page))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
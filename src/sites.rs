@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::http::RetryPolicy;
+use crate::output::OutputBackend;
+use crate::search::SearchEntry;
+
+const MANIFEST: &str = "terms.json";
+
+/// Terms documented by a generated site, published as `terms.json` so that
+/// sibling sites can link to their documentation instead of the bare IRIs.
+#[derive(Serialize, Deserialize, Default)]
+pub struct TermsManifest {
+    /// Absolute URL of the documentation of each term, by term IRI.
+    pub terms: BTreeMap<String, String>,
+}
+
+impl TermsManifest {
+    pub fn from_entries(entries: &[SearchEntry], base_url: &Url) -> Result<Self, url::ParseError> {
+        let mut terms = BTreeMap::new();
+        for entry in entries {
+            let mut url = base_url.join(&entry.page)?;
+            url.set_fragment(Some(&entry.anchor));
+            terms.insert(entry.iri.clone(), url.to_string());
+        }
+
+        Ok(TermsManifest { terms })
+    }
+
+    pub fn write(&self, output: &dyn OutputBackend) -> Result<(), Box<dyn std::error::Error>> {
+        output.write(
+            Path::new(MANIFEST),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Reads the manifest of another site, given the URL of the site (or of
+    /// its manifest) or a local path to either.
+    pub fn load(source: &str, retry: RetryPolicy) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = if source.starts_with("http://") || source.starts_with("https://") {
+            let mut url = Url::parse(source)?;
+            if !url.path().ends_with(".json") {
+                if !url.path().ends_with('/') {
+                    url.set_path(&format!("{}/", url.path()));
+                }
+                url = url.join(MANIFEST)?;
+            }
+            retry
+                .send(&format!("GET {}", url), None, || ureq::get(url.as_str()))?
+                .into_string()?
+        } else {
+            let path = Path::new(source);
+            if path.is_dir() {
+                fs::read_to_string(path.join(MANIFEST))?
+            } else {
+                fs::read_to_string(path)?
+            }
+        };
+
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid manifest {}: {}", source, e).into())
+    }
+}
@@ -0,0 +1,104 @@
+//! Links from the terms of the core vocabularies to the sections of the W3C
+//! specifications defining them.
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const RDFS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+const OWL: &str = "http://www.w3.org/2002/07/owl#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+const SKOS: &str = "http://www.w3.org/2004/02/skos/core#";
+
+const RDFS_SPEC: &str = "https://www.w3.org/TR/rdf11-schema/";
+const OWL_SPEC: &str = "https://www.w3.org/TR/owl-ref/";
+const OWL2_SPEC: &str = "https://www.w3.org/TR/owl2-quick-reference/";
+const XSD_SPEC: &str = "https://www.w3.org/TR/xmlschema11-2/";
+const SKOS_SPEC: &str = "https://www.w3.org/TR/skos-reference/";
+
+/// OWL terms with a definition of their own in the OWL reference.
+const OWL_REFERENCE_TERMS: &[&str] = &[
+    "AllDifferent",
+    "AnnotationProperty",
+    "Class",
+    "DataRange",
+    "DatatypeProperty",
+    "DeprecatedClass",
+    "DeprecatedProperty",
+    "FunctionalProperty",
+    "InverseFunctionalProperty",
+    "Nothing",
+    "ObjectProperty",
+    "Ontology",
+    "OntologyProperty",
+    "Restriction",
+    "SymmetricProperty",
+    "Thing",
+    "TransitiveProperty",
+    "allValuesFrom",
+    "backwardCompatibleWith",
+    "cardinality",
+    "complementOf",
+    "differentFrom",
+    "disjointWith",
+    "distinctMembers",
+    "equivalentClass",
+    "equivalentProperty",
+    "hasValue",
+    "imports",
+    "incompatibleWith",
+    "intersectionOf",
+    "inverseOf",
+    "maxCardinality",
+    "minCardinality",
+    "oneOf",
+    "onProperty",
+    "priorVersion",
+    "sameAs",
+    "someValuesFrom",
+    "unionOf",
+    "versionInfo",
+];
+
+/// URL of the specification section defining `iri`, if it belongs to one of
+/// the core vocabularies.
+pub fn specification_url(iri: &str) -> Option<String> {
+    // RDF Schema documents the terms of both the rdf: and rdfs: namespaces
+    if let Some(local) = local_name(iri, RDFS).or_else(|| local_name(iri, RDF)) {
+        return Some(format!("{}#ch_{}", RDFS_SPEC, local.to_lowercase()));
+    }
+
+    if let Some(local) = local_name(iri, OWL) {
+        return Some(if OWL_REFERENCE_TERMS.contains(&local) {
+            format!("{}#{}-def", OWL_SPEC, local)
+        } else {
+            OWL2_SPEC.to_string()
+        });
+    }
+
+    if let Some(local) = local_name(iri, XSD) {
+        return Some(format!("{}#{}", XSD_SPEC, local));
+    }
+
+    if let Some(local) = local_name(iri, SKOS) {
+        let section = match local {
+            "Concept" => "concepts",
+            "ConceptScheme" | "inScheme" | "hasTopConcept" | "topConceptOf" => "schemes",
+            "prefLabel" | "altLabel" | "hiddenLabel" => "labels",
+            "notation" => "notations",
+            "note" | "changeNote" | "definition" | "editorialNote" | "example" | "historyNote"
+            | "scopeNote" => "notes",
+            "semanticRelation" | "broader" | "narrower" | "related" | "broaderTransitive"
+            | "narrowerTransitive" => "semantic-relations",
+            "Collection" | "OrderedCollection" | "member" | "memberList" => "collections",
+            "mappingRelation" | "closeMatch" | "exactMatch" | "broadMatch" | "narrowMatch"
+            | "relatedMatch" => "mapping",
+            _ => return Some(SKOS_SPEC.to_string()),
+        };
+        return Some(format!("{}#{}", SKOS_SPEC, section));
+    }
+
+    None
+}
+
+fn local_name<'a>(iri: &'a str, namespace: &str) -> Option<&'a str> {
+    iri.strip_prefix(namespace)
+        .filter(|local| !local.is_empty())
+}
@@ -0,0 +1,147 @@
+//! Sorting that spills to disk, for grouping the statements of input files
+//! too large to sort in memory.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{self, AtomicUsize};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::de::IoRead;
+use serde_json::StreamDeserializer;
+
+/// Number of runs written by this process so far, to name their files.
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sorts `items` with `compare` by writing sorted runs of at most `budget`
+/// bytes, as measured by `size`, to temporary files and merging them. Like
+/// `sort_by`, the sort is stable. The files are removed once the returned
+/// iterator is dropped.
+pub fn sort_spilled<T: Serialize + DeserializeOwned>(
+    items: Vec<T>,
+    budget: usize,
+    size: fn(&T) -> usize,
+    compare: fn(&T, &T) -> Ordering,
+) -> io::Result<Merge<T>> {
+    let mut runs = Vec::new();
+    let mut run = Vec::new();
+    let mut run_size = 0;
+    for item in items {
+        run_size += size(&item);
+        run.push(item);
+        if run_size >= budget {
+            runs.push(Run::write(&mut run, compare)?);
+            run_size = 0;
+        }
+    }
+    if !run.is_empty() {
+        runs.push(Run::write(&mut run, compare)?);
+    }
+
+    let mut merge = Merge {
+        runs,
+        heads: BinaryHeap::new(),
+        compare,
+    };
+    for i in 0..merge.runs.len() {
+        merge.advance(i)?;
+    }
+    Ok(merge)
+}
+
+/// A sorted run of items in a temporary file.
+struct Run<T> {
+    path: PathBuf,
+    items: StreamDeserializer<'static, IoRead<BufReader<File>>, T>,
+}
+
+impl<T: Serialize + DeserializeOwned> Run<T> {
+    /// Sorts `items` and moves them to a new run, one JSON value per line.
+    fn write(items: &mut Vec<T>, compare: fn(&T, &T) -> Ordering) -> io::Result<Self> {
+        items.sort_by(compare);
+        let path = std::env::temp_dir().join(format!(
+            "ttl2html-spill-{}-{}.jsonl",
+            std::process::id(),
+            RUNS.fetch_add(1, atomic::Ordering::Relaxed)
+        ));
+        let mut file = BufWriter::new(File::create(&path)?);
+        for item in items.drain(..) {
+            serde_json::to_writer(&mut file, &item)?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()?;
+
+        let reader = BufReader::new(File::open(&path)?);
+        Ok(Run {
+            path,
+            items: serde_json::Deserializer::from_reader(reader).into_iter(),
+        })
+    }
+}
+
+impl<T> Drop for Run<T> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The next item of a run, ordered so that the heap yields the smallest
+/// first, and the earliest run among equal items.
+struct Head<T> {
+    item: T,
+    run: usize,
+    compare: fn(&T, &T) -> Ordering,
+}
+
+impl<T> Ord for Head<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.compare)(&other.item, &self.item).then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+impl<T> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for Head<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Head<T> {}
+
+/// The items of all runs in order, reading one item of each run at a time.
+pub struct Merge<T> {
+    runs: Vec<Run<T>>,
+    heads: BinaryHeap<Head<T>>,
+    compare: fn(&T, &T) -> Ordering,
+}
+
+impl<T: Serialize + DeserializeOwned> Merge<T> {
+    /// Reads the next item of run `i` into the heap.
+    fn advance(&mut self, i: usize) -> io::Result<()> {
+        if let Some(item) = self.runs[i].items.next() {
+            self.heads.push(Head {
+                item: item?,
+                run: i,
+                compare: self.compare,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Iterator for Merge<T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let head = self.heads.pop()?;
+        Some(self.advance(head.run).map(|()| head.item))
+    }
+}
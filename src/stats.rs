@@ -0,0 +1,133 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use crate::output::OutputBackend;
+use crate::parser::{Converted, IndexEntry};
+
+/// Counts of one input file.
+#[derive(Serialize)]
+struct FileStats {
+    source: IndexEntry,
+    triples: usize,
+    subjects: usize,
+    predicates: usize,
+}
+
+/// Row of a table of terms or namespaces, with the number of statements or
+/// instances counted for it.
+#[derive(Serialize)]
+struct Count {
+    iri: String,
+    /// The IRI shortened with a prefix declared in the input, if any.
+    name: String,
+    count: usize,
+}
+
+/// Collects the counts shown on the statistics page across the corpus.
+#[derive(Default)]
+pub struct SiteStats {
+    files: Vec<FileStats>,
+    triples: usize,
+    subjects: BTreeSet<String>,
+    predicates: HashMap<String, usize>,
+    instances: HashMap<String, usize>,
+    namespaces: BTreeMap<String, String>,
+}
+
+impl SiteStats {
+    /// Records the counts of one converted file, whose page `source` links
+    /// to.
+    pub fn add(&mut self, converted: &Converted, source: &IndexEntry) {
+        self.files.push(FileStats {
+            source: source.clone(),
+            triples: converted.triples,
+            subjects: converted.subjects.len(),
+            predicates: converted.usage.predicates.len(),
+        });
+        self.triples += converted.triples;
+        self.subjects
+            .extend(converted.subjects.iter().map(|subject| subject.iri.clone()));
+        for (iri, count) in &converted.usage.predicates {
+            *self.predicates.entry(iri.clone()).or_default() += count;
+        }
+        for (iri, count) in &converted.usage.instances {
+            *self.instances.entry(iri.clone()).or_default() += count;
+        }
+        // the first file declaring a prefix names the namespace
+        for (prefix, iri) in &converted.namespaces {
+            self.namespaces
+                .entry(iri.clone())
+                .or_insert_with(|| prefix.clone());
+        }
+    }
+
+    /// `iri` shortened with a declared prefix.
+    fn name(&self, iri: &str) -> String {
+        let (namespace, local) = split_iri(iri);
+        match self.namespaces.get(namespace) {
+            Some(prefix) => format!("{}:{}", prefix, local),
+            None => iri.to_string(),
+        }
+    }
+
+    /// Rows for `counts`, most counted first.
+    fn rows(&self, counts: &HashMap<String, usize>) -> Vec<Count> {
+        let mut rows: Vec<Count> = counts
+            .iter()
+            .map(|(iri, count)| Count {
+                iri: iri.clone(),
+                name: self.name(iri),
+                count: *count,
+            })
+            .collect();
+        rows.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.iri.cmp(&b.iri)));
+        rows
+    }
+
+    /// Writes `stats.html` with the totals of the corpus, the classes by
+    /// number of instances, the namespaces of the predicates by number of
+    /// statements, and the counts of every file.
+    pub fn write_page(
+        &self,
+        output: &dyn OutputBackend,
+        tera: &Tera,
+        base_context: &Context,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut namespaces: HashMap<String, usize> = HashMap::new();
+        for (iri, count) in &self.predicates {
+            *namespaces.entry(split_iri(iri).0.to_string()).or_default() += count;
+        }
+        let namespaces: Vec<Count> = self
+            .rows(&namespaces)
+            .into_iter()
+            .map(|row| Count {
+                name: self.namespaces.get(&row.iri).cloned().unwrap_or_default(),
+                ..row
+            })
+            .collect();
+
+        let mut context = base_context.clone();
+        context.insert("title", "Statistics");
+        context.insert("root", "");
+        context.insert("triples", &self.triples);
+        context.insert("subjects", &self.subjects.len());
+        context.insert("predicates", &self.rows(&self.predicates));
+        context.insert("classes", &self.rows(&self.instances));
+        context.insert("namespaces", &namespaces);
+        context.insert("files", &self.files);
+
+        let html = tera.render("stats.html", &context)?;
+        output.write(Path::new("stats.html"), html.as_bytes())
+    }
+}
+
+/// Splits `iri` after its last `#` or `/` into namespace and local name.
+fn split_iri(iri: &str) -> (&str, &str) {
+    match iri.rfind(['#', '/']) {
+        Some(position) => iri.split_at(position + 1),
+        None => (iri, ""),
+    }
+}
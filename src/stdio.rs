@@ -0,0 +1,49 @@
+//! Standard input and output in place of the input and output directories,
+//! given as `-i -` and `-o -`.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The argument naming standard input or output.
+pub const STDIO: &str = "-";
+
+/// Name of the input file read from standard input, which makes its page
+/// `stdin.html`.
+const STDIN_FILE: &str = "stdin.ttl";
+
+/// Standard input, read once, since pages may read their input again to show
+/// or copy it.
+static STDIN: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+
+/// The path that stands for standard input, placed in the input directory
+/// [`STDIO`].
+pub fn stdin_path() -> PathBuf {
+    Path::new(STDIO).join(STDIN_FILE)
+}
+
+/// Contents of the input file `path`, or of standard input for
+/// [`stdin_path`].
+pub fn read_input(path: &Path) -> io::Result<Vec<u8>> {
+    if path != stdin_path() {
+        return fs::read(path);
+    }
+    let mut stdin = STDIN.lock().unwrap();
+    if stdin.is_none() {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        *stdin = Some(input);
+    }
+    Ok(stdin.clone().unwrap_or_default())
+}
+
+/// Contents of the input file `path` as text, like [`read_input`].
+pub fn read_input_to_string(path: &Path) -> io::Result<String> {
+    String::from_utf8(read_input(path)?).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })
+}
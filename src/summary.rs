@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::output::OutputBackend;
+
+/// Machine-readable description of the generated site, written as
+/// `llms.txt` for language models and `index.json` for other tools.
+#[derive(Serialize, Default)]
+pub struct SiteSummary {
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Namespace IRIs by prefix, as declared in the input files.
+    pub namespaces: BTreeMap<String, String>,
+    /// Overview pages and term lists, such as the index and the search page.
+    pub resources: Vec<Resource>,
+    pub pages: Vec<SummaryPage>,
+    pub terms: usize,
+}
+
+#[derive(Serialize)]
+pub struct Resource {
+    pub path: String,
+    pub description: String,
+}
+
+/// Page generated from one input file.
+#[derive(Serialize)]
+pub struct SummaryPage {
+    pub name: String,
+    pub path: String,
+    pub triples: usize,
+}
+
+impl SiteSummary {
+    pub fn write(&self, output: &dyn OutputBackend) -> Result<(), Box<dyn std::error::Error>> {
+        output.write(
+            Path::new("index.json"),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )?;
+        output.write(Path::new("llms.txt"), self.llms_txt()?.as_bytes())
+    }
+
+    /// The summary in the llms.txt format: a title, a quoted description and
+    /// sections of links.
+    fn llms_txt(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let base_url = self.base_url.as_deref().map(Url::parse).transpose()?;
+        let url = |path: &str| -> Result<String, url::ParseError> {
+            Ok(match &base_url {
+                Some(base_url) => base_url.join(path)?.to_string(),
+                None => path.to_string(),
+            })
+        };
+
+        let mut text = String::new();
+        writeln!(text, "# {}", self.title)?;
+        writeln!(text)?;
+        let description = self.description.clone().unwrap_or_else(|| {
+            format!(
+                "Documentation generated from RDF: {} page(s) describing {} term(s).",
+                self.pages.len(),
+                self.terms
+            )
+        });
+        writeln!(text, "> {}", description.replace('\n', " "))?;
+
+        if !self.namespaces.is_empty() {
+            writeln!(text)?;
+            writeln!(text, "## Namespaces")?;
+            writeln!(text)?;
+            for (prefix, iri) in &self.namespaces {
+                writeln!(text, "- {}: <{}>", prefix, iri)?;
+            }
+        }
+
+        writeln!(text)?;
+        writeln!(text, "## Overview")?;
+        writeln!(text)?;
+        for resource in &self.resources {
+            writeln!(
+                text,
+                "- [{}]({}): {}",
+                resource.path,
+                url(&resource.path)?,
+                resource.description
+            )?;
+        }
+
+        writeln!(text)?;
+        writeln!(text, "## Pages")?;
+        writeln!(text)?;
+        for page in &self.pages {
+            writeln!(
+                text,
+                "- [{}]({}): {} statement(s)",
+                page.name,
+                url(&page.path)?,
+                page.triples
+            )?;
+        }
+
+        Ok(text)
+    }
+}
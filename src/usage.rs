@@ -0,0 +1,138 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+
+use crate::output::OutputBackend;
+
+/// How one input file uses and defines terms.
+#[derive(Serialize, Deserialize, Default)]
+pub struct FileUsage {
+    /// Statements per predicate IRI.
+    pub predicates: HashMap<String, usize>,
+    /// Instances per class IRI.
+    pub instances: HashMap<String, usize>,
+    pub definitions: Vec<Definition>,
+}
+
+/// Class or property defined by a subject of the corpus.
+#[derive(Serialize, Deserialize)]
+pub struct Definition {
+    pub iri: String,
+    pub label: String,
+    pub kind: TermKind,
+    /// Page describing the term, relative to the site root.
+    pub page: String,
+    pub anchor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum TermKind {
+    Class,
+    Property,
+}
+
+/// Row of the usage report.
+#[derive(Serialize)]
+struct TermUsage {
+    label: String,
+    iri: String,
+    kind: TermKind,
+    page: Option<String>,
+    anchor: Option<String>,
+    /// Instances of a class, or statements using a property.
+    uses: usize,
+}
+
+/// Collects term usage across the corpus for the usage report page.
+#[derive(Default)]
+pub struct UsageStats {
+    predicates: HashMap<String, usize>,
+    instances: HashMap<String, usize>,
+    definitions: BTreeMap<(TermKind, String), Definition>,
+}
+
+impl UsageStats {
+    pub fn add(&mut self, usage: FileUsage) {
+        for (iri, count) in usage.predicates {
+            *self.predicates.entry(iri).or_default() += count;
+        }
+        for (iri, count) in usage.instances {
+            *self.instances.entry(iri).or_default() += count;
+        }
+        // a term defined in several files links to the first of them
+        for definition in usage.definitions {
+            self.definitions
+                .entry((definition.kind, definition.iri.clone()))
+                .or_insert(definition);
+        }
+    }
+
+    fn uses(&self, kind: TermKind, iri: &str) -> usize {
+        let counts = match kind {
+            TermKind::Class => &self.instances,
+            TermKind::Property => &self.predicates,
+        };
+        counts.get(iri).copied().unwrap_or_default()
+    }
+
+    /// Writes `usage.html`: the defined terms, least used first, followed by
+    /// the terms used without being defined anywhere in the corpus.
+    pub fn write_report(
+        &self,
+        output: &dyn OutputBackend,
+        tera: &Tera,
+        base_context: &Context,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut defined: Vec<TermUsage> = self
+            .definitions
+            .values()
+            .map(|definition| TermUsage {
+                label: definition.label.clone(),
+                iri: definition.iri.clone(),
+                kind: definition.kind,
+                page: Some(definition.page.clone()),
+                anchor: definition.anchor.clone(),
+                uses: self.uses(definition.kind, &definition.iri),
+            })
+            .collect();
+        defined.sort_by(|a, b| a.uses.cmp(&b.uses).then_with(|| a.label.cmp(&b.label)));
+
+        let undefined_terms = self
+            .instances
+            .iter()
+            .map(|(iri, uses)| (TermKind::Class, iri, *uses))
+            .chain(
+                self.predicates
+                    .iter()
+                    .map(|(iri, uses)| (TermKind::Property, iri, *uses)),
+            )
+            .filter(|(kind, iri, _)| !self.definitions.contains_key(&(*kind, iri.to_string())));
+        let mut undefined: Vec<TermUsage> = undefined_terms
+            .map(|(kind, iri, uses)| TermUsage {
+                label: iri.clone(),
+                iri: iri.clone(),
+                kind,
+                page: None,
+                anchor: None,
+                uses,
+            })
+            .collect();
+        undefined.sort_by(|a, b| b.uses.cmp(&a.uses).then_with(|| a.iri.cmp(&b.iri)));
+
+        let mut context = base_context.clone();
+        context.insert("title", "Term Usage");
+        context.insert("root", "");
+        context.insert("defined", &defined);
+        context.insert(
+            "unused",
+            &defined.iter().filter(|term| term.uses == 0).count(),
+        );
+        context.insert("undefined", &undefined);
+
+        let html = tera.render("usage.html", &context)?;
+        output.write(Path::new("usage.html"), html.as_bytes())
+    }
+}
@@ -0,0 +1,156 @@
+//! IRIs of the well-known vocabularies the converter gives special treatment.
+
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_PROPERTY: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#Property";
+pub const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+pub const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+pub const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+pub const RDF_LIST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#List";
+
+pub const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+pub const RDFS_COMMENT: &str = "http://www.w3.org/2000/01/rdf-schema#comment";
+pub const RDFS_CLASS: &str = "http://www.w3.org/2000/01/rdf-schema#Class";
+pub const RDFS_SUB_CLASS_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subClassOf";
+pub const RDFS_SUB_PROPERTY_OF: &str = "http://www.w3.org/2000/01/rdf-schema#subPropertyOf";
+pub const RDFS_DOMAIN: &str = "http://www.w3.org/2000/01/rdf-schema#domain";
+pub const RDFS_RANGE: &str = "http://www.w3.org/2000/01/rdf-schema#range";
+
+pub const OWL_ONTOLOGY: &str = "http://www.w3.org/2002/07/owl#Ontology";
+pub const OWL_CLASS: &str = "http://www.w3.org/2002/07/owl#Class";
+pub const OWL_NAMED_INDIVIDUAL: &str = "http://www.w3.org/2002/07/owl#NamedIndividual";
+pub const OWL_OBJECT_PROPERTY: &str = "http://www.w3.org/2002/07/owl#ObjectProperty";
+pub const OWL_DATATYPE_PROPERTY: &str = "http://www.w3.org/2002/07/owl#DatatypeProperty";
+pub const OWL_ANNOTATION_PROPERTY: &str = "http://www.w3.org/2002/07/owl#AnnotationProperty";
+pub const OWL_FUNCTIONAL_PROPERTY: &str = "http://www.w3.org/2002/07/owl#FunctionalProperty";
+pub const OWL_INVERSE_FUNCTIONAL_PROPERTY: &str =
+    "http://www.w3.org/2002/07/owl#InverseFunctionalProperty";
+pub const OWL_TRANSITIVE_PROPERTY: &str = "http://www.w3.org/2002/07/owl#TransitiveProperty";
+pub const OWL_SYMMETRIC_PROPERTY: &str = "http://www.w3.org/2002/07/owl#SymmetricProperty";
+pub const OWL_EQUIVALENT_CLASS: &str = "http://www.w3.org/2002/07/owl#equivalentClass";
+pub const OWL_EQUIVALENT_PROPERTY: &str = "http://www.w3.org/2002/07/owl#equivalentProperty";
+pub const OWL_SAME_AS: &str = "http://www.w3.org/2002/07/owl#sameAs";
+pub const OWL_DISJOINT_WITH: &str = "http://www.w3.org/2002/07/owl#disjointWith";
+pub const OWL_COMPLEMENT_OF: &str = "http://www.w3.org/2002/07/owl#complementOf";
+pub const OWL_UNION_OF: &str = "http://www.w3.org/2002/07/owl#unionOf";
+pub const OWL_INTERSECTION_OF: &str = "http://www.w3.org/2002/07/owl#intersectionOf";
+pub const OWL_ONE_OF: &str = "http://www.w3.org/2002/07/owl#oneOf";
+pub const OWL_HAS_KEY: &str = "http://www.w3.org/2002/07/owl#hasKey";
+pub const OWL_INVERSE_OF: &str = "http://www.w3.org/2002/07/owl#inverseOf";
+pub const OWL_PROPERTY_CHAIN_AXIOM: &str = "http://www.w3.org/2002/07/owl#propertyChainAxiom";
+pub const OWL_PROPERTY_DISJOINT_WITH: &str = "http://www.w3.org/2002/07/owl#propertyDisjointWith";
+pub const OWL_AXIOM: &str = "http://www.w3.org/2002/07/owl#Axiom";
+pub const OWL_ANNOTATED_SOURCE: &str = "http://www.w3.org/2002/07/owl#annotatedSource";
+pub const OWL_ANNOTATED_PROPERTY: &str = "http://www.w3.org/2002/07/owl#annotatedProperty";
+pub const OWL_ANNOTATED_TARGET: &str = "http://www.w3.org/2002/07/owl#annotatedTarget";
+
+pub const SKOS_CONCEPT: &str = "http://www.w3.org/2004/02/skos/core#Concept";
+pub const SKOS_BROADER: &str = "http://www.w3.org/2004/02/skos/core#broader";
+pub const SKOS_NARROWER: &str = "http://www.w3.org/2004/02/skos/core#narrower";
+pub const SKOS_RELATED: &str = "http://www.w3.org/2004/02/skos/core#related";
+pub const SKOS_PREF_LABEL: &str = "http://www.w3.org/2004/02/skos/core#prefLabel";
+pub const SKOS_ALT_LABEL: &str = "http://www.w3.org/2004/02/skos/core#altLabel";
+pub const SKOS_HIDDEN_LABEL: &str = "http://www.w3.org/2004/02/skos/core#hiddenLabel";
+pub const SKOS_DEFINITION: &str = "http://www.w3.org/2004/02/skos/core#definition";
+pub const SKOS_NOTATION: &str = "http://www.w3.org/2004/02/skos/core#notation";
+pub const SKOS_CONCEPT_SCHEME: &str = "http://www.w3.org/2004/02/skos/core#ConceptScheme";
+pub const SKOS_IN_SCHEME: &str = "http://www.w3.org/2004/02/skos/core#inScheme";
+pub const SKOS_TOP_CONCEPT_OF: &str = "http://www.w3.org/2004/02/skos/core#topConceptOf";
+pub const SKOS_HAS_TOP_CONCEPT: &str = "http://www.w3.org/2004/02/skos/core#hasTopConcept";
+
+pub const SH_NODE_SHAPE: &str = "http://www.w3.org/ns/shacl#NodeShape";
+pub const SH_PROPERTY_SHAPE: &str = "http://www.w3.org/ns/shacl#PropertyShape";
+pub const SH_PROPERTY: &str = "http://www.w3.org/ns/shacl#property";
+pub const SH_PATH: &str = "http://www.w3.org/ns/shacl#path";
+pub const SH_MIN_COUNT: &str = "http://www.w3.org/ns/shacl#minCount";
+pub const SH_MAX_COUNT: &str = "http://www.w3.org/ns/shacl#maxCount";
+pub const SH_DATATYPE: &str = "http://www.w3.org/ns/shacl#datatype";
+pub const SH_CLASS: &str = "http://www.w3.org/ns/shacl#class";
+pub const SH_NODE_KIND: &str = "http://www.w3.org/ns/shacl#nodeKind";
+pub const SH_NODE: &str = "http://www.w3.org/ns/shacl#node";
+pub const SH_MESSAGE: &str = "http://www.w3.org/ns/shacl#message";
+pub const SH_ORDER: &str = "http://www.w3.org/ns/shacl#order";
+pub const SH_TARGET_CLASS: &str = "http://www.w3.org/ns/shacl#targetClass";
+pub const SH_TARGET_NODE: &str = "http://www.w3.org/ns/shacl#targetNode";
+pub const SH_TARGET_SUBJECTS_OF: &str = "http://www.w3.org/ns/shacl#targetSubjectsOf";
+pub const SH_TARGET_OBJECTS_OF: &str = "http://www.w3.org/ns/shacl#targetObjectsOf";
+
+pub const FOAF_MBOX: &str = "http://xmlns.com/foaf/0.1/mbox";
+
+pub const DCT_TITLE: &str = "http://purl.org/dc/terms/title";
+pub const DCT_DESCRIPTION: &str = "http://purl.org/dc/terms/description";
+
+pub const SCHEMA_ALTERNATE_NAME: &str = "http://schema.org/alternateName";
+pub const SCHEMA_ALTERNATE_NAME_HTTPS: &str = "https://schema.org/alternateName";
+
+/// Predicates giving the name a term is known by.
+pub const LABEL_PREDICATES: &[&str] = &[RDFS_LABEL, SKOS_PREF_LABEL];
+
+/// Predicates whose values are shown in place of a term's name, by
+/// priority, unless others are configured.
+pub const DISPLAY_LABEL_PREDICATES: &[&str] = &[RDFS_LABEL, SKOS_PREF_LABEL, DCT_TITLE];
+
+/// Predicates defining a term, by priority.
+pub const DEFINITION_PREDICATES: &[&str] = &[SKOS_DEFINITION, RDFS_COMMENT, DCT_DESCRIPTION];
+
+/// Predicates giving synonyms that search finds and shows.
+pub const ALT_LABEL_PREDICATES: &[&str] = &[
+    SKOS_ALT_LABEL,
+    SCHEMA_ALTERNATE_NAME,
+    SCHEMA_ALTERNATE_NAME_HTTPS,
+];
+
+/// Types marking a subject as a SHACL shape.
+pub const SHAPE_TYPES: &[&str] = &[SH_NODE_SHAPE, SH_PROPERTY_SHAPE];
+
+/// Predicates selecting the focus nodes of a shape.
+pub const SHAPE_TARGET_PREDICATES: &[&str] = &[
+    SH_TARGET_CLASS,
+    SH_TARGET_NODE,
+    SH_TARGET_SUBJECTS_OF,
+    SH_TARGET_OBJECTS_OF,
+];
+
+/// Predicates constraining the kind of value a property shape allows.
+pub const SHAPE_VALUE_TYPE_PREDICATES: &[&str] = &[SH_DATATYPE, SH_CLASS, SH_NODE_KIND, SH_NODE];
+
+/// Types marking a subject as a class.
+pub const CLASS_TYPES: &[&str] = &[OWL_CLASS, RDFS_CLASS];
+
+/// Types marking a subject as a property.
+pub const PROPERTY_TYPES: &[&str] = &[
+    RDF_PROPERTY,
+    OWL_OBJECT_PROPERTY,
+    OWL_DATATYPE_PROPERTY,
+    OWL_ANNOTATION_PROPERTY,
+    OWL_FUNCTIONAL_PROPERTY,
+    OWL_INVERSE_FUNCTIONAL_PROPERTY,
+    OWL_TRANSITIVE_PROPERTY,
+    OWL_SYMMETRIC_PROPERTY,
+];
+
+/// Predicates stating that two terms mean the same, shown as cross-links in
+/// both directions.
+pub const EQUIVALENCE_PREDICATES: &[&str] = &[OWL_EQUIVALENT_CLASS, OWL_EQUIVALENT_PROPERTY];
+
+/// Predicates that only describe a subject in its role as a class.
+pub const CLASS_PREDICATES: &[&str] = &[
+    RDFS_SUB_CLASS_OF,
+    OWL_EQUIVALENT_CLASS,
+    OWL_DISJOINT_WITH,
+    OWL_COMPLEMENT_OF,
+    OWL_UNION_OF,
+    OWL_INTERSECTION_OF,
+    OWL_ONE_OF,
+    OWL_HAS_KEY,
+];
+
+/// Predicates that only describe a subject in its role as a property.
+pub const PROPERTY_PREDICATES: &[&str] = &[
+    RDFS_SUB_PROPERTY_OF,
+    RDFS_DOMAIN,
+    RDFS_RANGE,
+    OWL_EQUIVALENT_PROPERTY,
+    OWL_INVERSE_OF,
+    OWL_PROPERTY_CHAIN_AXIOM,
+    OWL_PROPERTY_DISJOINT_WITH,
+];
@@ -0,0 +1,108 @@
+//! Which files below the input directory are converted.
+
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use log::warn;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::output::url_path;
+use crate::InputFormat;
+
+/// The traversal of an input directory, limited to the files matching the
+/// `--include` globs, if any, and not matching the `--exclude` globs.
+///
+/// Globs are matched against paths relative to the input directory, with
+/// `/` separators. A glob without a `/` matches a file or directory of that
+/// name at any depth, so `--exclude vendor` skips every `vendor` directory,
+/// and `*` does not match across directories.
+#[derive(Default, Clone)]
+pub struct InputWalk {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    /// Enters symbolically linked directories; links that lead back to a
+    /// directory above them are reported and skipped.
+    pub follow_symlinks: bool,
+    /// Depth of the deepest files converted, where 1 is the files directly
+    /// in the input directory.
+    pub max_depth: Option<usize>,
+    /// Does not enter directories on other file systems, such as mounts.
+    pub same_file_system: bool,
+}
+
+impl InputWalk {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(InputWalk {
+            include: glob_set(include)?,
+            exclude: glob_set(exclude)?,
+            ..Default::default()
+        })
+    }
+
+    /// Turtle, TriG and N-Triples files below `input_dir` that are selected,
+    /// in the order they are found. Excluded directories are not entered,
+    /// and those that cannot be read are skipped with a warning.
+    pub fn files(&self, input_dir: &str) -> Vec<PathBuf> {
+        let mut walk = WalkDir::new(input_dir)
+            .follow_links(self.follow_symlinks)
+            .same_file_system(self.same_file_system);
+        if let Some(max_depth) = self.max_depth {
+            walk = walk.max_depth(max_depth);
+        }
+        walk.into_iter()
+            .filter_entry(|entry| !self.is_excluded(entry, input_dir))
+            .filter_map(|entry| {
+                entry
+                    .map_err(|e| warn!("Skipped while reading the input directory: {}", e))
+                    .ok()
+            })
+            .map(|e| e.into_path())
+            .filter(|path| path.is_file() && InputFormat::from_path(path).is_some())
+            .filter(|path| self.is_included(path, input_dir))
+            .collect()
+    }
+
+    fn is_excluded(&self, entry: &DirEntry, input_dir: &str) -> bool {
+        // the input directory itself is never skipped
+        entry.depth() > 0
+            && self
+                .exclude
+                .as_ref()
+                .is_some_and(|exclude| exclude.is_match(relative(entry.path(), input_dir)))
+    }
+
+    fn is_included(&self, path: &Path, input_dir: &str) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.is_match(relative(path, input_dir)))
+    }
+}
+
+fn glob_set(globs: &[String]) -> Result<Option<GlobSet>, Box<dyn std::error::Error>> {
+    if globs.is_empty() {
+        return Ok(None);
+    }
+    let mut set = GlobSetBuilder::new();
+    for glob in globs {
+        set.add(parse_glob(glob)?);
+    }
+    Ok(Some(set.build()?))
+}
+
+fn parse_glob(glob: &str) -> Result<Glob, Box<dyn std::error::Error>> {
+    let pattern = glob.trim_start_matches("./").trim_end_matches('/');
+    let pattern = if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{}", pattern)
+    };
+    GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| format!("Invalid glob {:?}: {}", glob, e).into())
+}
+
+/// `path` relative to `input_dir`, with `/` separators.
+fn relative(path: &Path, input_dir: &str) -> String {
+    url_path(path.strip_prefix(input_dir).unwrap_or(path))
+}
@@ -0,0 +1,49 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use log::info;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// How long to wait after a change for further changes, so that saving
+/// several files at once causes a single rebuild.
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// Watches `dirs` recursively and calls `rebuild` after files in them
+/// change, until the watcher fails. Changes under `ignored`, the output
+/// directory when it sits inside a watched one, are left out.
+pub fn watch(
+    dirs: &[PathBuf],
+    ignored: &Path,
+    mut rebuild: impl FnMut(),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for dir in dirs {
+        // events carry the watched path, so canonical paths make them
+        // comparable with `ignored`
+        watcher.watch(&dir.canonicalize()?, RecursiveMode::Recursive)?;
+    }
+    let ignored = ignored
+        .canonicalize()
+        .unwrap_or_else(|_| ignored.to_path_buf());
+    info!("Watching {:?} for changes, press Ctrl-C to stop", dirs);
+
+    loop {
+        let event = rx.recv()??;
+        if !is_change(&event, &ignored) {
+            continue;
+        }
+        while rx.recv_timeout(SETTLE).is_ok() {}
+
+        info!("Change detected in {:?}, rebuilding", event.paths);
+        rebuild();
+    }
+}
+
+/// Whether `event` modified something outside `ignored`. Reads are left out,
+/// since rebuilding reads every input.
+fn is_change(event: &Event, ignored: &Path) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| !path.starts_with(ignored))
+}
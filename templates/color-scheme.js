@@ -0,0 +1,52 @@
+(function () {
+  "use strict";
+
+  // runs in the head, before the page is drawn, so that a stored choice
+  // never flashes the other scheme
+  var KEY = "ttl2html-color-scheme";
+  var root = document.documentElement;
+  var dark = window.matchMedia("(prefers-color-scheme: dark)");
+
+  function stored() {
+    try {
+      return localStorage.getItem(KEY);
+    } catch (e) {
+      return null;
+    }
+  }
+
+  function current() {
+    return root.getAttribute("data-color-scheme") || (dark.matches ? "dark" : "light");
+  }
+
+  var choice = stored();
+  if (choice === "light" || choice === "dark") {
+    root.setAttribute("data-color-scheme", choice);
+  }
+
+  document.addEventListener("DOMContentLoaded", function () {
+    var button = document.createElement("button");
+    button.type = "button";
+    button.className = "color-scheme-toggle";
+    button.textContent = "Dark mode";
+
+    function update() {
+      button.setAttribute("aria-pressed", String(current() === "dark"));
+    }
+
+    button.addEventListener("click", function () {
+      var next = current() === "dark" ? "light" : "dark";
+      root.setAttribute("data-color-scheme", next);
+      try {
+        localStorage.setItem(KEY, next);
+      } catch (e) {
+        // the choice only lasts for this page
+      }
+      update();
+    });
+    dark.addEventListener("change", update);
+
+    update();
+    document.body.appendChild(button);
+  });
+})();
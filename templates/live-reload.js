@@ -0,0 +1,5 @@
+// Reloads the page whenever the converter running with --watch and --serve
+// has rebuilt the site.
+new EventSource("/__live-reload").onmessage = function () {
+  location.reload();
+};
@@ -0,0 +1,122 @@
+(function () {
+  "use strict";
+
+  var MAX_RESULTS = 50;
+  var SNIPPET_CONTEXT = 60;
+  var input = document.getElementById("search-input");
+  var results = document.getElementById("search-results");
+  var status = document.getElementById("search-status");
+  var entries = [];
+
+  function contains(text, query) {
+    return text.toLowerCase().indexOf(query) !== -1;
+  }
+
+  // ranks: 0 for the name, 1 for a synonym, 2 for the descriptions
+  function find(entry, query, words) {
+    if (contains(entry.label, query)) {
+      return { entry: entry, rank: 0 };
+    }
+    var alt = (entry.alt_labels || []).find(function (label) {
+      return contains(label, query);
+    });
+    if (alt !== undefined) {
+      return { entry: entry, rank: 1, via: alt };
+    }
+    if ((entry.hidden_labels || []).some(function (label) {
+      return contains(label, query);
+    })) {
+      return { entry: entry, rank: 1, via: null };
+    }
+    // every word has to appear in the same description
+    var description = (entry.descriptions || []).find(function (text) {
+      return words.every(function (word) {
+        return contains(text, word);
+      });
+    });
+    if (description !== undefined) {
+      return { entry: entry, rank: 2, description: description, word: words[0] };
+    }
+    return null;
+  }
+
+  // the part of `text` around the first occurrence of `word`, with the
+  // occurrence marked
+  function snippet(text, word) {
+    var at = text.toLowerCase().indexOf(word);
+    var start = Math.max(0, at - SNIPPET_CONTEXT);
+    var end = Math.min(text.length, at + word.length + SNIPPET_CONTEXT);
+    var element = document.createElement("p");
+    element.className = "snippet";
+    var mark = document.createElement("mark");
+    mark.textContent = text.slice(at, at + word.length);
+    element.append(
+      (start > 0 ? "…" : "") + text.slice(start, at),
+      mark,
+      text.slice(at + word.length, end) + (end < text.length ? "…" : "")
+    );
+    return element;
+  }
+
+  function render(matches) {
+    results.textContent = "";
+    status.textContent = matches.length === 1 ? "1 result" : matches.length + " results";
+    matches.slice(0, MAX_RESULTS).forEach(function (match) {
+      var item = document.createElement("li");
+      var link = document.createElement("a");
+      link.href = match.entry.page + "#" + encodeURIComponent(match.entry.anchor);
+      link.textContent = match.entry.label;
+      item.appendChild(link);
+
+      if (match.entry.types && match.entry.types.length) {
+        var types = document.createElement("span");
+        types.className = "result-types";
+        types.textContent = " (" + match.entry.types.join(", ") + ")";
+        item.appendChild(types);
+      }
+      if (match.description !== undefined) {
+        item.appendChild(snippet(match.description, match.word));
+      }
+      if (match.via !== undefined) {
+        var note = document.createElement("span");
+        note.className = "matched-via";
+        note.textContent = match.via === null
+          ? " matched via alternative label"
+          : " matched via alternative label “" + match.via + "”";
+        item.appendChild(note);
+      }
+      results.appendChild(item);
+    });
+  }
+
+  function search() {
+    var query = input.value.trim().toLowerCase();
+    if (!query) {
+      results.textContent = "";
+      status.textContent = "";
+      return;
+    }
+
+    var words = query.split(/\s+/);
+    var matches = entries.map(function (entry) {
+      return find(entry, query, words);
+    }).filter(Boolean);
+    // name matches before synonyms, synonyms before descriptions
+    matches.sort(function (a, b) {
+      return a.rank - b.rank;
+    });
+    render(matches);
+  }
+
+  input.value = new URLSearchParams(window.location.search).get("q") || "";
+  input.addEventListener("input", search);
+
+  fetch("search-index.json")
+    .then(function (response) {
+      return response.json();
+    })
+    .then(function (data) {
+      entries = data;
+      search();
+    });
+})();
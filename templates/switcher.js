@@ -0,0 +1,178 @@
+(function () {
+  "use strict";
+
+  var MAX_RESULTS = 20;
+  var root = document.currentScript.getAttribute("data-root") || "";
+  var entries = null;
+  var palette, input, list;
+  var matches = [];
+  var selected = 0;
+
+  // Characters of the query must appear in order; matches at the start of
+  // the label or of a word, and runs of consecutive characters, score
+  // higher. Returns -1 when the text does not match.
+  function score(text, query) {
+    var lower = text.toLowerCase();
+    var total = 0;
+    var run = 0;
+    var position = -1;
+    for (var i = 0; i < query.length; i++) {
+      var found = lower.indexOf(query[i], position + 1);
+      if (found === -1) {
+        return -1;
+      }
+      run = found === position + 1 ? run + 1 : 0;
+      total += 1 + run * 2;
+      if (found === 0 || /[\s_\-:\/#.]/.test(lower[found - 1])) {
+        total += 3;
+      }
+      position = found;
+    }
+    return total - lower.length / 100;
+  }
+
+  function best(entry, query) {
+    var labels = [entry.label].concat(entry.alt_labels || []);
+    return labels.reduce(function (top, label) {
+      return Math.max(top, score(label, query));
+    }, -1);
+  }
+
+  function load() {
+    if (entries) {
+      return Promise.resolve(entries);
+    }
+    return fetch(root + "search-index.json")
+      .then(function (response) {
+        return response.json();
+      })
+      .then(function (data) {
+        entries = data;
+        return entries;
+      });
+  }
+
+  function render() {
+    list.textContent = "";
+    matches.forEach(function (entry, i) {
+      var item = document.createElement("li");
+      item.id = "switcher-option-" + i;
+      item.setAttribute("role", "option");
+      item.setAttribute("aria-selected", i === selected ? "true" : "false");
+      item.textContent = entry.label;
+      var page = document.createElement("span");
+      page.className = "switcher-page";
+      page.textContent = " " + entry.page;
+      item.appendChild(page);
+      item.addEventListener("mousedown", function (event) {
+        event.preventDefault();
+        go(entry);
+      });
+      list.appendChild(item);
+    });
+    if (matches.length) {
+      input.setAttribute("aria-activedescendant", "switcher-option-" + selected);
+    } else {
+      input.removeAttribute("aria-activedescendant");
+    }
+  }
+
+  function update() {
+    var query = input.value.trim().toLowerCase();
+    selected = 0;
+    if (!query || !entries) {
+      matches = [];
+    } else {
+      matches = entries
+        .map(function (entry) {
+          return { entry: entry, score: best(entry, query) };
+        })
+        .filter(function (match) {
+          return match.score >= 0;
+        })
+        .sort(function (a, b) {
+          return b.score - a.score;
+        })
+        .slice(0, MAX_RESULTS)
+        .map(function (match) {
+          return match.entry;
+        });
+    }
+    render();
+  }
+
+  function go(entry) {
+    window.location.href = root + entry.page + "#" + encodeURIComponent(entry.anchor);
+    close();
+  }
+
+  function build() {
+    palette = document.createElement("div");
+    palette.className = "switcher";
+    palette.setAttribute("role", "dialog");
+    palette.setAttribute("aria-label", "Jump to term");
+    palette.hidden = true;
+
+    input = document.createElement("input");
+    input.type = "text";
+    input.placeholder = "Jump to term…";
+    input.setAttribute("role", "combobox");
+    input.setAttribute("aria-label", "Jump to term");
+    input.setAttribute("aria-controls", "switcher-options");
+    input.setAttribute("aria-expanded", "true");
+
+    list = document.createElement("ul");
+    list.id = "switcher-options";
+    list.setAttribute("role", "listbox");
+
+    palette.appendChild(input);
+    palette.appendChild(list);
+    document.body.appendChild(palette);
+
+    input.addEventListener("input", update);
+    input.addEventListener("blur", close);
+    input.addEventListener("keydown", function (event) {
+      if (event.key === "ArrowDown" || event.key === "ArrowUp") {
+        event.preventDefault();
+        if (matches.length) {
+          var step = event.key === "ArrowDown" ? 1 : -1;
+          selected = (selected + step + matches.length) % matches.length;
+          render();
+        }
+      } else if (event.key === "Enter" && matches[selected]) {
+        event.preventDefault();
+        go(matches[selected]);
+      } else if (event.key === "Escape") {
+        close();
+      }
+    });
+  }
+
+  function open() {
+    if (!palette) {
+      build();
+    }
+    palette.hidden = false;
+    input.value = "";
+    input.focus();
+    load().then(update);
+  }
+
+  function close() {
+    if (palette) {
+      palette.hidden = true;
+    }
+  }
+
+  function editing(target) {
+    var tag = target.tagName;
+    return tag === "INPUT" || tag === "TEXTAREA" || tag === "SELECT" || target.isContentEditable;
+  }
+
+  document.addEventListener("keydown", function (event) {
+    if (event.key === "/" && !event.ctrlKey && !event.metaKey && !event.altKey && !editing(event.target)) {
+      event.preventDefault();
+      open();
+    }
+  });
+})();
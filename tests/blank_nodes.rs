@@ -0,0 +1,36 @@
+//! Blank nodes are labelled in order of appearance, whether the input names
+//! them or not, so that pages are the same on every run.
+
+mod common;
+
+use std::fs;
+
+use common::convert;
+
+const WRITTEN_AND_GENERATED: &str = r#"
+@prefix e: <http://example.org/> .
+_:anon0 e:p "user" .
+[] e:q "generated" .
+"#;
+
+#[test]
+fn written_labels_do_not_merge_with_generated_ones() {
+    let output = convert("blank-labels", &[("data.ttl", WRITTEN_AND_GENERATED)]);
+    let html = fs::read_to_string(output.join("data.html")).unwrap();
+
+    assert_eq!(html.matches(r#"<div id="_:b0">"#).count(), 1);
+    assert_eq!(html.matches(r#"<div id="_:b1">"#).count(), 1);
+    assert!(html.contains("user"));
+    assert!(html.contains("generated"));
+}
+
+#[test]
+fn generated_labels_are_stable() {
+    let first = convert("blank-stable-1", &[("data.ttl", WRITTEN_AND_GENERATED)]);
+    let second = convert("blank-stable-2", &[("data.ttl", WRITTEN_AND_GENERATED)]);
+
+    assert_eq!(
+        fs::read(first.join("data.html")).unwrap(),
+        fs::read(second.join("data.html")).unwrap()
+    );
+}
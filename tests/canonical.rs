@@ -0,0 +1,91 @@
+//! Canonical links added to the pages with `--base-url`.
+
+mod common;
+
+use std::fs;
+
+use common::{convert_with_args, run};
+
+const VOCAB: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+ex:Thing rdfs:label "Thing" .
+"#;
+
+const CANONICAL: &str = r#"<link rel="canonical" href="https://example.org/docs/vocab.html" />"#;
+
+#[test]
+fn pages_link_to_their_public_url() {
+    let output = convert_with_args(
+        "canonical",
+        &[("vocab.ttl", VOCAB)],
+        &["--base-url", "https://example.org/docs/"],
+    );
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert_eq!(html.matches(CANONICAL).count(), 1);
+}
+
+#[test]
+fn incremental_builds_keep_one_link() {
+    let args = ["--base-url", "https://example.org/docs/", "--incremental"];
+    let output = convert_with_args("canonical-incremental", &[("vocab.ttl", VOCAB)], &args);
+    let input = output.with_file_name("input");
+    assert!(run(&input, &output, &args));
+    assert!(run(&input, &output, &args));
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert_eq!(html.matches(r#"rel="canonical""#).count(), 1);
+    assert!(html.contains(CANONICAL));
+}
+
+#[test]
+fn print_views_link_to_their_page() {
+    let output = convert_with_args(
+        "canonical-print",
+        &[("vocab.ttl", VOCAB)],
+        &["--base-url", "https://example.org/docs/", "--print-view"],
+    );
+    let html = fs::read_to_string(output.join("vocab.print.html")).unwrap();
+
+    assert_eq!(html.matches(r#"rel="canonical""#).count(), 1);
+    assert!(html.contains(CANONICAL));
+}
+
+#[test]
+fn minified_pages_keep_the_link() {
+    let output = convert_with_args(
+        "canonical-minify",
+        &[("vocab.ttl", VOCAB)],
+        &["--base-url", "https://example.org/docs/", "--minify"],
+    );
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert_eq!(html.matches(CANONICAL).count(), 1);
+}
+
+#[test]
+fn robots_txt_names_the_sitemap() {
+    let output = convert_with_args(
+        "canonical-robots",
+        &[("vocab.ttl", VOCAB)],
+        &["--base-url", "https://example.org/docs/", "--sitemap"],
+    );
+    let robots = fs::read_to_string(output.join("robots.txt")).unwrap();
+
+    assert!(robots.contains("User-agent: *"));
+    assert!(robots.contains("Sitemap: https://example.org/docs/sitemap.xml"));
+}
+
+#[test]
+fn robots_txt_without_sitemap() {
+    let output = convert_with_args(
+        "canonical-robots-only",
+        &[("vocab.ttl", VOCAB)],
+        &["--base-url", "https://example.org/docs/"],
+    );
+    let robots = fs::read_to_string(output.join("robots.txt")).unwrap();
+
+    assert!(robots.contains("User-agent: *"));
+    assert!(!robots.contains("Sitemap:"));
+}
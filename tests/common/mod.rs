@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Writes `files` into a fresh input directory, converts it and returns the
+/// output directory.
+#[allow(dead_code)]
+pub fn convert(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    convert_with_args(name, files, &[])
+}
+
+/// Like `convert`, passing extra command line arguments.
+#[allow(dead_code)]
+pub fn convert_with_args(name: &str, files: &[(&str, &str)], args: &[&str]) -> PathBuf {
+    let (output, success) = try_convert(name, files, args);
+    assert!(success);
+    output
+}
+
+/// Like `convert_with_args`, returning the output directory and whether the
+/// conversion succeeded instead of requiring it to.
+#[allow(dead_code)]
+pub fn try_convert(name: &str, files: &[(&str, &str)], args: &[&str]) -> (PathBuf, bool) {
+    let root = std::env::temp_dir().join(format!("turtle2rdf-{}-{}", name, std::process::id()));
+    let input = root.join("input");
+    let output = root.join("output");
+    let _ = fs::remove_dir_all(&root);
+
+    for (path, contents) in files {
+        let path = input.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    let success = run(&input, &output, args);
+    (output, success)
+}
+
+/// Converts `input` into `output` as it is, without clearing either first,
+/// and returns whether the conversion succeeded.
+pub fn run(input: &Path, output: &Path, args: &[&str]) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_turtle2rdf"))
+        .arg("--input")
+        .arg(input)
+        .arg("--output")
+        .arg(output)
+        .args(args)
+        .status()
+        .unwrap()
+        .success()
+}
@@ -0,0 +1,84 @@
+//! Options read from a configuration file, and the command line options
+//! taking precedence over them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const VOCAB: &str = "<http://example.org/s> <http://example.org/p> \"o\" .";
+
+/// A fresh directory holding `docs/ttl2html.toml` with `config`, next to
+/// the inputs `docs/input/a.ttl` and `docs/input/b.ttl`.
+fn project(name: &str, config: &str) -> PathBuf {
+    let root =
+        std::env::temp_dir().join(format!("turtle2rdf-config-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let input = root.join("docs").join("input");
+    fs::create_dir_all(&input).unwrap();
+    fs::write(input.join("a.ttl"), VOCAB).unwrap();
+    fs::write(input.join("b.ttl"), VOCAB).unwrap();
+    fs::write(root.join("docs").join("ttl2html.toml"), config).unwrap();
+    root
+}
+
+/// Converts with `docs/ttl2html.toml` from the root of the project.
+fn run(root: &Path, args: &[&str]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_turtle2rdf"))
+        .current_dir(root)
+        .args(["--config", "docs/ttl2html.toml"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn relative_paths_are_resolved_against_the_file() {
+    let root = project("paths", "input = \"input\"\noutput = \"site\"\n");
+    run(&root, &[]);
+
+    assert!(root.join("docs/site/a.html").is_file());
+    assert!(!root.join("site").exists());
+}
+
+#[test]
+fn command_line_values_replace_single_values() {
+    let root = project("single", "input = \"input\"\noutput = \"site\"\n");
+    run(&root, &["--output", "elsewhere"]);
+
+    assert!(root.join("elsewhere/a.html").is_file());
+    assert!(!root.join("docs/site").exists());
+}
+
+#[test]
+fn command_line_turns_off_flags() {
+    let config = "input = \"input\"\noutput = \"site\"\nminify = true\n";
+    let root = project("flags", config);
+    run(&root, &[]);
+    let minified = fs::read_to_string(root.join("docs/site/a.html")).unwrap();
+    run(&root, &["--no-minify"]);
+    let html = fs::read_to_string(root.join("docs/site/a.html")).unwrap();
+
+    assert!(!minified.contains("  "));
+    assert!(html.contains("  "));
+}
+
+#[test]
+fn command_line_values_replace_lists() {
+    let config = "input = \"input\"\noutput = \"site\"\nexclude = [\"a.ttl\"]\n";
+    let root = project("lists", config);
+    run(&root, &["--exclude", "b.ttl"]);
+
+    assert!(root.join("docs/site/a.html").is_file());
+    assert!(!root.join("docs/site/b.html").exists());
+}
+
+#[test]
+fn file_lists_apply_without_command_line_values() {
+    let config = "input = \"input\"\noutput = \"site\"\nexclude = [\"a.ttl\"]\n";
+    let root = project("file-lists", config);
+    run(&root, &[]);
+
+    assert!(!root.join("docs/site/a.html").exists());
+    assert!(root.join("docs/site/b.html").is_file());
+}
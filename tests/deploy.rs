@@ -0,0 +1,83 @@
+//! Deploying to IPFS reports the CID of the site so that releases can be
+//! recorded.
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::thread;
+
+use common::convert;
+
+const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+/// Answers one `/api/v0/add` request the way an IPFS node does and returns
+/// the port it listens on.
+fn fake_ipfs_node() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            if line == "\r\n" {
+                break;
+            }
+        }
+        reader.read_exact(&mut vec![0; length]).unwrap();
+
+        let body = format!(
+            "{{\"Name\":\"output/vocab.html\",\"Hash\":\"bafkreifile\"}}\n\
+             {{\"Name\":\"output\",\"Hash\":\"{}\"}}\n",
+            CID
+        );
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    });
+    port
+}
+
+#[test]
+fn ipfs_cid_is_printed_and_recorded() {
+    let output = convert(
+        "deploy-ipfs",
+        &[(
+            "vocab.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+    );
+    let cid_file = output.with_file_name("CID");
+    let port = fake_ipfs_node();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_turtle2rdf"))
+        .arg("deploy")
+        .arg("--quiet")
+        .arg("--output")
+        .arg(&output)
+        .arg("--target")
+        .arg(format!("ipfs://127.0.0.1:{}", port))
+        .arg("--cid-file")
+        .arg(&cid_file)
+        .output()
+        .unwrap();
+
+    assert!(result.status.success());
+    assert_eq!(
+        String::from_utf8(result.stdout).unwrap(),
+        format!("{}\n", CID)
+    );
+    assert_eq!(fs::read_to_string(cid_file).unwrap(), format!("{}\n", CID));
+}
@@ -0,0 +1,118 @@
+//! The golden output check: its diffs and the assertion for tests.
+
+use std::fs;
+use std::path::PathBuf;
+
+use turtle2rdf::golden::{assert_matches_golden, render, unified_diff, update};
+use turtle2rdf::ConvertOptions;
+
+const VOCAB: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+ex:Thing rdfs:label "Thing" .
+"#;
+
+/// A fresh directory with `vocab.ttl` as its only input, and the golden
+/// tree rendered from it.
+fn golden_tree(name: &str) -> (String, PathBuf) {
+    let root =
+        std::env::temp_dir().join(format!("turtle2rdf-golden-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let input = root.join("input");
+    fs::create_dir_all(&input).unwrap();
+    fs::write(input.join("vocab.ttl"), VOCAB).unwrap();
+
+    let input = input.to_string_lossy().to_string();
+    let golden = root.join("golden");
+    let files = render(&input, &ConvertOptions::default()).unwrap();
+    update(&files, &golden).unwrap();
+    (input, golden)
+}
+
+#[test]
+fn diff_shows_changes_with_context() {
+    let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    let new = "a\nb\nc\nD\ne\nf\ng\nh\n";
+
+    assert_eq!(
+        unified_diff(old, new, "page.html"),
+        "--- a/page.html\n+++ b/page.html\n@@ -1,7 +1,7 @@\n a\n b\n c\n-d\n+D\n e\n f\n g\n"
+    );
+}
+
+#[test]
+fn diff_of_new_file_starts_at_line_zero() {
+    assert_eq!(
+        unified_diff("", "x\n", "page.html"),
+        "--- a/page.html\n+++ b/page.html\n@@ -0,0 +1,1 @@\n+x\n"
+    );
+}
+
+#[test]
+fn distant_changes_get_hunks_of_their_own() {
+    let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+    let new: String = (1..=20)
+        .map(|i| match i {
+            2 => "two\n".to_string(),
+            18 => "eighteen\n".to_string(),
+            i => format!("{}\n", i),
+        })
+        .collect();
+
+    let diff = unified_diff(&old, &new, "page.html");
+    assert_eq!(diff.matches("\n@@ ").count(), 2);
+    assert!(diff.contains("@@ -1,5 +1,5 @@\n"));
+    assert!(diff.contains("@@ -15,6 +15,6 @@\n"));
+}
+
+#[test]
+fn diff_is_shortest() {
+    // the example of Myers' paper, with an edit distance of 5
+    let old = "a\nb\nc\na\nb\nb\na\n";
+    let new = "c\nb\na\nb\na\nc\n";
+
+    let diff = unified_diff(old, new, "page.html");
+    let edits = diff
+        .lines()
+        .skip(2)
+        .filter(|line| line.starts_with('-') || line.starts_with('+'))
+        .count();
+    assert_eq!(edits, 5);
+}
+
+#[test]
+fn rewritten_files_are_not_diffed() {
+    let old: String = (0..3000).map(|i| format!("old {}\n", i)).collect();
+    let new: String = (0..3000).map(|i| format!("new {}\n", i)).collect();
+
+    assert_eq!(
+        unified_diff(&old, &new, "page.html"),
+        "More than 2000 lines changed\n"
+    );
+}
+
+#[test]
+fn unchanged_output_matches() {
+    let (input, golden) = golden_tree("matches");
+    assert_matches_golden(&input, &golden, &ConvertOptions::default());
+}
+
+#[test]
+#[should_panic(expected = "Changed: vocab.html")]
+fn changed_output_fails() {
+    let (input, golden) = golden_tree("changed");
+    let page = golden.join("vocab.html");
+    let html = fs::read_to_string(&page).unwrap();
+    fs::write(&page, html.replace("Thing", "Something")).unwrap();
+
+    assert_matches_golden(&input, &golden, &ConvertOptions::default());
+}
+
+#[test]
+#[should_panic(expected = "Missing from output: extra.html")]
+fn files_no_longer_written_fail() {
+    let (input, golden) = golden_tree("missing");
+    fs::write(golden.join("extra.html"), "").unwrap();
+
+    assert_matches_golden(&input, &golden, &ConvertOptions::default());
+}
@@ -0,0 +1,67 @@
+//! Grouping the statements of files past the memory budget on disk.
+
+use std::fs;
+
+use turtle2rdf::golden::render;
+use turtle2rdf::ConvertOptions;
+
+const VOCAB: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+ex:Dog a rdfs:Class ;
+    rdfs:label "Dog"@en, "Hund"@de ;
+    rdfs:comment "second", "first" ;
+    ex:born "2020-01-01"^^xsd:date ;
+    ex:legs 4 ;
+    ex:toys ( ex:Ball ex:Stick ) ;
+    ex:owner [ rdfs:label "Alice" ; ex:age 30 ] .
+ex:Cat rdfs:label "Cat" ; skos:broader ex:Animal .
+ex:Animal skos:prefLabel "Animal" .
+<< ex:Dog ex:chases ex:Cat >> ex:since "2021" .
+"#;
+
+fn input(name: &str) -> String {
+    let input = std::env::temp_dir().join(format!(
+        "turtle2rdf-group-memory-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&input);
+    fs::create_dir_all(&input).unwrap();
+    fs::write(input.join("vocab.ttl"), VOCAB).unwrap();
+    input.to_string_lossy().to_string()
+}
+
+/// Temporary files of runs this process left behind.
+fn spilled_runs() -> usize {
+    let prefix = format!("ttl2html-spill-{}-", std::process::id());
+    fs::read_dir(std::env::temp_dir())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .count()
+}
+
+#[test]
+fn pages_are_the_same_when_grouped_on_disk() {
+    let input = input("same");
+    let in_memory = render(&input, &ConvertOptions::default()).unwrap();
+    // every statement exceeds the budget, so each is a run of its own
+    let options = ConvertOptions {
+        group_memory: Some(1),
+        ..Default::default()
+    };
+    let on_disk = render(&input, &options).unwrap();
+
+    assert_eq!(
+        in_memory.keys().collect::<Vec<_>>(),
+        on_disk.keys().collect::<Vec<_>>()
+    );
+    for (path, contents) in &in_memory {
+        assert!(contents == &on_disk[path], "{:?} differs", path);
+    }
+    assert_eq!(spilled_runs(), 0);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::fs;
+
+use common::convert;
+
+#[test]
+fn object_iris_are_percent_encoded() {
+    let output = convert(
+        "object-iris",
+        &[(
+            "vocab.ttl",
+            "<http://example.org/s> <http://example.org/p> \
+             <http://example.org/a%20b>, <http://example.org/(x)>, <http://example.org/ü> .",
+        )],
+    );
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert!(html.contains(r#"href="http://example.org/a%20b""#));
+    assert!(html.contains(r#"href="http://example.org/%28x%29""#));
+    assert!(html.contains(r#"href="http://example.org/%C3%BC""#));
+}
+
+#[test]
+fn prefixed_objects_link_to_their_full_iri() {
+    let output = convert(
+        "prefixed-objects",
+        &[(
+            "vocab.ttl",
+            "@prefix ex: <http://example.org/> . ex:s ex:p ex:o .",
+        )],
+    );
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert!(html.contains(r#"<a href="http://example.org/o">o</a>"#));
+}
+
+#[test]
+fn index_paths_are_percent_encoded() {
+    let output = convert(
+        "index-paths",
+        &[(
+            "my dir/odd (1) ü.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+    );
+    let html = fs::read_to_string(output.join("index.html")).unwrap();
+
+    assert!(html.contains(r#"href="my%20dir/odd%20%281%29%20%C3%BC.html""#));
+    assert!(output.join("my dir/odd (1) ü.html").exists());
+}
@@ -0,0 +1,104 @@
+//! Data values come from third-party Turtle and must never be able to inject
+//! markup or scripts into the generated pages.
+
+mod common;
+
+use std::fs;
+
+use common::{convert, convert_with_args};
+
+const HOSTILE: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:term rdfs:label "<script>alert('label')</script>" ;
+    rdfs:comment "\"><img src=x onerror=alert(1)>" ;
+    ex:lang "<b>bold</b>"@en ;
+    ex:typed "</td></tr></table><script>alert(2)</script>"^^ex:markup ;
+    ex:link <javascript:alert(3)> ;
+    ex:data <data:text/html,%3Cscript%3Ealert(4)%3C/script%3E> .
+
+<http://example.org/%22%3E%3Cscript%3Ealert(5)%3C/script%3E> ex:p "x" .
+"#;
+
+fn hostile_page() -> String {
+    // readable IRIs decode the percent-escapes back into markup characters
+    let output = convert_with_args("hostile", &[("hostile.ttl", HOSTILE)], &["--readable-iris"]);
+    fs::read_to_string(output.join("hostile.html")).unwrap()
+}
+
+#[test]
+fn literals_are_escaped() {
+    let html = hostile_page();
+
+    assert!(!html.contains("<script>"));
+    assert!(!html.contains("<img"));
+    assert!(!html.contains("<b>bold"));
+    assert!(html.contains("&lt;script&gt;alert("));
+    assert!(html.contains("&lt;b&gt;bold&lt;/b&gt;"));
+}
+
+#[test]
+fn attribute_values_cannot_be_broken_out_of() {
+    let html = hostile_page();
+
+    assert!(!html.contains("\"><script"));
+    assert!(!html.contains("\"><img"));
+    assert!(html.contains("&quot;&gt;&lt;img src=x onerror=alert(1)&gt;"));
+    assert!(html.contains("&quot;&gt;&lt;script&gt;alert(5)"));
+}
+
+#[test]
+fn script_urls_are_not_linked() {
+    let html = hostile_page();
+
+    assert!(!html.contains("href=\"javascript:"));
+    assert!(!html.contains("href=\"data:"));
+    assert!(html.contains("<a href=\"#\">javascript:alert(3)</a>"));
+}
+
+#[test]
+fn file_names_are_escaped_in_the_index() {
+    let output = convert(
+        "hostile-name",
+        &[(
+            "<i>name<&i>.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+    );
+    let html = fs::read_to_string(output.join("index.html")).unwrap();
+
+    assert!(!html.contains("<i>"));
+    assert!(html.contains("&lt;i&gt;name&lt;&amp;i&gt;.ttl"));
+}
+
+#[test]
+fn source_view_is_escaped() {
+    let output = convert_with_args(
+        "hostile-source",
+        &[("hostile.ttl", HOSTILE)],
+        &["--source-view"],
+    );
+    let html = fs::read_to_string(output.join("hostile.source.html")).unwrap();
+
+    assert!(!html.contains("<script>"));
+    assert!(!html.contains("<img"));
+    assert!(html.contains("&lt;script&gt;alert(&#x27;label&#x27;)&lt;/script&gt;"));
+    assert!(html.contains("id=\"L7\""));
+}
+
+#[test]
+fn analytics_domain_is_escaped() {
+    let output = convert_with_args(
+        "hostile-plausible",
+        &[(
+            "vocab.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+        &["--plausible", "example.org\"><script>alert(6)</script>"],
+    );
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert!(!html.contains("<script>alert(6)"));
+    assert!(html.contains("data-domain=\"example.org&quot;&gt;&lt;script&gt;"));
+}
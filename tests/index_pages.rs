@@ -0,0 +1,48 @@
+//! Splitting the index into pages through the library.
+
+use std::fs;
+use std::path::Path;
+
+use turtle2rdf::golden::render;
+use turtle2rdf::ConvertOptions;
+
+/// A fresh input directory with `count` files.
+fn inputs(name: &str, count: usize) -> String {
+    let input =
+        std::env::temp_dir().join(format!("turtle2rdf-index-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&input);
+    fs::create_dir_all(&input).unwrap();
+    for i in 0..count {
+        fs::write(
+            input.join(format!("vocab{}.ttl", i)),
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )
+        .unwrap();
+    }
+    input.to_string_lossy().to_string()
+}
+
+#[test]
+fn index_is_split_past_the_page_size() {
+    let options = ConvertOptions {
+        index_page_size: Some(2),
+        ..Default::default()
+    };
+    let files = render(&inputs("split", 3), &options).unwrap();
+
+    assert!(files.contains_key(Path::new("index-1.html")));
+    assert!(files.contains_key(Path::new("index-2.html")));
+    assert!(!files.contains_key(Path::new("index-3.html")));
+}
+
+#[test]
+fn page_size_of_zero_lists_one_entry_per_page() {
+    let options = ConvertOptions {
+        index_page_size: Some(0),
+        ..Default::default()
+    };
+    let files = render(&inputs("zero", 2), &options).unwrap();
+
+    assert!(files.contains_key(Path::new("index-1.html")));
+    assert!(files.contains_key(Path::new("index-2.html")));
+}
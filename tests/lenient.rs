@@ -0,0 +1,38 @@
+//! Blank nodes are always shown, and `--lenient` reads generalized RDF
+//! instead of dropping it.
+
+mod common;
+
+use std::fs;
+
+use common::{convert, convert_with_args};
+
+const GENERALIZED: &str = r#"
+@prefix ex: <http://example.org/> .
+"literal subject" ex:p ex:o .
+ex:s _:predicate ex:o .
+ex:s ex:p _:shared .
+ex:t ex:p _:shared .
+"#;
+
+#[test]
+fn blank_node_objects_are_shown() {
+    let output = convert("blank-objects", &[("vocab.ttl", GENERALIZED)]);
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert!(html.contains("_:b0"));
+    assert!(!html.contains("literal subject"));
+}
+
+#[test]
+fn generalized_statements_are_shown_as_written() {
+    let output = convert_with_args("generalized", &[("vocab.ttl", GENERALIZED)], &["--lenient"]);
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert!(html.contains("&quot;literal subject&quot;"));
+    // blank nodes are relabelled in generalized statements too
+    assert!(html.contains("_:b0"));
+    assert!(html.contains("_:b1"));
+    assert!(!html.contains("_:predicate"));
+    assert!(!html.contains("urn:x-ttl2html"));
+}
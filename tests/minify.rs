@@ -0,0 +1,101 @@
+//! `--minify` collapses whitespace in the pages, except where it matters.
+
+mod common;
+
+use std::fs;
+
+use common::{convert_with_args, try_convert};
+
+const VOCAB: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+ex:Thing rdfs:label "Thing" ; rdfs:comment "Spaced     out" .
+"#;
+
+/// A page template with whitespace in every kind of place the minifier
+/// treats differently.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>Page</title>
+    <style>
+      p   { margin:   0; }
+    </style>
+  </head>
+  <body>
+    <p   class="note"   title="two   spaces">Some     text
+        on two lines</p>
+    <pre>
+  indented   line
+	tabbed
+    </pre>
+    <textarea>  keep   this  </textarea>
+    <script>
+      var  answer = 42;
+      // a comment ending the line
+    </script>
+    <!--   comment   kept   -->
+    <a href="a.html" data-x='1 > 0'   >link</a>
+  </body>
+</html>
+"#;
+
+/// Converts `VOCAB` with the page template replaced by `PAGE`.
+fn minified(name: &str) -> String {
+    let templates = std::env::temp_dir().join(format!(
+        "turtle2rdf-{}-templates-{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(templates.join("page.html"), PAGE).unwrap();
+
+    let output = convert_with_args(
+        name,
+        &[("vocab.ttl", VOCAB)],
+        &["--minify", "--templates", templates.to_str().unwrap()],
+    );
+    fs::read_to_string(output.join("vocab.html")).unwrap()
+}
+
+#[test]
+fn whitespace_runs_collapse() {
+    let html = minified("minify-text");
+
+    assert!(html.contains("<p class=\"note\" title=\"two   spaces\">Some text\non two lines</p>"));
+    assert!(html.contains("<html>\n<head>\n<title>Page</title>"));
+    assert!(html.contains("p { margin: 0; }"));
+}
+
+#[test]
+fn preformatted_text_and_scripts_are_kept() {
+    let html = minified("minify-verbatim");
+
+    assert!(html.contains("<pre>\n  indented   line\n\ttabbed\n    </pre>"));
+    assert!(html.contains("<textarea>  keep   this  </textarea>"));
+    assert!(html.contains(
+        "<script>\n      var  answer = 42;\n      // a comment ending the line\n    </script>"
+    ));
+}
+
+#[test]
+fn comments_and_attribute_values_are_kept() {
+    let html = minified("minify-attributes");
+
+    assert!(html.contains("<!--   comment   kept   -->"));
+    assert!(html.contains("<a href=\"a.html\" data-x='1 > 0' >link</a>"));
+}
+
+#[test]
+fn generated_pages_keep_their_text() {
+    let (output, success) = try_convert("minify-page", &[("vocab.ttl", VOCAB)], &["--minify"]);
+    assert!(success);
+    let html = fs::read_to_string(output.join("vocab.html")).unwrap();
+
+    assert!(
+        !html.contains("  "),
+        "runs of spaces outside preformatted text"
+    );
+    assert!(html.contains("Spaced out"));
+    assert!(html.contains("Thing"));
+}
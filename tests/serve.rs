@@ -0,0 +1,116 @@
+//! The preview server answers like a production host.
+
+mod common;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Child, Command};
+use std::thread;
+use std::time::Duration;
+
+use common::convert;
+
+/// Kills the server when the test ends, also when it fails.
+struct Serving(Child);
+
+impl Drop for Serving {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Serves `site` and returns the server with its address once it accepts
+/// connections.
+fn serve(site: &Path) -> (Serving, String) {
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+    let server = Serving(
+        Command::new(env!("CARGO_BIN_EXE_turtle2rdf"))
+            .arg("serve")
+            .arg("--output")
+            .arg(site)
+            .arg("--address")
+            .arg(&address)
+            .spawn()
+            .unwrap(),
+    );
+    for _ in 0..100 {
+        if TcpStream::connect(&address).is_ok() {
+            return (server, address);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("server did not start on {}", address);
+}
+
+/// Sends `request` and returns the whole response.
+fn request(address: &str, request: &str) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+/// The value of the header `name` in `response`.
+fn header<'a>(response: &'a str, name: &str) -> Option<&'a str> {
+    let prefix = format!("{}: ", name);
+    response
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| line.strip_prefix(prefix.as_str()))
+}
+
+fn site(name: &str) -> std::path::PathBuf {
+    convert(
+        name,
+        &[(
+            "vocab.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+    )
+}
+
+#[test]
+fn head_and_not_modified_describe_the_get() {
+    let (_server, address) = serve(&site("serve-headers"));
+
+    let get = request(&address, "GET /vocab.html HTTP/1.1\r\n\r\n");
+    let length = header(&get, "Content-Length").unwrap();
+    let etag = header(&get, "ETag").unwrap();
+    let head = request(&address, "HEAD /vocab.html HTTP/1.1\r\n\r\n");
+    let not_modified = request(
+        &address,
+        &format!(
+            "GET /vocab.html HTTP/1.1\r\nIf-None-Match: {}\r\n\r\n",
+            etag
+        ),
+    );
+
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+    assert!(head.ends_with("\r\n\r\n"));
+    assert_eq!(header(&head, "Content-Length"), Some(length));
+    assert_eq!(header(&head, "ETag"), Some(etag));
+    assert!(not_modified.starts_with("HTTP/1.1 304 Not Modified"));
+    assert!(not_modified.ends_with("\r\n\r\n"));
+    assert_eq!(header(&not_modified, "Content-Length"), Some(length));
+    assert_eq!(header(&not_modified, "ETag"), Some(etag));
+}
+
+#[test]
+fn slow_clients_do_not_hold_up_others() {
+    let (_server, address) = serve(&site("serve-slow"));
+
+    // connected, but never sends its request
+    let _slow = TcpStream::connect(&address).unwrap();
+    let response = request(&address, "GET /vocab.html HTTP/1.1\r\n\r\n");
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+}
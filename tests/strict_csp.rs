@@ -0,0 +1,62 @@
+//! `--strict-csp` must reject markup a strict policy blocks, and only that.
+
+mod common;
+
+use std::fs;
+
+use common::try_convert;
+
+#[test]
+fn text_mentioning_inline_markup_is_allowed() {
+    let (output, success) = try_convert(
+        "csp-text",
+        &[(
+            "vocab.ttl",
+            r#"<http://example.org/s> <http://example.org/p> "Set the style= attribute, not <style> or onclick= <script>" ."#,
+        )],
+        &["--strict-csp"],
+    );
+
+    assert!(success);
+    assert!(fs::read_to_string(output.join("vocab.html"))
+        .unwrap()
+        .contains("Set the style= attribute"));
+}
+
+#[test]
+fn inline_scripts_are_rejected() {
+    let snippet = std::env::temp_dir().join(format!("turtle2rdf-csp-{}.html", std::process::id()));
+    fs::write(&snippet, "<script>track()</script>").unwrap();
+
+    let (_, success) = try_convert(
+        "csp-script",
+        &[(
+            "vocab.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+        &[
+            "--strict-csp",
+            "--analytics-snippet",
+            snippet.to_str().unwrap(),
+        ],
+    );
+
+    assert!(!success);
+}
+
+#[test]
+fn policy_allows_plausible_analytics() {
+    let (output, success) = try_convert(
+        "csp-plausible",
+        &[(
+            "vocab.ttl",
+            "<http://example.org/s> <http://example.org/p> \"o\" .",
+        )],
+        &["--strict-csp", "--plausible", "example.org"],
+    );
+    let policy = fs::read_to_string(output.join("content-security-policy.txt")).unwrap();
+
+    assert!(success);
+    assert!(policy.contains("script-src https://plausible.io;"));
+    assert!(policy.contains("connect-src https://plausible.io\n"));
+}